@@ -2,5 +2,11 @@
 -- Enable UUID extension
 CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
 
--- Channel type enum
-CREATE TYPE channel_type AS ENUM ('casual', 'debate');
+-- Channel type enum (Postgres has no CREATE TYPE IF NOT EXISTS)
+DO $$
+BEGIN
+    CREATE TYPE channel_type AS ENUM ('casual', 'debate');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
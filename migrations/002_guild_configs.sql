@@ -1,5 +1,5 @@
 -- Guild configuration table
-CREATE TABLE guild_configs (
+CREATE TABLE IF NOT EXISTS guild_configs (
     guild_id BIGINT PRIMARY KEY,
     jtc_casual_channel_id BIGINT,
     jtc_debate_channel_id BIGINT,
@@ -12,5 +12,5 @@ CREATE TABLE guild_configs (
 );
 
 -- Index for quick lookups
-CREATE INDEX idx_guild_configs_jtc_channels
+CREATE INDEX IF NOT EXISTS idx_guild_configs_jtc_channels
     ON guild_configs(jtc_casual_channel_id, jtc_debate_channel_id);
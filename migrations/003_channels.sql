@@ -1,5 +1,5 @@
 -- Active voice channels table
-CREATE TABLE active_voice_channels (
+CREATE TABLE IF NOT EXISTS active_voice_channels (
     channel_id BIGINT PRIMARY KEY,
     guild_id BIGINT NOT NULL REFERENCES guild_configs(guild_id) ON DELETE CASCADE,
     owner_id BIGINT NOT NULL,
@@ -10,5 +10,5 @@ CREATE TABLE active_voice_channels (
 );
 
 -- Indexes for common queries
-CREATE INDEX idx_active_vc_guild ON active_voice_channels(guild_id);
-CREATE INDEX idx_active_vc_owner ON active_voice_channels(owner_id);
+CREATE INDEX IF NOT EXISTS idx_active_vc_guild ON active_voice_channels(guild_id);
+CREATE INDEX IF NOT EXISTS idx_active_vc_owner ON active_voice_channels(owner_id);
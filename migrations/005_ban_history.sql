@@ -1,5 +1,5 @@
 -- VC ban history table
-CREATE TABLE vc_ban_history (
+CREATE TABLE IF NOT EXISTS vc_ban_history (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     guild_id BIGINT NOT NULL,
     channel_id BIGINT NOT NULL,
@@ -10,7 +10,7 @@ CREATE TABLE vc_ban_history (
 );
 
 -- Indexes for lookups
-CREATE INDEX idx_vc_ban_guild ON vc_ban_history(guild_id);
-CREATE INDEX idx_vc_ban_channel ON vc_ban_history(channel_id);
-CREATE INDEX idx_vc_ban_user ON vc_ban_history(banned_user_id);
-CREATE INDEX idx_vc_ban_channel_user ON vc_ban_history(channel_id, banned_user_id);
+CREATE INDEX IF NOT EXISTS idx_vc_ban_guild ON vc_ban_history(guild_id);
+CREATE INDEX IF NOT EXISTS idx_vc_ban_channel ON vc_ban_history(channel_id);
+CREATE INDEX IF NOT EXISTS idx_vc_ban_user ON vc_ban_history(banned_user_id);
+CREATE INDEX IF NOT EXISTS idx_vc_ban_channel_user ON vc_ban_history(channel_id, banned_user_id);
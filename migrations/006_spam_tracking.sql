@@ -1,5 +1,5 @@
 -- Spam user status table
-CREATE TABLE spam_user_status (
+CREATE TABLE IF NOT EXISTS spam_user_status (
     guild_id BIGINT NOT NULL,
     user_id BIGINT NOT NULL,
     current_timeout_level INT NOT NULL DEFAULT 0,
@@ -11,4 +11,4 @@ CREATE TABLE spam_user_status (
 );
 
 -- Index for cleanup queries
-CREATE INDEX idx_spam_status_last_infraction ON spam_user_status(last_infraction_at);
+CREATE INDEX IF NOT EXISTS idx_spam_status_last_infraction ON spam_user_status(last_infraction_at);
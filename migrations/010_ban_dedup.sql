@@ -0,0 +1,10 @@
+-- Deduplicate VC bans so a user can only be banned once per channel
+-- Keep the earliest ban record for each channel/user pair
+DELETE FROM vc_ban_history a
+    USING vc_ban_history b
+    WHERE a.channel_id = b.channel_id
+    AND a.banned_user_id = b.banned_user_id
+    AND (a.banned_at > b.banned_at OR (a.banned_at = b.banned_at AND a.id > b.id));
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vc_ban_channel_user_unique
+    ON vc_ban_history(channel_id, banned_user_id);
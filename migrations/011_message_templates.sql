@@ -0,0 +1,5 @@
+-- Per-guild overrides for user-facing message wording
+-- NULL means the built-in default is used
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS spam_prompt_template TEXT;
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS deadline_dm_template TEXT;
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS naming_prompt_template TEXT;
@@ -0,0 +1,19 @@
+-- Channel where ban appeals are posted for moderator review (NULL = appeals disabled)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS appeal_channel_id BIGINT;
+
+-- Ban appeals submitted by users, one per ban
+CREATE TABLE IF NOT EXISTS ban_appeals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ban_id UUID NOT NULL UNIQUE REFERENCES vc_ban_history(id) ON DELETE CASCADE,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    reason VARCHAR(1000) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- 'pending', 'approved' or 'denied'
+    resolved_by_user_id BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+-- Index for per-user rate limiting
+CREATE INDEX IF NOT EXISTS idx_ban_appeals_user ON ban_appeals(user_id, created_at);
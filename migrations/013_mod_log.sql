@@ -0,0 +1,5 @@
+-- Channel for moderator notifications (NULL = disabled)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS mod_log_channel_id BIGINT;
+
+-- Whether to notify the mod log when a globally-muted user joins voice
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS announce_global_mute_joins BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Track whether a channel mute also server-deafened the user
+ALTER TABLE mute_history ADD COLUMN IF NOT EXISTS is_deafened BOOLEAN NOT NULL DEFAULT FALSE;
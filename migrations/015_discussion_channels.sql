@@ -0,0 +1,5 @@
+-- Whether each new voice room also gets a paired text channel
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS create_discussion_channels BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- The text channel paired with a voice room (NULL = none)
+ALTER TABLE active_voice_channels ADD COLUMN IF NOT EXISTS text_channel_id BIGINT;
@@ -0,0 +1,3 @@
+-- What happens to a room when its owner hops to another managed channel
+-- 'release' (transfer/delete right away), 'hold' (keep it for a grace period), 'prompt' (hold and DM the owner)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS owner_hop_policy TEXT NOT NULL DEFAULT 'release';
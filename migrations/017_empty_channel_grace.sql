@@ -0,0 +1,2 @@
+-- Seconds an emptied room is kept before deletion, in case someone comes back (0 = delete right away)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS empty_channel_grace_seconds INTEGER NOT NULL DEFAULT 0;
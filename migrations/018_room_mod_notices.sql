@@ -0,0 +1,2 @@
+-- Post a short-lived notice in a room's text chat when its owner mutes or bans someone
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS announce_room_mod_actions BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Joins within the raid window that auto-lock a room (NULL = disabled)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS raid_lock_joins INTEGER;
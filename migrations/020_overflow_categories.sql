@@ -0,0 +1,3 @@
+-- Extra categories used once the main one for a channel type hits Discord's 50-channel limit
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS overflow_casual_category_ids BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS overflow_debate_category_ids BIGINT[] NOT NULL DEFAULT '{}';
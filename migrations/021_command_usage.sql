@@ -0,0 +1,9 @@
+-- One row per slash command invocation, for bot-wide usage analytics
+CREATE TABLE IF NOT EXISTS command_usage (
+    id BIGSERIAL PRIMARY KEY,
+    command_name TEXT NOT NULL,
+    guild_id BIGINT,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_usage_used_at ON command_usage(used_at);
@@ -0,0 +1,2 @@
+-- Per-guild cap on room name length, at most Discord's 100-character limit
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS max_name_length INTEGER NOT NULL DEFAULT 100;
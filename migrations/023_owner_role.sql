@@ -0,0 +1,2 @@
+-- Role given to members while they own a managed room (NULL = none)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS owner_role_id BIGINT;
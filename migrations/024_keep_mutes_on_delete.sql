@@ -0,0 +1,2 @@
+-- Whether owner mutes (and the server mute) outlive the room they were given in
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS keep_mutes_on_channel_delete BOOLEAN NOT NULL DEFAULT FALSE;
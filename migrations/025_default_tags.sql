@@ -0,0 +1,3 @@
+-- Tags applied to new rooms whose owner has no saved preference
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS default_casual_tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS default_debate_tags TEXT[] NOT NULL DEFAULT '{}';
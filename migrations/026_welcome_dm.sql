@@ -0,0 +1,11 @@
+-- Optional one-time DM explaining room controls to first-time owners
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS send_welcome_dm BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS welcome_dm_template TEXT;
+
+-- Users who already got the welcome DM in a guild
+CREATE TABLE IF NOT EXISTS seen_welcome (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, user_id)
+);
@@ -0,0 +1,23 @@
+-- Join-to-create channels as rows, so a guild can eventually have more than one per type
+-- The legacy jtc_*_channel_id columns stay authoritative and are mirrored here by the setters
+CREATE TABLE IF NOT EXISTS jtc_channels (
+    channel_id BIGINT PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guild_configs(guild_id) ON DELETE CASCADE,
+    channel_type channel_type NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_jtc_channels_guild ON jtc_channels(guild_id, channel_type);
+
+-- Backfill from the legacy columns (safe to run again)
+INSERT INTO jtc_channels (channel_id, guild_id, channel_type)
+SELECT jtc_casual_channel_id, guild_id, 'casual'::channel_type
+FROM guild_configs
+WHERE jtc_casual_channel_id IS NOT NULL
+ON CONFLICT (channel_id) DO NOTHING;
+
+INSERT INTO jtc_channels (channel_id, guild_id, channel_type)
+SELECT jtc_debate_channel_id, guild_id, 'debate'::channel_type
+FROM guild_configs
+WHERE jtc_debate_channel_id IS NOT NULL
+ON CONFLICT (channel_id) DO NOTHING;
@@ -0,0 +1,3 @@
+-- Where the spam ban/ignore prompt goes
+-- 'channel' (the room's text chat), 'owner_dm' (DM the room owner), 'mod_channel' (the mod log channel)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS spam_prompt_target TEXT NOT NULL DEFAULT 'channel';
@@ -0,0 +1,3 @@
+-- Emoji tags a guild offers on top of the built-in tags, stored as unicode emoji or <:name:id> mentions
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS emoji_casual_tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS emoji_debate_tags TEXT[] NOT NULL DEFAULT '{}';
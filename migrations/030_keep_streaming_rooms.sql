@@ -0,0 +1,2 @@
+-- Whether a room is kept while its owner is streaming or on camera elsewhere, instead of deleted when it empties
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS keep_rooms_while_streaming BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Whether the owner locked the room to new joins, re-applied to the channel on startup
+ALTER TABLE active_voice_channels ADD COLUMN IF NOT EXISTS locked BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,9 @@
+-- Role overwrites every new room in a guild starts with, on top of the owner's own
+-- allow and deny are Discord permission bitfields
+CREATE TABLE IF NOT EXISTS room_permission_templates (
+    guild_id BIGINT NOT NULL REFERENCES guild_configs(guild_id) ON DELETE CASCADE,
+    role_id BIGINT NOT NULL,
+    allow BIGINT NOT NULL DEFAULT 0,
+    deny BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild_id, role_id)
+);
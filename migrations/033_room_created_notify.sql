@@ -0,0 +1,2 @@
+-- Optional channel that gets a short "come join" post whenever a new room is created
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS channel_created_notify_channel_id BIGINT;
@@ -0,0 +1,6 @@
+-- Optional end time for a mute, after which a background task lifts it
+ALTER TABLE mute_history ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_mute_history_expires_at
+    ON mute_history (expires_at)
+    WHERE unmuted_at IS NULL AND expires_at IS NOT NULL;
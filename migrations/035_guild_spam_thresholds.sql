@@ -0,0 +1,4 @@
+-- Per-guild spam detector sensitivity, NULL means the bot-wide default from the environment
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS spam_prompt_threshold INTEGER;
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS spam_timeout_threshold INTEGER;
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS spam_window_seconds INTEGER;
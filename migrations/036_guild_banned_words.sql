@@ -0,0 +1,9 @@
+-- Words a guild adds to the room name filter, on top of the built-in list
+-- Stored lowercased, the form they are matched in
+CREATE TABLE IF NOT EXISTS guild_banned_words (
+    guild_id BIGINT NOT NULL REFERENCES guild_configs(guild_id) ON DELETE CASCADE,
+    word TEXT NOT NULL,
+    added_by BIGINT NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, word)
+);
@@ -0,0 +1,2 @@
+-- Whether a new room only counts as configured once it has at least one tag, not just a name
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS require_tags BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Seconds a user waits after their room is deleted before they can make another (0 = no wait)
+ALTER TABLE guild_configs ADD COLUMN IF NOT EXISTS recreate_cooldown_seconds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Appeals outlive the ban they were made against: unbanning (including an approved appeal)
+-- and channel purges delete the ban row, which used to take the appeal with it.
+-- The appeal already records its own guild, channel and user.
+ALTER TABLE ban_appeals DROP CONSTRAINT IF EXISTS ban_appeals_ban_id_fkey;
+ALTER TABLE ban_appeals ALTER COLUMN ban_id DROP NOT NULL;
+ALTER TABLE ban_appeals
+    ADD CONSTRAINT ban_appeals_ban_id_fkey
+    FOREIGN KEY (ban_id) REFERENCES vc_ban_history(id) ON DELETE SET NULL;
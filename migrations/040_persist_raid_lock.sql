@@ -0,0 +1,2 @@
+-- Whether the room was auto-locked after a join flood, so the lock can be lifted after a restart
+ALTER TABLE active_voice_channels ADD COLUMN IF NOT EXISTS raid_locked BOOLEAN NOT NULL DEFAULT FALSE;
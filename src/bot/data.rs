@@ -1,11 +1,40 @@
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::hash::Hash;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
 use sqlx::PgPool;
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
 
+use crate::bot::error::Error;
+use crate::bot::logging::LogControl;
+use crate::bot::shutdown::BackgroundTasks;
 use crate::config::Settings;
+use crate::constants::timeouts::{
+    JTC_FLOW_TIMEOUT_SECONDS, LIMIT_RATE_WINDOW_SECONDS, MAX_RECREATE_COOLDOWN_SECONDS,
+    MAX_SPAM_WINDOW_SECONDS, OWNER_CACHE_RECONCILE_INTERVAL_SECONDS, RAID_LOCK_WINDOW_SECONDS,
+    STATE_PRUNE_INTERVAL_SECONDS,
+};
+use crate::db::config_cache::GuildConfigCache;
+use crate::db::models::GuildConfig;
+use crate::db::queries::voice_channel;
+use crate::services::component_expiry::ExpiringComponents;
+use crate::services::jtc::channel_deleter::MAX_CONCURRENT_DELETIONS;
+use crate::services::jtc::queue::{JtcQueue, QueueStats};
+use crate::services::jtc::transfer_guard::TransferHistory;
+use crate::services::jtc::voice_presence::RecentJoins;
+use crate::services::moderation::mute_service::PendingUnmutes;
+use crate::services::rate_limit::RateLimitMonitor;
 use crate::services::spam::detector::ActivityTracker;
+use crate::utils::profanity::GuildFilters;
+
+/// How long a pending bot unmute marker is trusted
+const PENDING_UNMUTE_TTL: Duration = Duration::from_secs(5);
 
 /// Shared data available to all commands and handlers
 pub struct Data {
@@ -17,26 +46,103 @@ pub struct Data {
     pub activity_tracker: ActivityTracker,
     /// Users currently in the JTC flow (user_id -> timestamp)
     pub jtc_pending: DashMap<u64, std::time::Instant>,
+    /// The JTC creation queue every new room goes through
+    pub jtc_queue: JtcQueue,
+    /// Depth and latency counters for the JTC creation queue
+    pub jtc_queue_stats: QueueStats,
+    /// Slots for Discord channel deletions; creations are already one at a time through the queue
+    pub channel_deletions: Semaphore,
+    /// Discord rate limit hits, used to slow down non-critical work under pressure
+    pub rate_limits: RateLimitMonitor,
+    /// Delayed unmutes for muted users who left a channel, cancelled if they rejoin a muted one
+    pub pending_leave_unmutes: PendingUnmutes,
     /// Track pending bot unmutes: (guild_id, user_id) -> timestamp
     /// Used to distinguish bot unmutes from manual owner unmutes
     pub pending_bot_unmutes: DashMap<(u64, u64), std::time::Instant>,
     /// Rate limit tracker for user limit changes: (user_id, channel_id) -> timestamps
     pub limit_change_timestamps: DashMap<(u64, u64), Vec<std::time::Instant>>,
+    /// Recent owner transfers per room, rate limited to stop rooms being passed around
+    pub transfer_history: TransferHistory,
+    /// When each user's room was last deleted, for the guild's recreate cooldown
+    /// ((guild_id, user_id) -> deleted at)
+    pub room_deletions: DashMap<(u64, u64), std::time::Instant>,
+    /// Joins into rooms with no leave seen yet, to double-check an empty-looking voice state cache
+    pub recent_joins: RecentJoins,
+    /// Rooms held for an owner who hopped to another channel (channel_id -> held since)
+    pub held_rooms: DashMap<u64, std::time::Instant>,
+    /// Emptied rooms waiting out the guild's grace before deletion
+    /// (channel_id -> scheduled at, task), aborted if someone joins
+    pub pending_deletions: DashMap<u64, (std::time::Instant, AbortHandle)>,
+    /// Rooms Discord refused to delete (channel_id -> attempts so far, retry scheduled)
+    pub deletion_retries: DashMap<u64, (u32, bool)>,
+    /// Rooms auto-locked after a join flood (channel_id -> locked at)
+    pub raid_locked: DashMap<u64, std::time::Instant>,
+    /// Confirmation prompts awaiting a response (nonce -> created at)
+    pub pending_confirmations: DashMap<String, std::time::Instant>,
+    /// Interactive messages to clean up if nobody uses them in time
+    pub expiring_components: ExpiringComponents,
+    /// Guild configs read on every voice join, dropped when /setup changes them
+    pub guild_configs: GuildConfigCache,
+    /// Compiled room-name filters per guild, rebuilt when the guild's banned words change
+    pub word_filters: GuildFilters,
+    /// Runtime control over the tracing filter
+    pub log_control: LogControl,
+    /// When this process started, for uptime reporting
+    pub started_at: std::time::Instant,
+    /// Long-running loops started on Ready, stopped on shutdown
+    pub background_tasks: BackgroundTasks,
+    /// The context from the first Ready, kept for work outside event handlers (shutdown cleanup)
+    ready: OnceLock<serenity::Context>,
 }
 
 impl Data {
-    pub fn new(pool: PgPool, settings: Settings) -> Self {
+    pub fn new(pool: PgPool, settings: Settings, log_control: LogControl) -> Self {
         Self {
             pool,
             settings,
             channel_owners: DashMap::new(),
             activity_tracker: ActivityTracker::new(),
             jtc_pending: DashMap::new(),
+            jtc_queue: JtcQueue::default(),
+            jtc_queue_stats: QueueStats::default(),
+            channel_deletions: Semaphore::new(MAX_CONCURRENT_DELETIONS),
+            rate_limits: RateLimitMonitor::default(),
+            pending_leave_unmutes: PendingUnmutes::default(),
             pending_bot_unmutes: DashMap::new(),
             limit_change_timestamps: DashMap::new(),
+            transfer_history: TransferHistory::default(),
+            room_deletions: DashMap::new(),
+            recent_joins: RecentJoins::default(),
+            held_rooms: DashMap::new(),
+            pending_deletions: DashMap::new(),
+            deletion_retries: DashMap::new(),
+            raid_locked: DashMap::new(),
+            pending_confirmations: DashMap::new(),
+            expiring_components: ExpiringComponents::default(),
+            guild_configs: GuildConfigCache::default(),
+            word_filters: GuildFilters::default(),
+            log_control,
+            started_at: std::time::Instant::now(),
+            background_tasks: BackgroundTasks::default(),
+            ready: OnceLock::new(),
         }
     }
 
+    /// Record that the bot reached Ready and ran its startup tasks
+    pub fn mark_ready(&self, ctx: &serenity::Context) {
+        let _ = self.ready.set(ctx.clone());
+    }
+
+    /// Whether the bot has been ready at least once this process
+    pub fn has_been_ready(&self) -> bool {
+        self.ready.get().is_some()
+    }
+
+    /// The context from the first Ready, if the bot got that far
+    pub fn ready_context(&self) -> Option<&serenity::Context> {
+        self.ready.get()
+    }
+
     /// Mark that the bot is about to unmute a user (to ignore the voice state event)
     pub fn mark_pending_unmute(&self, guild_id: u64, user_id: u64) {
         self.pending_bot_unmutes
@@ -47,13 +153,69 @@ impl Data {
     /// Returns true if it was a bot unmute (should be ignored), false if manual
     pub fn consume_pending_unmute(&self, guild_id: u64, user_id: u64) -> bool {
         if let Some((_, timestamp)) = self.pending_bot_unmutes.remove(&(guild_id, user_id)) {
-            // Only valid if within last 5 seconds
-            timestamp.elapsed().as_secs() < 5
+            timestamp.elapsed() < PENDING_UNMUTE_TTL
         } else {
             false
         }
     }
 
+    /// Drop transient, time-based state that can't be trusted across a gateway reconnect
+    /// Durable state (channel owner cache, spam activity) is left untouched
+    pub fn clear_transient_state(&self) {
+        self.jtc_pending.clear();
+        self.pending_bot_unmutes.clear();
+    }
+
+    /// Drop in-memory state about a user who left a guild
+    pub fn forget_member(&self, guild_id: u64, user_id: u64) {
+        self.pending_leave_unmutes.cancel(guild_id, user_id);
+        self.pending_bot_unmutes.remove(&(guild_id, user_id));
+        self.jtc_pending.remove(&user_id);
+        self.limit_change_timestamps.retain(|(user, _), _| *user != user_id);
+    }
+
+    /// The guild's config, cached for the voice join path
+    pub async fn guild_config(&self, guild_id: u64) -> Result<Option<GuildConfig>, Error> {
+        Ok(self.guild_configs.get(&self.pool, guild_id).await?)
+    }
+
+    /// Time left before a user whose room was deleted may make another, if any
+    pub fn recreate_cooldown_remaining(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        cooldown: Duration,
+    ) -> Option<Duration> {
+        let deleted_at = *self.room_deletions.get(&(guild_id, user_id))?;
+        cooldown_remaining(deleted_at, Instant::now(), cooldown)
+    }
+
+    /// How long join/leave activity is kept, long enough for the widest window any guild can set
+    pub fn spam_activity_window(&self) -> u64 {
+        self.settings.spam_window_seconds.max(MAX_SPAM_WINDOW_SECONDS)
+    }
+
+    /// Drop entries from the in-memory rate-limit and cooldown maps once they can no longer matter
+    /// Most of these are only trimmed when the same user acts again, so idle users would linger
+    /// Returns how many entries were removed
+    pub fn prune_expired_state(&self) -> usize {
+        let activity_age =
+            Duration::from_secs(self.spam_activity_window().max(RAID_LOCK_WINDOW_SECONDS));
+
+        prune_timestamp_lists(
+            &self.limit_change_timestamps,
+            Duration::from_secs(LIMIT_RATE_WINDOW_SECONDS),
+        ) + prune_timestamps(&self.pending_bot_unmutes, PENDING_UNMUTE_TTL)
+            + prune_timestamps(&self.jtc_pending, Duration::from_secs(JTC_FLOW_TIMEOUT_SECONDS))
+            + self.transfer_history.prune()
+            + prune_timestamps(
+                &self.room_deletions,
+                Duration::from_secs(MAX_RECREATE_COOLDOWN_SECONDS as u64),
+            )
+            + self.recent_joins.prune()
+            + self.activity_tracker.prune(activity_age)
+    }
+
     /// Check if a user is the owner of a channel
     pub fn is_channel_owner(&self, channel_id: u64, user_id: u64) -> bool {
         self.channel_owners
@@ -70,12 +232,46 @@ impl Data {
     /// Remove a channel from the cache
     pub fn remove_channel(&self, channel_id: u64) {
         self.channel_owners.remove(&channel_id);
+        self.held_rooms.remove(&channel_id);
+        self.raid_locked.remove(&channel_id);
+        self.transfer_history.forget(channel_id);
+        self.recent_joins.forget(channel_id);
+        self.deletion_retries.remove(&channel_id);
+        if let Some((_, (_, task))) = self.pending_deletions.remove(&channel_id) {
+            task.abort();
+        }
     }
 
     /// Get the owner of a channel from cache
     pub fn get_channel_owner(&self, channel_id: u64) -> Option<u64> {
         self.channel_owners.get(&channel_id).map(|r| *r)
     }
+
+    /// Get the owner of a managed channel, failing unless it's `user_id`
+    #[allow(clippy::result_large_err)]
+    pub fn require_owner(&self, channel_id: u64, user_id: u64) -> Result<u64, Error> {
+        check_owner(&self.channel_owners, channel_id, user_id)
+    }
+
+    /// The room a user owns in this guild
+    /// Looked up by guild, so a room they own in another server never matches
+    pub async fn owned_channel(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<serenity::ChannelId, Error> {
+        let vc = voice_channel::get_by_owner(&self.pool, guild_id as i64, user_id as i64)
+            .await?
+            .ok_or_else(|| {
+                Error::custom(
+                    "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
+                )
+            })?;
+
+        let channel_id = vc.channel_id as u64;
+        self.set_channel_owner(channel_id, user_id);
+        Ok(serenity::ChannelId::new(channel_id))
+    }
 }
 
 impl fmt::Debug for Data {
@@ -83,8 +279,189 @@ impl fmt::Debug for Data {
         f.debug_struct("Data")
             .field("channel_owners_count", &self.channel_owners.len())
             .field("jtc_pending_count", &self.jtc_pending.len())
+            .field("jtc_queue_depth", &self.jtc_queue_stats.depth())
+            .field("rate_limited", &self.rate_limits.is_throttled())
+            .field("expiring_components", &self.expiring_components.len())
             .finish_non_exhaustive()
     }
 }
 
-pub type Context<'a> = poise::Context<'a, Arc<Data>, crate::bot::error::Error>;
+/// Periodically prune expired in-memory state (see `Data::prune_expired_state`)
+pub fn spawn_state_pruner(data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(STATE_PRUNE_INTERVAL_SECONDS));
+        // The first tick fires immediately and there's nothing to prune yet
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let removed = data.prune_expired_state();
+            if removed > 0 {
+                debug!("Pruned {} expired in-memory entries", removed);
+            }
+        }
+    })
+    .abort_handle()
+}
+
+/// Periodically resync the channel owner cache with the database
+/// Event-driven updates keep it current; this heals drift from missed events or external deletes
+pub fn spawn_owner_cache_reconciler(data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(OWNER_CACHE_RECONCILE_INTERVAL_SECONDS));
+        // The cache was just loaded from the database on startup
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match reconcile_owner_cache(&data).await {
+                Ok(0) => {}
+                Ok(fixed) => info!("Owner cache reconciliation fixed {} entries", fixed),
+                Err(e) => warn!("Failed to reconcile owner cache: {:?}", e),
+            }
+        }
+    })
+    .abort_handle()
+}
+
+/// Bring `channel_owners` in line with the database, returning how many entries changed
+async fn reconcile_owner_cache(data: &Data) -> Result<usize, sqlx::Error> {
+    let records: HashMap<u64, u64> = voice_channel::list_all(&data.pool)
+        .await?
+        .into_iter()
+        .map(|vc| (vc.channel_id as u64, vc.owner_id as u64))
+        .collect();
+
+    let mut fixed = 0;
+    for channel_id in owner_cache_drift(&data.channel_owners, &records) {
+        // Rooms created, transferred or deleted since the list was read are already up to date,
+        // so re-read each drifted row rather than trusting the snapshot
+        match voice_channel::get(&data.pool, channel_id as i64).await? {
+            Some(vc) => {
+                let owner_id = vc.owner_id as u64;
+                if data.get_channel_owner(channel_id) != Some(owner_id) {
+                    debug!("Owner cache for {} corrected to {}", channel_id, owner_id);
+                    data.set_channel_owner(channel_id, owner_id);
+                    fixed += 1;
+                }
+            }
+            None => {
+                if data.channel_owners.remove(&channel_id).is_some() {
+                    debug!("Dropped channel {} from owner cache, no longer in database", channel_id);
+                    fixed += 1;
+                }
+            }
+        }
+    }
+
+    Ok(fixed)
+}
+
+/// Channels that are cached with the wrong owner, cached without a record, or recorded but not cached
+fn owner_cache_drift(owners: &DashMap<u64, u64>, records: &HashMap<u64, u64>) -> Vec<u64> {
+    let mut drift: Vec<u64> = owners
+        .iter()
+        .filter(|entry| records.get(entry.key()) != Some(entry.value()))
+        .map(|entry| *entry.key())
+        .collect();
+    drift.extend(records.keys().filter(|channel_id| !owners.contains_key(channel_id)));
+    drift
+}
+
+#[allow(clippy::result_large_err)]
+fn check_owner(owners: &DashMap<u64, u64>, channel_id: u64, user_id: u64) -> Result<u64, Error> {
+    match owners.get(&channel_id).map(|owner| *owner) {
+        Some(owner_id) if owner_id == user_id => Ok(owner_id),
+        Some(_) => Err(Error::NotChannelOwner),
+        None => Err(Error::ChannelNotManaged),
+    }
+}
+
+/// What's left of `cooldown` started at `since`, or None once it has run out
+fn cooldown_remaining(since: Instant, now: Instant, cooldown: Duration) -> Option<Duration> {
+    cooldown
+        .checked_sub(now.saturating_duration_since(since))
+        .filter(|left| !left.is_zero())
+}
+
+/// Remove entries stamped more than `max_age` ago
+fn prune_timestamps<K: Eq + Hash>(map: &DashMap<K, Instant>, max_age: Duration) -> usize {
+    let before = map.len();
+    map.retain(|_, at| at.elapsed() < max_age);
+    before - map.len()
+}
+
+/// Trim timestamps older than `max_age` and remove entries left with none
+fn prune_timestamp_lists<K: Eq + Hash>(map: &DashMap<K, Vec<Instant>>, max_age: Duration) -> usize {
+    let before = map.len();
+    map.retain(|_, stamps| {
+        stamps.retain(|at| at.elapsed() < max_age);
+        !stamps.is_empty()
+    });
+    before - map.len()
+}
+
+pub type Context<'a> = poise::Context<'a, Arc<Data>, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_check_distinguishes_unmanaged_channels() {
+        let owners = DashMap::new();
+        owners.insert(10, 1);
+
+        assert_eq!(check_owner(&owners, 10, 1).unwrap(), 1);
+        assert!(matches!(check_owner(&owners, 10, 2), Err(Error::NotChannelOwner)));
+        assert!(matches!(check_owner(&owners, 11, 1), Err(Error::ChannelNotManaged)));
+    }
+
+    #[test]
+    fn stale_entries_are_pruned() {
+        let max_age = Duration::from_secs(10);
+        let old = Instant::now() - max_age * 2;
+
+        let stamps: DashMap<u64, Instant> = DashMap::new();
+        stamps.insert(1, old);
+        stamps.insert(2, Instant::now());
+        assert_eq!(prune_timestamps(&stamps, max_age), 1);
+        assert!(stamps.contains_key(&2) && !stamps.contains_key(&1));
+
+        let lists: DashMap<(u64, u64), Vec<Instant>> = DashMap::new();
+        lists.insert((1, 1), vec![old, old]);
+        lists.insert((2, 2), vec![old, Instant::now()]);
+        assert_eq!(prune_timestamp_lists(&lists, max_age), 1);
+        assert_eq!(lists.get(&(2, 2)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cooldown_runs_out() {
+        let start = Instant::now();
+        let cooldown = Duration::from_secs(60);
+
+        assert_eq!(cooldown_remaining(start, start, cooldown), Some(cooldown));
+        assert_eq!(
+            cooldown_remaining(start, start + Duration::from_secs(45), cooldown),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(cooldown_remaining(start, start + cooldown, cooldown), None);
+        assert_eq!(cooldown_remaining(start, start, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn owner_cache_drift_finds_stale_wrong_and_missing_entries() {
+        let owners = DashMap::new();
+        owners.insert(10, 1); // matches
+        owners.insert(11, 1); // owner changed
+        owners.insert(12, 1); // deleted externally
+
+        let records = HashMap::from([(10, 1), (11, 2), (13, 3)]);
+
+        let mut drift = owner_cache_drift(&owners, &records);
+        drift.sort();
+        assert_eq!(drift, vec![11, 12, 13]);
+    }
+}
@@ -26,6 +26,12 @@ pub enum Error {
     #[error("JTC not configured for this guild")]
     JtcNotConfigured,
 
+    #[error("This channel is not managed by the bot")]
+    ChannelNotManaged,
+
+    #[error("Only the channel owner can do that")]
+    NotChannelOwner,
+
     #[error("{0}")]
     Custom(String),
 }
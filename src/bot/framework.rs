@@ -1,36 +1,126 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use poise::serenity_prelude::{self as serenity, GatewayIntents, GuildId};
+use poise::serenity_prelude::{self as serenity, GatewayIntents, GuildId, UserId};
 use sqlx::PgPool;
 use tracing::{error, info, warn};
 
-use crate::bot::data::Data;
+use crate::bot::data::{spawn_owner_cache_reconciler, spawn_state_pruner, Context, Data};
 use crate::bot::error::Error;
+use crate::bot::logging::LogControl;
+use crate::bot::shutdown;
 use crate::commands;
 use crate::config::Settings;
+use crate::constants::embeds;
+use crate::constants::timeouts::{STARTUP_BACKOFF_BASE_SECONDS, STARTUP_BACKOFF_MAX_SECONDS};
+use crate::db::queries::command_usage;
 use crate::handlers::event_handler::event_handler;
+use crate::services::{backup, component_expiry};
 use crate::services::jtc::{channel_deleter, deadline_tracker, queue};
+use crate::services::moderation::mute_service;
+use crate::services::spam::timeout_decay;
 
-pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
-    let data = Arc::new(Data::new(pool, settings.clone()));
+pub async fn run(settings: Settings, pool: PgPool, log_control: LogControl) -> Result<(), Error> {
+    let data = Arc::new(Data::new(pool, settings.clone(), log_control));
 
-    let framework = poise::Framework::builder()
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MEMBERS
+        | GatewayIntents::GUILD_VOICE_STATES
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT;
+
+    // Retry the initial connection with backoff; once the bot has been ready,
+    // reconnecting is serenity's job and a failure here is final
+    let max_attempts = settings.startup_max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let mut client = serenity::ClientBuilder::new(&settings.discord_token, intents)
+            .framework(build_framework(data.clone()))
+            .await
+            .map_err(Error::Serenity)?;
+
+        info!("Starting Discord client (attempt {}/{})...", attempt, max_attempts);
+        let shard_manager = client.shard_manager.clone();
+        let err = tokio::select! {
+            result = client.start() => match result {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            },
+            _ = shutdown::signal() => {
+                shutdown::shutdown(&data, &shard_manager).await;
+                return Ok(());
+            }
+        };
+
+        if data.has_been_ready() || is_fatal_startup_error(&err) || attempt >= max_attempts {
+            return Err(Error::Serenity(err));
+        }
+
+        let delay = startup_backoff(attempt);
+        warn!(
+            "Discord client failed to start: {:?}. Retrying in {}s ({}/{})",
+            err,
+            delay.as_secs(),
+            attempt,
+            max_attempts
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown::signal() => {
+                data.pool.close().await;
+                return Ok(());
+            }
+        }
+        attempt += 1;
+    }
+}
+
+/// Build the poise framework (rebuilt for each startup attempt, since the client consumes it)
+fn build_framework(data: Arc<Data>) -> poise::Framework<Arc<Data>, Error> {
+    poise::Framework::builder()
         .options(poise::FrameworkOptions {
             commands: vec![
                 commands::setup::setup(),
                 commands::stats::stats(),
+                commands::guildstats::guildstats(),
                 commands::owner::mute::mute(),
                 commands::owner::mute::unmute(),
+                commands::owner::mute::unmuteall(),
                 commands::owner::ban::vcban(),
                 commands::owner::ban::vcunban(),
+                commands::owner::ban::banlist(),
                 commands::owner::transfer::transfer(),
+                commands::owner::claim::claim(),
                 commands::owner::rename::rename(),
                 commands::owner::retag::retag(),
+                commands::owner::limit::limit(),
+                commands::owner::lock::lock(),
+                commands::owner::lock::unlock(),
+                commands::owner::kick::kick(),
+                commands::admin::admin(),
+                commands::diagnostics::diagnostics(),
+                commands::preferences::preferences(),
             ],
             prefix_options: poise::PrefixFrameworkOptions {
                 prefix: None, // Disable prefix commands - only use slash commands
                 ..Default::default()
             },
+            // Operators from OWNER_IDS; the application's owners are added when the client starts
+            owners: data.settings.owner_ids.iter().map(|&id| UserId::new(id)).collect(),
+            command_check: Some(|ctx| Box::pin(commands::checks::guild_setup_check(ctx))),
+            post_command: |ctx| {
+                Box::pin(async move {
+                    // Usage analytics are best-effort and never fail the command
+                    let guild_id = ctx.guild_id().map(|g| g.get() as i64);
+                    if let Err(e) =
+                        command_usage::record(&ctx.data().pool, &ctx.command().qualified_name, guild_id)
+                            .await
+                    {
+                        warn!("Failed to record usage of /{}: {:?}", ctx.command().qualified_name, e);
+                    }
+                    forget_changed_config(ctx);
+                })
+            },
             event_handler: |ctx, event, framework, data| {
                 Box::pin(event_handler(ctx, event, framework, data))
             },
@@ -39,11 +129,36 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
                     match error {
                         poise::FrameworkError::Command { error, ctx, .. } => {
                             error!("Command error: {:?}", error);
+                            // A failed /setup may still have saved part of its change
+                            forget_changed_config(ctx);
                             let _ = ctx.say(format!("Error: {}", error)).await;
                         }
                         poise::FrameworkError::ArgumentParse { error, ctx, .. } => {
                             let _ = ctx.say(format!("Invalid argument: {}", error)).await;
                         }
+                        poise::FrameworkError::CommandCheckFailed {
+                            error: Some(Error::JtcNotConfigured),
+                            ctx,
+                            ..
+                        } => {
+                            let embed = embeds::warning_embed()
+                                .title("Not Set Up Yet")
+                                .description(
+                                    "This server hasn't set up Jarvis yet. \
+                                    An admin needs to run `/setup` to configure the voice channels first.",
+                                );
+                            let _ = ctx
+                                .send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                                .await;
+                        }
+                        poise::FrameworkError::NotAnOwner { ctx, .. } => {
+                            let embed = embeds::error_embed()
+                                .title("Operators Only")
+                                .description("Only bot operators can use this command.");
+                            let _ = ctx
+                                .send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                                .await;
+                        }
                         poise::FrameworkError::UnknownCommand { .. } => {
                             // Ignore unknown command errors - bot only uses slash commands
                             // This happens when users ping the bot or use prefix commands
@@ -59,6 +174,7 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
         .setup(|ctx, ready, framework| {
             Box::pin(async move {
                 info!("Bot connected as {}", ready.user.name);
+                data.mark_ready(ctx);
 
                 // Clean up orphaned voice channels from previous session
                 match channel_deleter::cleanup_orphaned_channels(&ctx.http, &data.pool, &data).await {
@@ -76,20 +192,22 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
                 }
 
                 // Start background task for VC naming deadline enforcement
-                deadline_tracker::spawn_deadline_checker(ctx.http.clone(), data.clone());
+                let tasks = &data.background_tasks;
+                tasks.track(deadline_tracker::spawn_deadline_checker(ctx.clone(), data.clone()));
                 info!("Started VC naming deadline checker");
 
-                // Create JTC queue for processing users waiting in JTC channels
+                // Create the JTC queue; every room, live join or startup scan, is created through it
                 let (queue_tx, queue_rx) = tokio::sync::mpsc::unbounded_channel();
-                
+                data.jtc_queue.attach(queue_tx);
+
                 // Check for users in JTC channels and queue them
                 let ctx_clone = ctx.clone();
                 let data_clone = data.clone();
-                tokio::spawn(async move {
+                let startup_scan = tokio::spawn(async move {
                     // Wait a few seconds for cache to populate
                     tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
                     
-                    match queue::check_jtc_channels_on_startup(&ctx_clone, &data_clone, &queue_tx).await {
+                    match queue::check_jtc_channels_on_startup(&ctx_clone, &data_clone).await {
                         Ok(count) => {
                             if count > 0 {
                                 info!("Queued {} users waiting in JTC channels", count);
@@ -100,15 +218,17 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
                         }
                     }
                 });
+                tasks.track(startup_scan.abort_handle());
 
                 // Start queue processor
-                queue::spawn_queue_processor(ctx.clone(), data.clone(), queue_rx);
+                tasks.track(queue::spawn_queue_processor(ctx.clone(), data.clone(), queue_rx));
+                tasks.track(queue::spawn_health_logger(data.clone()));
                 info!("Started JTC queue processor");
 
                 // Check for empty channels after a short delay (to allow cache to populate)
                 let ctx_clone = ctx.clone();
                 let data_clone = data.clone();
-                tokio::spawn(async move {
+                let startup_cleanup = tokio::spawn(async move {
                     // Wait 5 seconds for cache to populate
                     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                     match channel_deleter::cleanup_empty_channels(&ctx_clone, &data_clone).await {
@@ -121,7 +241,61 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
                             warn!("Failed to cleanup empty channels on startup: {:?}", e);
                         }
                     }
+
+                    // Fix channels recorded as owned by a bot or a user who left the guild
+                    match channel_deleter::reconcile_invalid_owners(&ctx_clone, &data_clone).await {
+                        Ok((transferred, deleted)) => {
+                            if transferred > 0 || deleted > 0 {
+                                info!(
+                                    "Owner reconciliation: transferred {} channels, deleted {} channels",
+                                    transferred, deleted
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to reconcile channel owners on startup: {:?}", e);
+                        }
+                    }
+
+                    // Reconcile mute records with who is actually where after downtime
+                    match mute_service::reconcile_active_mutes(&ctx_clone, &data_clone).await {
+                        Ok((reapplied, cleared)) => {
+                            if reapplied > 0 || cleared > 0 {
+                                info!(
+                                    "Mute reconciliation: re-applied {} mutes, cleared {} stale mutes",
+                                    reapplied, cleared
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to reconcile mutes on startup: {:?}", e);
+                        }
+                    }
                 });
+                tasks.track(startup_cleanup.abort_handle());
+
+                // Periodically drop DB rows for channels deleted while we weren't looking
+                tasks.track(channel_deleter::spawn_orphan_reaper(ctx.clone(), data.clone()));
+
+                // Keep rate-limit and cooldown maps from growing with idle users
+                tasks.track(spawn_state_pruner(data.clone()));
+
+                // Heal owner cache drift from missed events
+                tasks.track(spawn_owner_cache_reconciler(data.clone()));
+
+                // Clear out tag menus, spam alerts and hold notices nobody answered
+                tasks.track(component_expiry::spawn_component_sweeper(ctx.http.clone(), data.clone()));
+
+                // Lift timed mutes once they run out
+                tasks.track(mute_service::spawn_mute_expiry(ctx.clone(), data.clone()));
+
+                // Give users who've behaved for a while a fresh timeout level
+                tasks.track(timeout_decay::spawn_timeout_decay(data.clone()));
+
+                // Scheduled table backups (no-op unless BACKUP_S3_* is configured)
+                if let Some(task) = backup::spawn_backup_task(data.clone()) {
+                    tasks.track(task);
+                }
 
                 // Register commands globally or per-guild based on GUILD_ID env var
                 match data.settings.guild_id {
@@ -236,19 +410,82 @@ pub async fn run(settings: Settings, pool: PgPool) -> Result<(), Error> {
                 Ok(data)
             })
         })
-        .build();
+        .build()
+}
 
-    let intents = GatewayIntents::GUILDS
-        | GatewayIntents::GUILD_MEMBERS
-        | GatewayIntents::GUILD_VOICE_STATES
-        | GatewayIntents::GUILD_MESSAGES
-        | GatewayIntents::MESSAGE_CONTENT;
+/// Errors retrying can't fix: bad token or intents the bot isn't allowed to use
+fn is_fatal_startup_error(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Gateway(e) => matches!(
+            e,
+            serenity::GatewayError::InvalidAuthentication
+                | serenity::GatewayError::NoAuthentication
+                | serenity::GatewayError::InvalidGatewayIntents
+                | serenity::GatewayError::DisallowedGatewayIntents
+                | serenity::GatewayError::InvalidShardData
+                | serenity::GatewayError::OverloadedShard
+        ),
+        serenity::Error::Http(e) => matches!(
+            e.status_code(),
+            Some(status) if status == serenity::StatusCode::UNAUTHORIZED
+                || status == serenity::StatusCode::FORBIDDEN
+        ),
+        _ => false,
+    }
+}
+
+/// Drop the cached guild config after a command that may have changed it
+fn forget_changed_config(ctx: Context<'_>) {
+    if let Some(guild_id) = ctx.guild_id() {
+        if changes_guild_config(&ctx.command().qualified_name) {
+            ctx.data().guild_configs.invalidate(guild_id.get());
+        }
+    }
+}
+
+/// Whether a command can change the guild's config: everything under /setup
+fn changes_guild_config(qualified_name: &str) -> bool {
+    qualified_name.split(' ').next() == Some("setup")
+}
+
+/// Delay before startup attempt `attempt + 1`: doubles from the base, capped
+fn startup_backoff(attempt: u32) -> Duration {
+    let secs = STARTUP_BACKOFF_BASE_SECONDS
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(16))
+        .min(STARTUP_BACKOFF_MAX_SECONDS);
+    Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn startup_backoff_doubles_then_caps() {
+        assert_eq!(startup_backoff(1), Duration::from_secs(STARTUP_BACKOFF_BASE_SECONDS));
+        assert_eq!(startup_backoff(2), Duration::from_secs(STARTUP_BACKOFF_BASE_SECONDS * 2));
+        assert_eq!(startup_backoff(3), Duration::from_secs(STARTUP_BACKOFF_BASE_SECONDS * 4));
+        assert_eq!(startup_backoff(50), Duration::from_secs(STARTUP_BACKOFF_MAX_SECONDS));
+    }
 
-    let mut client = serenity::ClientBuilder::new(&settings.discord_token, intents)
-        .framework(framework)
-        .await
-        .map_err(Error::Serenity)?;
+    #[test]
+    fn setup_commands_drop_the_cached_config() {
+        assert!(changes_guild_config("setup"));
+        assert!(changes_guild_config("setup jtc-channel"));
+        assert!(!changes_guild_config("setupx"));
+        assert!(!changes_guild_config("admin inventory"));
+    }
 
-    info!("Starting Discord client...");
-    client.start().await.map_err(Error::Serenity)
+    #[test]
+    fn auth_failures_are_fatal() {
+        assert!(is_fatal_startup_error(&serenity::Error::Gateway(
+            serenity::GatewayError::InvalidAuthentication
+        )));
+        assert!(is_fatal_startup_error(&serenity::Error::Gateway(
+            serenity::GatewayError::DisallowedGatewayIntents
+        )));
+        assert!(!is_fatal_startup_error(&serenity::Error::Gateway(
+            serenity::GatewayError::ReconnectFailure
+        )));
+    }
 }
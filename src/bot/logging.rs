@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Handle to the reloadable `EnvFilter` installed in `main.rs`
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Runtime control over per-module log levels
+/// Keeps the startup `RUST_LOG` directives as a base and layers overrides on top
+pub struct LogControl {
+    handle: FilterHandle,
+    base: String,
+    overrides: Mutex<BTreeMap<String, String>>,
+}
+
+impl LogControl {
+    pub fn new(handle: FilterHandle, base: String) -> Self {
+        Self {
+            handle,
+            base,
+            overrides: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Set the log level for a target (e.g. `jarvis::handlers::voice_state`)
+    /// Returns the full filter string now in effect
+    pub fn set_level(&self, target: &str, level: &str) -> Result<String, String> {
+        if target.is_empty()
+            || !target
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        {
+            return Err(format!("'{}' is not a valid log target", target));
+        }
+
+        let mut overrides = self.overrides.lock().unwrap_or_else(|e| e.into_inner());
+        overrides.insert(target.to_string(), level.to_string());
+
+        let directives = self.directives(&overrides);
+        let filter = EnvFilter::try_new(&directives)
+            .map_err(|e| format!("Invalid log filter: {}", e))?;
+
+        self.handle
+            .reload(filter)
+            .map_err(|e| format!("Failed to reload log filter: {}", e))?;
+
+        Ok(directives)
+    }
+
+    /// Build the combined directive string (base first so overrides win)
+    fn directives(&self, overrides: &BTreeMap<String, String>) -> String {
+        let mut parts: Vec<String> = self
+            .base
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        parts.extend(
+            overrides
+                .iter()
+                .map(|(target, level)| format!("{}={}", target, level)),
+        );
+
+        parts.join(",")
+    }
+}
@@ -1,3 +1,5 @@
 pub mod data;
 pub mod error;
 pub mod framework;
+pub mod logging;
+pub mod shutdown;
@@ -0,0 +1,108 @@
+//! Graceful shutdown on Ctrl+C or SIGTERM
+//! Empty rooms are deleted on the way out instead of waiting for the next startup, background
+//! loops are stopped, the gateway connection is closed and the database pool drained.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use poise::serenity_prelude::ShardManager;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+
+use crate::bot::data::Data;
+use crate::constants::timeouts::SHUTDOWN_CLEANUP_SECONDS;
+use crate::services::jtc::channel_deleter;
+
+/// The long-running loops started on Ready, aborted together on shutdown
+#[derive(Debug, Default)]
+pub struct BackgroundTasks {
+    tasks: Mutex<Vec<AbortHandle>>,
+}
+
+impl BackgroundTasks {
+    pub fn track(&self, task: AbortHandle) {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).push(task);
+    }
+
+    /// Abort every tracked task, returning how many were still running
+    pub fn abort_all(&self) -> usize {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        let running = tasks.iter().filter(|task| !task.is_finished()).count();
+        for task in tasks {
+            task.abort();
+        }
+        running
+    }
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM (what `docker stop` sends) on Unix
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {:?}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+/// Stop the bot cleanly once a shutdown signal arrives
+pub async fn shutdown(data: &Arc<Data>, shard_manager: &ShardManager) {
+    info!("Shutting down...");
+
+    // Nothing new gets queued, swept or backed up while rooms are being cleaned up
+    let stopped = data.background_tasks.abort_all();
+    debug!("Stopped {} background tasks", stopped);
+
+    // Only possible once the bot has been ready; before that there's no cache to check rooms against
+    if let Some(ctx) = data.ready_context() {
+        let cleanup = channel_deleter::cleanup_empty_channels(ctx, data);
+        match tokio::time::timeout(Duration::from_secs(SHUTDOWN_CLEANUP_SECONDS), cleanup).await {
+            Ok(Ok(deleted)) => info!("Deleted {} empty channels on shutdown", deleted),
+            Ok(Err(e)) => warn!("Failed to clean up empty channels on shutdown: {:?}", e),
+            Err(_) => warn!("Shutdown cleanup timed out; the next startup will finish it"),
+        }
+    }
+
+    shard_manager.shutdown_all().await;
+    data.pool.close().await;
+
+    info!("Shutdown complete");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn abort_all_stops_tracked_tasks() {
+        let tasks = BackgroundTasks::default();
+        let handle = tokio::spawn(std::future::pending::<()>());
+        tasks.track(handle.abort_handle());
+
+        assert_eq!(tasks.abort_all(), 1);
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert_eq!(tasks.abort_all(), 0);
+    }
+}
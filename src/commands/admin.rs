@@ -0,0 +1,562 @@
+use std::time::Instant;
+
+use dashmap::mapref::entry::Entry;
+use poise::serenity_prelude::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateAttachment, CreateButton, CreateEmbedFooter,
+    User,
+};
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::commands::checks::is_bot_operator;
+use crate::commands::setup::ChannelTypeChoice;
+use crate::components::confirm::{self, ConfirmResult};
+use crate::constants::embeds;
+use crate::db::queries::{command_usage, guild_config, spam, voice_channel};
+use crate::services::jtc::{channel_creator, inventory};
+use crate::services::moderation::{forgive_service, mod_log};
+use crate::services::spam::timeout_calculator::{self, LevelChange};
+
+/// Discord allows at most 5 rows of 5 buttons per message
+const MAX_CHANNEL_BUTTONS: usize = 25;
+
+/// Commands listed by `/admin globalstats`
+const GLOBALSTATS_TOP_COMMANDS: i64 = 15;
+
+/// Rooms per page of `/admin inventory`
+const INVENTORY_PAGE_SIZE: usize = 10;
+
+/// Moderation and bot operator commands
+#[poise::command(
+    slash_command,
+    subcommands(
+        "loglevel",
+        "user_channels",
+        "forgive",
+        "spam_level",
+        "globalstats",
+        "inventory",
+        "test_jtc"
+    ),
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn admin(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/admin loglevel`, `/admin user-channels`, `/admin forgive`, `/admin spam-level`, `/admin globalstats`, `/admin inventory`, `/admin test-jtc`").await?;
+    Ok(())
+}
+
+/// List every channel a user owns, with force-delete buttons
+#[poise::command(
+    slash_command,
+    rename = "user-channels",
+    required_permissions = "MANAGE_CHANNELS",
+    guild_only
+)]
+pub async fn user_channels(
+    ctx: Context<'_>,
+    #[description = "User whose channels to list"] user: User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let channels = voice_channel::list_by_owner(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        user.id.get() as i64,
+    )
+    .await?;
+
+    if channels.is_empty() {
+        let embed = embeds::info_embed()
+            .title("No Channels")
+            .description(format!("<@{}> doesn't own any managed channels.", user.id));
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = channels
+        .iter()
+        .enumerate()
+        .map(|(i, vc)| {
+            format!(
+                "**{}.** <#{}> ({}, created <t:{}:R>)",
+                i + 1,
+                vc.channel_id,
+                vc.channel_type,
+                vc.created_at.timestamp()
+            )
+        })
+        .collect();
+
+    let mut embed = embeds::standard_embed()
+        .title(format!("Channels owned by {}", user.name))
+        .description(lines.join("\n"));
+
+    if channels.len() > MAX_CHANNEL_BUTTONS {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "Only the newest {} channels have delete buttons.",
+            MAX_CHANNEL_BUTTONS
+        )));
+    }
+
+    let buttons: Vec<CreateButton> = channels
+        .iter()
+        .take(MAX_CHANNEL_BUTTONS)
+        .enumerate()
+        .map(|(i, vc)| {
+            CreateButton::new(format!("chanadmin_delete_{}", vc.channel_id))
+                .label(format!("Delete #{}", i + 1))
+                .style(ButtonStyle::Danger)
+        })
+        .collect();
+
+    let rows: Vec<CreateActionRow> = buttons
+        .chunks(5)
+        .map(|chunk| CreateActionRow::Buttons(chunk.to_vec()))
+        .collect();
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .components(rows)
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Wipe a user's mutes, channel bans and spam timeout level in this server
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+pub async fn forgive(
+    ctx: Context<'_>,
+    #[description = "User to give a clean slate"] user: User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let (result, handle) = confirm::prompt_command(
+        ctx,
+        &format!(
+            "This clears all active mutes and channel bans for <@{}> and resets their spam timeout level.",
+            user.id
+        ),
+    )
+    .await?;
+
+    if result != ConfirmResult::Confirmed {
+        return Ok(());
+    }
+
+    let summary =
+        forgive_service::forgive_user(ctx.serenity_context(), ctx.data(), guild_id, user.id)
+            .await?;
+
+    let mut embed = embeds::success_embed()
+        .title("User Forgiven")
+        .description(format!("<@{}> has a clean slate.", user.id))
+        .field("Mutes cleared", summary.mutes_cleared.to_string(), true)
+        .field("Bans lifted", summary.bans_lifted.to_string(), true)
+        .field(
+            "Timeout level",
+            match summary.timeout_level_reset {
+                Some(level) => format!("{} → 0", level),
+                None => "Already 0".to_string(),
+            },
+            true,
+        );
+
+    if summary.bans_failed > 0 {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "{} ban(s) couldn't be lifted and were left in place.",
+            summary.bans_failed
+        )));
+    }
+
+    handle
+        .edit(ctx, poise::CreateReply::default().embed(embed))
+        .await?;
+
+    let log_embed = embeds::info_embed()
+        .title("User Forgiven")
+        .description(format!(
+            "<@{}> cleared the moderation record of <@{}>: {} mute(s), {} ban(s), timeout level {}.",
+            ctx.author().id,
+            user.id,
+            summary.mutes_cleared,
+            summary.bans_lifted,
+            summary.timeout_level_reset.unwrap_or(0)
+        ));
+    if let Err(e) = mod_log::post(ctx.serenity_context(), ctx.data(), guild_id, log_embed).await {
+        tracing::warn!("Failed to post forgive to mod log: {:?}", e);
+    }
+
+    Ok(())
+}
+
+/// How `/admin spam-level` changes the level
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum SpamLevelAction {
+    #[name = "Set to a value"]
+    Set,
+    #[name = "Add (or subtract) a value"]
+    Add,
+    #[name = "Reset to 0"]
+    Reset,
+}
+
+/// Adjust a user's spam timeout level in this server
+#[poise::command(
+    slash_command,
+    rename = "spam-level",
+    required_permissions = "ADMINISTRATOR",
+    guild_only
+)]
+pub async fn spam_level(
+    ctx: Context<'_>,
+    #[description = "User whose timeout level to change"] user: User,
+    #[description = "How to change the level"] action: SpamLevelAction,
+    #[description = "Level to set, or amount to add (negative to lower it)"]
+    #[min = -7]
+    #[max = 7]
+    value: Option<i32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let max = timeout_calculator::max_level();
+
+    let change = match (action, value) {
+        (SpamLevelAction::Reset, _) => LevelChange::Reset,
+        (SpamLevelAction::Set, Some(level)) if (0..=max as i32).contains(&level) => {
+            LevelChange::Set(level as u32)
+        }
+        (SpamLevelAction::Add, Some(delta)) => LevelChange::Add(delta),
+        (SpamLevelAction::Set, Some(level)) => {
+            return Err(Error::InvalidOperation(format!(
+                "The level must be between 0 and {}, got {}.",
+                max, level
+            )));
+        }
+        (_, None) => {
+            return Err(Error::InvalidOperation(
+                "Give a value to set or add.".to_string(),
+            ));
+        }
+    };
+
+    let pool = &ctx.data().pool;
+    let (guild, target) = (guild_id.get() as i64, user.id.get() as i64);
+    let before = spam::get_user_stats(pool, guild, target)
+        .await?
+        .map_or(0, |record| record.current_timeout_level.max(0) as u32);
+    let after = timeout_calculator::adjust_level(before, change);
+
+    if after != before {
+        spam::set_timeout_level(pool, guild, target, after as i32).await?;
+    }
+
+    let embed = embeds::success_embed()
+        .title("Timeout Level Updated")
+        .description(format!(
+            "<@{}>'s next spam timeout will last {}.",
+            user.id,
+            timeout_calculator::format_timeout_level(after)
+        ))
+        .field("Timeout level", format!("{} → {}", before, after), true);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    if after != before {
+        let log_embed = embeds::info_embed()
+            .title("Timeout Level Changed")
+            .description(format!(
+                "<@{}> changed the spam timeout level of <@{}> from {} to {}.",
+                ctx.author().id,
+                user.id,
+                before,
+                after
+            ));
+        if let Err(e) = mod_log::post(ctx.serenity_context(), ctx.data(), guild_id, log_embed).await
+        {
+            tracing::warn!("Failed to post timeout level change to mod log: {:?}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a room for yourself as if you'd joined a join-to-create channel
+#[poise::command(
+    slash_command,
+    rename = "test-jtc",
+    required_permissions = "ADMINISTRATOR",
+    guild_only
+)]
+pub async fn test_jtc(
+    ctx: Context<'_>,
+    #[description = "Which join-to-create channel to simulate"] channel_type: ChannelTypeChoice,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let user_id = ctx.author().id;
+    let data = ctx.data();
+    let is_casual = matches!(channel_type, ChannelTypeChoice::Casual);
+
+    let config = guild_config::get(&data.pool, guild_id.get() as i64)
+        .await?
+        .ok_or(Error::JtcNotConfigured)?;
+    let jtc_channel_id = config.jtc_channel_id(is_casual).ok_or(Error::JtcNotConfigured)?;
+    if config.category_id(is_casual).is_none() {
+        return Err(Error::JtcNotConfigured);
+    }
+
+    // The new room is cleaned up like any other once it empties, which needs someone to move into it
+    let in_voice = ctx
+        .cache()
+        .guild(guild_id)
+        .and_then(|guild| guild.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+        .is_some();
+    if !in_voice {
+        return Err(Error::InvalidOperation(
+            "Join any voice channel first so you can be moved into the test room".to_string(),
+        ));
+    }
+
+    ctx.defer_ephemeral().await?;
+
+    // Claimed in one step, so two quick runs can't both start a flow
+    match data.jtc_pending.entry(user_id.get()) {
+        Entry::Occupied(_) => {
+            return Err(Error::InvalidOperation(
+                "A room is already being created for you".to_string(),
+            ));
+        }
+        Entry::Vacant(slot) => {
+            slot.insert(Instant::now());
+        }
+    }
+
+    tracing::info!(
+        "{} triggered a test {} JTC flow in guild {}",
+        user_id,
+        if is_casual { "casual" } else { "debate" },
+        guild_id
+    );
+
+    // Called directly rather than queued: the queue skips users who aren't sitting in the JTC channel
+    if let Err(e) = channel_creator::start_jtc_flow(
+        ctx.serenity_context(),
+        data,
+        guild_id,
+        user_id,
+        ChannelId::new(jtc_channel_id as u64),
+        is_casual,
+    )
+    .await
+    {
+        data.jtc_pending.remove(&user_id.get());
+        return Err(e);
+    }
+
+    let embed = embeds::success_embed()
+        .title("Test Room Created")
+        .description(
+            "You've been moved into a new room, set up exactly as a real join would. \
+            Leave it to have it deleted like any other room.",
+        );
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Change the log level of a module at runtime
+#[poise::command(slash_command, owners_only)]
+pub async fn loglevel(
+    ctx: Context<'_>,
+    #[description = "Log target, e.g. jarvis::handlers::voice_state"] target: String,
+    #[description = "New log level"] level: LogLevelChoice,
+) -> Result<(), Error> {
+    let filter = ctx
+        .data()
+        .log_control
+        .set_level(target.trim(), level.as_str())
+        .map_err(Error::InvalidOperation)?;
+
+    tracing::info!(
+        "Log level for {} set to {} by {}",
+        target.trim(),
+        level.as_str(),
+        ctx.author().id
+    );
+
+    let embed = embeds::success_embed()
+        .title("Log Level Updated")
+        .description(format!(
+            "`{}` is now logging at **{}**.",
+            target.trim(),
+            level.as_str()
+        ))
+        .field("Active filter", format!("`{}`", filter), false);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Show which commands are used most across every server
+#[poise::command(slash_command, owners_only)]
+pub async fn globalstats(
+    ctx: Context<'_>,
+    #[description = "How many days back to count (default 30)"]
+    #[min = 1]
+    #[max = 365]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let days = days.unwrap_or(30);
+    let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    let pool = &ctx.data().pool;
+
+    let top = command_usage::top_commands(pool, since, GLOBALSTATS_TOP_COMMANDS).await?;
+    let total = command_usage::total_since(pool, since).await?;
+
+    let description = if top.is_empty() {
+        "No commands have been used in this period.".to_string()
+    } else {
+        top.iter()
+            .enumerate()
+            .map(|(i, usage)| {
+                format!(
+                    "**{}.** `/{}`: {} uses in {} server{}",
+                    i + 1,
+                    usage.command_name,
+                    usage.uses,
+                    usage.guilds,
+                    if usage.guilds == 1 { "" } else { "s" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = embeds::standard_embed()
+        .title(format!("Command Usage (last {} days)", days))
+        .description(description)
+        .field("Total invocations", total.to_string(), true)
+        .field("Servers", ctx.cache().guild_count().to_string(), true);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// List every managed room with its live state, or export it as JSON
+#[poise::command(slash_command, required_permissions = "MANAGE_CHANNELS", guild_only)]
+pub async fn inventory(
+    ctx: Context<'_>,
+    #[description = "Page to show (default 1)"]
+    #[min = 1]
+    page: Option<u32>,
+    #[description = "Attach the full inventory as a JSON file"] json: Option<bool>,
+    #[description = "Include every server the bot is in (bot operators only)"]
+    everywhere: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let everywhere = everywhere.unwrap_or(false);
+
+    if everywhere && !is_bot_operator(ctx, ctx.author().id) {
+        return Err(Error::PermissionDenied(
+            "Only bot operators can list rooms in every server.".to_string(),
+        ));
+    }
+
+    ctx.defer_ephemeral().await?;
+
+    let pool = &ctx.data().pool;
+    let channels = if everywhere {
+        voice_channel::list_all(pool).await?
+    } else {
+        voice_channel::list_by_guild(pool, guild_id.get() as i64).await?
+    };
+    let entries = inventory::collect(ctx.serenity_context(), ctx.data(), &channels).await;
+
+    let pages = entries.len().div_ceil(INVENTORY_PAGE_SIZE).max(1);
+    let page = (page.unwrap_or(1) as usize).min(pages);
+    let shown = entries
+        .iter()
+        .enumerate()
+        .skip((page - 1) * INVENTORY_PAGE_SIZE)
+        .take(INVENTORY_PAGE_SIZE);
+
+    let description = if entries.is_empty() {
+        "There are no managed rooms right now.".to_string()
+    } else {
+        shown
+            .map(|(i, room)| {
+                let mut state = vec![format!("{} in", room.members)];
+                if let Some(limit) = room.user_limit {
+                    state.push(format!("limit {}", limit));
+                }
+                if room.locked {
+                    state.push("locked".to_string());
+                }
+                if room.raid_locked {
+                    state.push("raid locked".to_string());
+                }
+                format!(
+                    "**{}.** <#{}> ({}), owner <@{}>, {}",
+                    i + 1,
+                    room.channel_id,
+                    room.channel_type,
+                    room.owner_id,
+                    state.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let members: usize = entries.iter().map(|room| room.members).sum();
+    let embed = embeds::standard_embed()
+        .title(if everywhere { "Rooms In Every Server" } else { "Rooms In This Server" })
+        .description(description)
+        .field("Rooms", entries.len().to_string(), true)
+        .field("People in rooms", members.to_string(), true)
+        .footer(CreateEmbedFooter::new(format!("Page {}/{}", page, pages)));
+
+    let mut reply = poise::CreateReply::default().embed(embed).ephemeral(true);
+    if json.unwrap_or(false) {
+        reply = reply.attachment(CreateAttachment::bytes(
+            inventory::to_json(&entries).into_bytes(),
+            "inventory.json",
+        ));
+    }
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Log level choice for commands
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum LogLevelChoice {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Off,
+}
+
+impl LogLevelChoice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevelChoice::Trace => "trace",
+            LogLevelChoice::Debug => "debug",
+            LogLevelChoice::Info => "info",
+            LogLevelChoice::Warn => "warn",
+            LogLevelChoice::Error => "error",
+            LogLevelChoice::Off => "off",
+        }
+    }
+}
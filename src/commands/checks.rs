@@ -0,0 +1,64 @@
+//! Framework-wide command checks, run by poise before every command
+
+use poise::serenity_prelude::UserId;
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::db::queries::guild_config;
+
+/// Top-level commands that work before a server has set up JTC.
+/// `/setup` does the configuring, `/admin` and `/diagnostics` are operator tools,
+/// and `/stats` only reads history. Every other command is gated.
+const SETUP_EXEMPT_COMMANDS: &[&str] = &["setup", "admin", "diagnostics", "stats"];
+
+/// Whether a top-level command needs the guild to be set up first
+pub fn requires_guild_setup(root_command: &str) -> bool {
+    !SETUP_EXEMPT_COMMANDS.contains(&root_command)
+}
+
+/// Whether a user is a bot operator: an owner of the application, or listed in `OWNER_IDS`
+/// Both end up in poise's owner set, which `owners_only` commands check as well
+pub fn is_bot_operator(ctx: Context<'_>, user_id: UserId) -> bool {
+    ctx.framework().options().owners.contains(&user_id)
+}
+
+/// Refuse room commands in guilds that haven't configured a category yet
+/// Fails with `Error::JtcNotConfigured`, which `on_error` turns into a setup hint
+pub async fn guild_setup_check(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(true);
+    };
+
+    // Subcommands are checked against the command group they belong to
+    let root = ctx
+        .parent_commands()
+        .first()
+        .map_or(ctx.command().name.as_str(), |parent| parent.name.as_str());
+    if !requires_guild_setup(root) {
+        return Ok(true);
+    }
+
+    let configured = guild_config::get(&ctx.data().pool, guild_id.get() as i64)
+        .await?
+        .is_some_and(|config| config.has_any_category());
+    if !configured {
+        return Err(Error::JtcNotConfigured);
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setup_and_operator_commands_are_exempt() {
+        for command in ["setup", "admin", "diagnostics", "stats"] {
+            assert!(!requires_guild_setup(command), "{}", command);
+        }
+        for command in ["mute", "unmute", "vcban", "vcunban", "banlist", "transfer", "claim", "rename", "retag"] {
+            assert!(requires_guild_setup(command), "{}", command);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::timeouts::DIAGNOSTICS_DB_TIMEOUT_SECONDS;
+use crate::db::pool;
+use crate::utils::duration::format_short;
+
+/// Check the bot's health: gateway and database latency, managed channels, uptime and rate limits
+#[poise::command(slash_command, default_member_permissions = "MANAGE_CHANNELS")]
+pub async fn diagnostics(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    // Zero until the shard has completed its first heartbeat
+    let gateway = ctx.ping().await;
+    let gateway = if gateway.is_zero() {
+        "Not measured yet".to_string()
+    } else {
+        format!("{}ms", gateway.as_millis())
+    };
+
+    let started = Instant::now();
+    let db_check = tokio::time::timeout(
+        Duration::from_secs(DIAGNOSTICS_DB_TIMEOUT_SECONDS),
+        pool::ping(&ctx.data().pool),
+    )
+    .await;
+    let (database, healthy) = match db_check {
+        Ok(Ok(())) => (format!("{}ms", started.elapsed().as_millis()), true),
+        Ok(Err(e)) => (format!("Error: {}", e), false),
+        Err(_) => (
+            format!("No response within {}s", DIAGNOSTICS_DB_TIMEOUT_SECONDS),
+            false,
+        ),
+    };
+
+    let rate_limits = ctx.data().rate_limits.snapshot();
+    let rate_limit_status = match rate_limits.throttled_for {
+        Some(left) => format!(
+            "Throttled for {} ({} recent hits)",
+            format_short(left),
+            rate_limits.recent_hits
+        ),
+        None => format!("Normal ({} hits since start)", rate_limits.total_hits),
+    };
+
+    let embed = if healthy {
+        embeds::success_embed().title("Diagnostics")
+    } else {
+        embeds::warning_embed()
+            .title("Diagnostics")
+            .description("The database isn't responding normally.")
+    }
+    .field("Gateway Latency", gateway, true)
+    .field("Database", database, true)
+    .field("Managed Channels", ctx.data().channel_owners.len().to_string(), true)
+    .field("Uptime", format_short(ctx.data().started_at.elapsed()), true)
+    .field("Rate Limits", rate_limit_status, true);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
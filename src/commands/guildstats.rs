@@ -0,0 +1,67 @@
+use poise::serenity_prelude::CreateAttachment;
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::constants::embeds::{self, BULLET, DIVIDER};
+use crate::services::stats::{aggregator, chart_generator};
+
+/// View moderation and room statistics for this server
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+pub async fn guildstats(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    // Defer since this might take a moment
+    ctx.defer_ephemeral().await?;
+
+    let stats = aggregator::get_guild_stats(&ctx.data().pool, guild_id.get() as i64).await?;
+
+    let guild_name = ctx
+        .serenity_context()
+        .cache
+        .guild(guild_id)
+        .map(|guild| guild.name.clone())
+        .unwrap_or_else(|| "This Server".to_string());
+
+    let description = format!(
+        "{}\n\n\
+        **Moderation Summary**\n\
+        {} Mutes: **{}**\n\
+        {} Bans: **{}**\n\n\
+        {}\n\n\
+        **Rooms**\n\
+        {} Active channels: **{}**",
+        DIVIDER,
+        BULLET, stats.total_mutes,
+        BULLET, stats.total_bans,
+        DIVIDER,
+        BULLET, stats.active_channels
+    );
+
+    let mut embed = embeds::standard_embed()
+        .title(format!("Stats for {}", guild_name))
+        .description(description);
+
+    let mut reply = poise::CreateReply::default();
+
+    // Try to generate chart
+    match chart_generator::generate_guild_stats_chart(
+        stats.total_mutes,
+        stats.total_bans,
+        stats.active_channels,
+        &guild_name,
+    ) {
+        Ok(chart_data) => {
+            embed = embed.image("attachment://guildstats.png");
+            reply = reply.attachment(CreateAttachment::bytes(chart_data, "guildstats.png"));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to generate guild chart: {:?}", e);
+            // Continue without chart
+        }
+    }
+
+    reply = reply.embed(embed).ephemeral(true);
+    ctx.send(reply).await?;
+
+    Ok(())
+}
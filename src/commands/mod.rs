@@ -1,3 +1,8 @@
+pub mod admin;
+pub mod checks;
+pub mod diagnostics;
+pub mod guildstats;
 pub mod owner;
+pub mod preferences;
 pub mod setup;
 pub mod stats;
@@ -2,9 +2,12 @@ use poise::serenity_prelude::User;
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
+use crate::commands::owner::ensure_not_self;
+use crate::components::ban_list;
 use crate::constants::embeds;
-use crate::db::queries::voice_channel;
+use crate::db::queries::ban;
 use crate::services::moderation::ban_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
 
 /// Ban a user from your voice channel
 #[poise::command(slash_command, guild_only)]
@@ -16,11 +19,13 @@ pub async fn vcban(
     let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
     let author_id = ctx.author().id;
 
+    ensure_not_self(author_id, user.id, "ban")?;
+
     // Find the channel the author owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Perform the ban
-    ban_service::ban_user(
+    let is_new_ban = ban_service::ban_user(
         ctx.serenity_context(),
         ctx.data(),
         guild_id,
@@ -31,12 +36,33 @@ pub async fn vcban(
     )
     .await?;
 
-    let mut embed = embeds::success_embed()
-        .title("User Banned")
-        .description(format!(
-            "<@{}> has been banned from your voice channel.",
-            user.id
-        ));
+    if is_new_ban {
+        room_notice::announce(
+            ctx.serenity_context(),
+            ctx.data(),
+            guild_id,
+            channel_id,
+            RoomAction::Ban,
+            user.id,
+        )
+        .await;
+    }
+
+    let mut embed = if is_new_ban {
+        embeds::success_embed()
+            .title("User Banned")
+            .description(format!(
+                "<@{}> has been banned from your voice channel.",
+                user.id
+            ))
+    } else {
+        embeds::warning_embed()
+            .title("Already Banned")
+            .description(format!(
+                "<@{}> was already banned from your voice channel.",
+                user.id
+            ))
+    };
 
     if let Some(ref r) = reason {
         embed = embed.field("Reason", r, false);
@@ -58,7 +84,7 @@ pub async fn vcunban(
     let author_id = ctx.author().id;
 
     // Find the channel the author owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Perform the unban
     let unbanned = ban_service::unban_user(
@@ -69,6 +95,18 @@ pub async fn vcunban(
     )
     .await?;
 
+    if unbanned {
+        room_notice::announce(
+            ctx.serenity_context(),
+            ctx.data(),
+            guild_id,
+            channel_id,
+            RoomAction::Unban,
+            user.id,
+        )
+        .await;
+    }
+
     let embed = if unbanned {
         embeds::success_embed()
             .title("User Unbanned")
@@ -91,32 +129,24 @@ pub async fn vcunban(
     Ok(())
 }
 
-/// Find a channel owned by the user
-async fn find_owned_channel(
-    ctx: Context<'_>,
-    guild_id: u64,
-    user_id: u64,
-) -> Result<serenity::all::ChannelId, Error> {
-    use serenity::all::ChannelId;
-
-    // First check cache
-    for entry in ctx.data().channel_owners.iter() {
-        if *entry.value() == user_id {
-            return Ok(ChannelId::new(*entry.key()));
-        }
-    }
+/// List who is banned from your voice channel, with a menu to unban them
+#[poise::command(slash_command, guild_only)]
+pub async fn banlist(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
 
-    // Check database
-    if let Some(vc) =
-        voice_channel::get_by_owner(&ctx.data().pool, guild_id as i64, user_id as i64).await?
-    {
-        let channel_id = ChannelId::new(vc.channel_id as u64);
-        // Update cache
-        ctx.data().set_channel_owner(vc.channel_id as u64, user_id);
-        return Ok(channel_id);
-    }
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), ctx.author().id.get()).await?;
+
+    let bans = ban::get_bans_for_channel(&ctx.data().pool, channel_id.get() as i64).await?;
+    let (embed, components) = ban_list::build(ctx.serenity_context(), channel_id, &bans);
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .components(components)
+            .ephemeral(true),
+    )
+    .await?;
 
-    Err(Error::custom(
-        "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
-    ))
+    Ok(())
 }
@@ -0,0 +1,138 @@
+use poise::serenity_prelude::{ChannelId, CreateMessage, UserId};
+use tracing::{error, info};
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::components::owner_actions;
+use crate::constants::embeds;
+use crate::db::queries::voice_channel;
+use crate::services::jtc::{channel_creator, channel_deleter, owner_role, voice_presence};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
+
+/// The voice channel a user is currently connected to
+fn current_channel(ctx: Context<'_>, user_id: UserId) -> Option<ChannelId> {
+    let guild_id = ctx.guild_id()?;
+    ctx.serenity_context()
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| guild.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+}
+
+/// Take over the voice channel you're in once its owner has left
+#[poise::command(slash_command, guild_only)]
+pub async fn claim(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let author_id = ctx.author().id;
+
+    let channel_id = current_channel(ctx, author_id)
+        .ok_or(Error::custom("Join the voice channel you want to claim first."))?;
+
+    let vc = voice_channel::get(&ctx.data().pool, channel_id.get() as i64)
+        .await?
+        .ok_or(Error::custom("Only Join-to-Create rooms can be claimed."))?;
+    let owner_id = UserId::new(vc.owner_id as u64);
+
+    if owner_id == author_id {
+        return Err(Error::InvalidOperation("You already own this room.".to_string()));
+    }
+
+    // Owners who are still in their room keep it; /transfer is how they hand it over
+    let present = voice_presence::is_in_channel(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        channel_id,
+        owner_id,
+    )
+    .await;
+    if present {
+        let embed = embeds::error_embed()
+            .title("Owner Still Here")
+            .description(format!(
+                "<@{}> is still in <#{}>. A room can only be claimed once its owner has left.",
+                owner_id, channel_id
+            ));
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    // Claims count towards the room's transfer limit, so it can't be passed around this way
+    if let Err(reason) = ctx
+        .data()
+        .transfer_history
+        .record(channel_id.get(), owner_id.get(), author_id.get())
+    {
+        let embed = embeds::error_embed()
+            .title("Transfer Cooldown")
+            .description(reason);
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    // Update database
+    let vc = voice_channel::update_owner(
+        &ctx.data().pool,
+        channel_id.get() as i64,
+        author_id.get() as i64,
+    )
+    .await?;
+
+    // Update cache; a room held for its owner is no longer theirs to come back to
+    ctx.data().set_channel_owner(channel_id.get(), author_id.get());
+    channel_deleter::cancel_hold(ctx.data(), channel_id);
+
+    let serenity_ctx = ctx.serenity_context();
+    owner_role::hand_over(&serenity_ctx.http, &ctx.data().pool, guild_id, owner_id, author_id).await;
+
+    if let Err(e) = owner_actions::update_channel_permissions(
+        serenity_ctx,
+        guild_id,
+        channel_id,
+        owner_id,
+        author_id,
+    )
+    .await
+    {
+        error!("Failed to update channel permissions: {:?}", e);
+    }
+    if let Some(vc) = vc {
+        if let Err(e) =
+            channel_creator::transfer_discussion_channel(serenity_ctx, &vc, owner_id, author_id)
+                .await
+        {
+            error!("Failed to update discussion channel permissions: {:?}", e);
+        }
+    }
+
+    info!("User {} claimed channel {} from {}", author_id, channel_id, owner_id);
+
+    let entry = LogEntry {
+        action: LogAction::Transfer,
+        actor: Some(author_id),
+        target: author_id,
+        channel_id: Some(channel_id),
+        reason: Some(format!("Claimed after <@{}> left the room", owner_id)),
+    };
+    mod_log::record(serenity_ctx, ctx.data(), guild_id, entry).await;
+
+    let embed = embeds::success_embed()
+        .title("Room Claimed")
+        .description(format!("You are now the owner of <#{}>.", channel_id));
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    // Let the room know who's in charge now
+    let notify_embed = embeds::standard_embed()
+        .title("New Channel Owner")
+        .description(format!(
+            "<@{}> claimed this voice channel after <@{}> left.",
+            author_id, owner_id
+        ));
+    let _ = channel_id
+        .send_message(serenity_ctx, CreateMessage::new().embed(notify_embed))
+        .await;
+
+    Ok(())
+}
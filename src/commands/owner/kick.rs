@@ -0,0 +1,76 @@
+use poise::serenity_prelude::User;
+
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::commands::owner::ensure_not_self;
+use crate::constants::embeds;
+use crate::services::moderation::room_notice::{self, RoomAction};
+
+/// Check if a user is in a specific voice channel
+fn is_user_in_channel(ctx: Context<'_>, guild_id: u64, channel_id: u64, user_id: u64) -> bool {
+    ctx.serenity_context()
+        .cache
+        .guild(serenity::all::GuildId::new(guild_id))
+        .map(|guild| {
+            guild
+                .voice_states
+                .get(&serenity::all::UserId::new(user_id))
+                .and_then(|vs| vs.channel_id)
+                .map(|cid| cid.get() == channel_id)
+                .unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+/// Disconnect a user from your voice channel without banning them
+#[poise::command(slash_command, guild_only)]
+pub async fn kick(
+    ctx: Context<'_>,
+    #[description = "User to kick"] user: User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let author_id = ctx.author().id;
+
+    ensure_not_self(author_id, user.id, "kick")?;
+
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
+
+    // Verify the target user is in the owner's channel
+    if !is_user_in_channel(ctx, guild_id.get(), channel_id.get(), user.id.get()) {
+        let embed = embeds::error_embed()
+            .title("Not In Your Channel")
+            .description(format!(
+                "<@{}> is not in your voice channel. You can only kick people who are actually in your room.",
+                user.id
+            ));
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    // Only a disconnect - no ban is recorded, so they can rejoin right away
+    guild_id.disconnect_member(ctx, user.id).await?;
+
+    room_notice::announce(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        channel_id,
+        RoomAction::Kick,
+        user.id,
+    )
+    .await;
+
+    let embed = embeds::success_embed()
+        .title("User Kicked")
+        .description(format!(
+            "<@{}> has been disconnected from your channel. They can rejoin; use `/vcban` to keep them out.",
+            user.id
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
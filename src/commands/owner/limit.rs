@@ -0,0 +1,57 @@
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::components::limit_selector::{self, MAX_USER_LIMIT};
+use crate::constants::embeds;
+
+/// Set how many people can join your voice channel
+#[poise::command(slash_command, guild_only)]
+pub async fn limit(
+    ctx: Context<'_>,
+    #[description = "Maximum number of people (0 for unlimited)"]
+    #[min = 0]
+    #[max = 69]
+    limit: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let author_id = ctx.author().id;
+
+    if limit > MAX_USER_LIMIT {
+        return Err(Error::InvalidOperation(format!(
+            "The limit must be between 0 and {}.",
+            MAX_USER_LIMIT
+        )));
+    }
+
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
+
+    // Shares the hourly allowance with the panel's Set Join Limit button
+    if let Err(reason) =
+        limit_selector::record_limit_change(ctx.data(), author_id.get(), channel_id.get())
+    {
+        let embed = embeds::error_embed()
+            .title("Rate Limit")
+            .description(reason);
+
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+
+        return Ok(());
+    }
+
+    limit_selector::apply_limit(ctx.serenity_context(), channel_id, limit).await?;
+
+    let embed = embeds::success_embed()
+        .title("User Limit Updated")
+        .description(limit_selector::limit_summary(
+            ctx.serenity_context(),
+            Some(guild_id),
+            channel_id,
+            limit,
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::services::jtc::channel_lock;
+
+/// Stop new people from joining your voice channel
+#[poise::command(slash_command, guild_only)]
+pub async fn lock(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), ctx.author().id.get()).await?;
+
+    let changed =
+        channel_lock::lock_channel(ctx.serenity_context(), ctx.data(), guild_id, channel_id).await?;
+
+    let embed = if changed {
+        embeds::success_embed()
+            .title("Room Locked")
+            .description(format!(
+                "<#{}> is locked. People already inside can stay, but nobody new can join.",
+                channel_id
+            ))
+    } else {
+        embeds::secondary_embed()
+            .title("Already Locked")
+            .description("Your room is already locked. Use `/unlock` to open it again.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Let people join your voice channel again
+#[poise::command(slash_command, guild_only)]
+pub async fn unlock(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), ctx.author().id.get()).await?;
+
+    let changed = channel_lock::unlock_channel(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        channel_id,
+    )
+    .await?;
+
+    let embed = if changed {
+        embeds::success_embed()
+            .title("Room Unlocked")
+            .description(format!("Anyone can join <#{}> again.", channel_id))
+    } else {
+        embeds::secondary_embed()
+            .title("Not Locked")
+            .description("Your room isn't locked.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
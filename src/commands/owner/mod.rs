@@ -1,5 +1,43 @@
 pub mod ban;
+pub mod claim;
+pub mod kick;
+pub mod limit;
+pub mod lock;
 pub mod mute;
 pub mod rename;
 pub mod retag;
 pub mod transfer;
+
+use poise::serenity_prelude::UserId;
+
+use crate::bot::error::Error;
+
+/// Refuse an owner action aimed at the owner themselves, e.g. `/mute @self`
+/// `action` completes "You can't ... yourself."
+#[allow(clippy::result_large_err)]
+pub(crate) fn ensure_not_self(
+    author_id: UserId,
+    target_id: UserId,
+    action: &str,
+) -> Result<(), Error> {
+    if author_id == target_id {
+        return Err(Error::InvalidOperation(format!("You can't {} yourself.", action)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_commands_reject_self_targets() {
+        let (owner, other) = (UserId::new(1), UserId::new(2));
+        for action in ["mute", "ban", "kick", "transfer the room to"] {
+            let rejected = ensure_not_self(owner, owner, action);
+            let expected = format!("You can't {} yourself.", action);
+            assert!(matches!(rejected, Err(Error::InvalidOperation(message)) if message == expected));
+            assert!(ensure_not_self(owner, other, action).is_ok());
+        }
+    }
+}
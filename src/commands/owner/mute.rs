@@ -2,9 +2,11 @@ use poise::serenity_prelude::User;
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
+use crate::commands::owner::ensure_not_self;
 use crate::constants::embeds;
-use crate::db::queries::voice_channel;
 use crate::services::moderation::mute_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
+use crate::utils::duration::{format_short, parse_moderation_duration};
 
 /// Check if a user is in a specific voice channel
 fn is_user_in_channel(ctx: Context<'_>, guild_id: u64, channel_id: u64, user_id: u64) -> bool {
@@ -27,12 +29,18 @@ fn is_user_in_channel(ctx: Context<'_>, guild_id: u64, channel_id: u64, user_id:
 pub async fn mute(
     ctx: Context<'_>,
     #[description = "User to mute"] user: User,
+    #[description = "Also server deafen them until they're unmuted or leave"] deafen: Option<bool>,
+    #[description = "Lift the mute automatically after this long, e.g. 10m or 1h"] duration: Option<
+        String,
+    >,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
     let author_id = ctx.author().id;
 
+    ensure_not_self(author_id, user.id, "mute")?;
+
     // Find the channel the author is in and owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Verify the target user is in the owner's channel
     if !is_user_in_channel(ctx, guild_id.get(), channel_id.get(), user.id.get()) {
@@ -51,6 +59,12 @@ pub async fn mute(
     // This is intentional: even if the owner has admin perms, using /mute in their own
     // channel should create a local mute, not a permanent admin mute
     let is_admin_mute = false;
+    let deafen = deafen.unwrap_or(false);
+    let duration = duration
+        .as_deref()
+        .map(parse_moderation_duration)
+        .transpose()
+        .map_err(Error::InvalidOperation)?;
 
     // Perform the mute
     mute_service::mute_user(
@@ -61,12 +75,31 @@ pub async fn mute(
         user.id,
         author_id,
         is_admin_mute,
+        deafen,
+        duration,
     )
     .await?;
 
+    room_notice::announce(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        channel_id,
+        RoomAction::Mute,
+        user.id,
+    )
+    .await;
+
     let embed = embeds::success_embed()
         .title("User Muted")
-        .description(format!("<@{}> has been server muted.", user.id));
+        .description(format!(
+            "<@{}> has been server {}{}.",
+            user.id,
+            if deafen { "muted and deafened" } else { "muted" },
+            duration
+                .map(|d| format!(" for {}", format_short(d)))
+                .unwrap_or_default()
+        ));
 
     ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
         .await?;
@@ -84,7 +117,7 @@ pub async fn unmute(
     let author_id = ctx.author().id;
 
     // Find the channel the author is in and owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Verify the target user is in the owner's channel
     if !is_user_in_channel(ctx, guild_id.get(), channel_id.get(), user.id.get()) {
@@ -106,9 +139,22 @@ pub async fn unmute(
         guild_id,
         channel_id,
         user.id,
+        ctx.author().id,
     )
     .await?;
 
+    if unmuted {
+        room_notice::announce(
+            ctx.serenity_context(),
+            ctx.data(),
+            guild_id,
+            channel_id,
+            RoomAction::Unmute,
+            user.id,
+        )
+        .await;
+    }
+
     let embed = if unmuted {
         embeds::success_embed()
             .title("User Unmuted")
@@ -125,32 +171,36 @@ pub async fn unmute(
     Ok(())
 }
 
-/// Find a channel owned by the user
-async fn find_owned_channel(
-    ctx: Context<'_>,
-    guild_id: u64,
-    user_id: u64,
-) -> Result<serenity::all::ChannelId, Error> {
-    use serenity::all::ChannelId;
-
-    // First check cache
-    for entry in ctx.data().channel_owners.iter() {
-        if *entry.value() == user_id {
-            return Ok(ChannelId::new(*entry.key()));
-        }
-    }
+/// Unmute everyone you muted in your voice channel
+#[poise::command(slash_command, guild_only)]
+pub async fn unmuteall(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
 
-    // Check database
-    if let Some(vc) =
-        voice_channel::get_by_owner(&ctx.data().pool, guild_id as i64, user_id as i64).await?
-    {
-        let channel_id = ChannelId::new(vc.channel_id as u64);
-        // Update cache
-        ctx.data().set_channel_owner(vc.channel_id as u64, user_id);
-        return Ok(channel_id);
-    }
+    // Find the channel the author owns
+    let channel_id = ctx.data().owned_channel(guild_id.get(), ctx.author().id.get()).await?;
+
+    // Lifting many mutes is spaced out, so this can take a moment
+    ctx.defer_ephemeral().await?;
+
+    let lifted =
+        mute_service::unmute_all_in_channel(ctx.serenity_context(), ctx.data(), guild_id, channel_id)
+            .await?;
 
-    Err(Error::custom(
-        "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
-    ))
+    let embed = embeds::success_embed()
+        .title("Mutes Lifted")
+        .description(unmute_all_summary(lifted));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Reply text for lifting every mute in a room
+pub fn unmute_all_summary(lifted: usize) -> String {
+    match lifted {
+        0 => "Nobody is muted in your room.".to_string(),
+        1 => "Unmuted 1 person in your room.".to_string(),
+        n => format!("Unmuted {} people in your room.", n),
+    }
 }
@@ -1,11 +1,10 @@
 use chrono::Utc;
-use poise::serenity_prelude::ChannelId;
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
 use crate::constants::embeds;
 use crate::constants::timeouts::RENAME_RETAG_RATE_LIMIT_SECONDS;
-use crate::db::queries::{rate_limit, user_vc_preference, voice_channel};
+use crate::db::queries::{banned_word, guild_config, rate_limit, user_vc_preference, voice_channel};
 use crate::db::queries::rate_limit::CommandType;
 use crate::services::jtc::channel_creator;
 use crate::utils::profanity;
@@ -14,7 +13,7 @@ use crate::utils::profanity;
 #[poise::command(slash_command, guild_only)]
 pub async fn rename(
     ctx: Context<'_>,
-    #[description = "New name for your channel (max 100 characters)"] name: String,
+    #[description = "New name for your channel"] name: String,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
     let author_id = ctx.author().id;
@@ -32,9 +31,14 @@ pub async fn rename(
     }
 
     // Check for profanity and validate name
-    if let Err(reason) = profanity::validate_channel_name(&name) {
+    let max_length = guild_config::get_or_create(&ctx.data().pool, guild_id.get() as i64)
+        .await?
+        .max_name_length();
+    let banned_words = banned_word::list(&ctx.data().pool, guild_id.get() as i64).await?;
+    let filter = ctx.data().word_filters.get(guild_id.get(), banned_words);
+    if let Err(reason) = profanity::validate_channel_name(&name, max_length, &filter) {
         let embed = embeds::error_embed()
-            .title("Inappropriate Name")
+            .title("Invalid Name")
             .description(reason);
 
         ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
@@ -44,7 +48,7 @@ pub async fn rename(
     }
 
     // Find the channel the author owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Check rate limit
     let last_used = rate_limit::get_last_used(
@@ -133,31 +137,3 @@ pub async fn rename(
 
     Ok(())
 }
-
-/// Find a channel owned by the user
-async fn find_owned_channel(
-    ctx: Context<'_>,
-    guild_id: u64,
-    user_id: u64,
-) -> Result<ChannelId, Error> {
-    // First check cache
-    for entry in ctx.data().channel_owners.iter() {
-        if *entry.value() == user_id {
-            return Ok(ChannelId::new(*entry.key()));
-        }
-    }
-
-    // Check database
-    if let Some(vc) =
-        voice_channel::get_by_owner(&ctx.data().pool, guild_id as i64, user_id as i64).await?
-    {
-        let channel_id = ChannelId::new(vc.channel_id as u64);
-        // Update cache
-        ctx.data().set_channel_owner(vc.channel_id as u64, user_id);
-        return Ok(channel_id);
-    }
-
-    Err(Error::custom(
-        "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
-    ))
-}
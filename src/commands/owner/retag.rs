@@ -1,12 +1,11 @@
 use chrono::Utc;
-use poise::serenity_prelude::ChannelId;
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
 use crate::constants::embeds;
-use crate::constants::tags::{get_tags, MAX_TAGS};
+use crate::constants::tags::{available_tags, is_available_tag, normalize_tags};
 use crate::constants::timeouts::RENAME_RETAG_RATE_LIMIT_SECONDS;
-use crate::db::queries::{rate_limit, voice_channel};
+use crate::db::queries::{guild_config, rate_limit, voice_channel};
 use crate::db::queries::rate_limit::CommandType;
 use crate::services::jtc::channel_creator;
 
@@ -20,7 +19,7 @@ pub async fn retag(
     let author_id = ctx.author().id;
 
     // Find the channel the author owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Get channel info to determine if it's casual or debate
     let vc = voice_channel::get(&ctx.data().pool, channel_id.get() as i64)
@@ -28,27 +27,18 @@ pub async fn retag(
         .ok_or(Error::custom("Channel not found in database."))?;
 
     let is_casual = vc.channel_type.is_casual();
-    let available_tags = get_tags(is_casual);
-
-    // Parse tags from input
-    let tag_list: Vec<String> = tags
-        .split_whitespace()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    // Validate tag count
-    if tag_list.len() > MAX_TAGS {
-        return Err(Error::custom(format!(
-            "You can only select up to {} tags.",
-            MAX_TAGS
-        )));
-    }
+    let config = guild_config::get_or_create(&ctx.data().pool, guild_id.get() as i64).await?;
+    let available_tags = available_tags(is_casual, config.emoji_tags(is_casual));
+
+    // Parse and normalize tags from input (also enforces the tag count and length)
+    let tag_case = ctx.data().settings.tag_case;
+    let tag_list: Vec<&str> = tags.split_whitespace().collect();
+    let tag_list = normalize_tags(&tag_list, tag_case).map_err(Error::custom)?;
 
     // Validate tags are in the available list
     let mut valid_tags = Vec::new();
     for tag in &tag_list {
-        if available_tags.contains(&tag.as_str()) {
+        if is_available_tag(tag, &available_tags, tag_case) {
             valid_tags.push(tag.clone());
         } else {
             return Err(Error::custom(format!(
@@ -137,31 +127,3 @@ pub async fn retag(
 
     Ok(())
 }
-
-/// Find a channel owned by the user
-async fn find_owned_channel(
-    ctx: Context<'_>,
-    guild_id: u64,
-    user_id: u64,
-) -> Result<ChannelId, Error> {
-    // First check cache
-    for entry in ctx.data().channel_owners.iter() {
-        if *entry.value() == user_id {
-            return Ok(ChannelId::new(*entry.key()));
-        }
-    }
-
-    // Check database
-    if let Some(vc) =
-        voice_channel::get_by_owner(&ctx.data().pool, guild_id as i64, user_id as i64).await?
-    {
-        let channel_id = ChannelId::new(vc.channel_id as u64);
-        // Update cache
-        ctx.data().set_channel_owner(vc.channel_id as u64, user_id);
-        return Ok(channel_id);
-    }
-
-    Err(Error::custom(
-        "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
-    ))
-}
@@ -2,8 +2,11 @@ use poise::serenity_prelude::User;
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
+use crate::commands::owner::ensure_not_self;
 use crate::constants::embeds;
 use crate::db::queries::voice_channel;
+use crate::services::jtc::{channel_creator, owner_role};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
 
 /// Transfer ownership of your voice channel to another user
 #[poise::command(slash_command, guild_only)]
@@ -15,26 +18,48 @@ pub async fn transfer(
     let author_id = ctx.author().id;
 
     // Find the channel the author owns
-    let channel_id = find_owned_channel(ctx, guild_id.get(), author_id.get()).await?;
+    let channel_id = ctx.data().owned_channel(guild_id.get(), author_id.get()).await?;
 
     // Prevent transferring to self
-    if user.id == author_id {
-        return Err(Error::custom("You cannot transfer ownership to yourself."));
-    }
+    ensure_not_self(author_id, user.id, "transfer the room to")?;
 
     // Prevent transferring to bots
     if user.bot {
         return Err(Error::custom("You cannot transfer ownership to a bot."));
     }
 
+    if let Err(reason) = ctx
+        .data()
+        .transfer_history
+        .record(channel_id.get(), author_id.get(), user.id.get())
+    {
+        let embed = embeds::error_embed()
+            .title("Transfer Cooldown")
+            .description(reason);
+
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+
+        return Ok(());
+    }
+
     // Update database
-    voice_channel::update_owner(&ctx.data().pool, channel_id.get() as i64, user.id.get() as i64)
+    let vc = voice_channel::update_owner(&ctx.data().pool, channel_id.get() as i64, user.id.get() as i64)
         .await?;
 
     // Update cache
     ctx.data()
         .set_channel_owner(channel_id.get(), user.id.get());
 
+    owner_role::hand_over(
+        &ctx.serenity_context().http,
+        &ctx.data().pool,
+        guild_id,
+        author_id,
+        user.id,
+    )
+    .await;
+
     // Update channel permissions
     // Remove manage permission from old owner, add to new owner
     use serenity::all::{PermissionOverwrite, PermissionOverwriteType, Permissions};
@@ -58,6 +83,20 @@ pub async fn transfer(
         )
         .await;
 
+    if let Some(vc) = vc {
+        let _ = channel_creator::transfer_discussion_channel(ctx.serenity_context(), &vc, author_id, user.id)
+            .await;
+    }
+
+    let entry = LogEntry {
+        action: LogAction::Transfer,
+        actor: Some(author_id),
+        target: user.id,
+        channel_id: Some(channel_id),
+        reason: Some("Handed over by the owner".to_string()),
+    };
+    mod_log::record(ctx.serenity_context(), ctx.data(), guild_id, entry).await;
+
     let embed = embeds::success_embed()
         .title("Ownership Transferred")
         .description(format!(
@@ -85,33 +124,3 @@ pub async fn transfer(
 
     Ok(())
 }
-
-/// Find a channel owned by the user
-async fn find_owned_channel(
-    ctx: Context<'_>,
-    guild_id: u64,
-    user_id: u64,
-) -> Result<serenity::all::ChannelId, Error> {
-    use serenity::all::ChannelId;
-
-    // First check cache
-    for entry in ctx.data().channel_owners.iter() {
-        if *entry.value() == user_id {
-            return Ok(ChannelId::new(*entry.key()));
-        }
-    }
-
-    // Check database
-    if let Some(vc) =
-        voice_channel::get_by_owner(&ctx.data().pool, guild_id as i64, user_id as i64).await?
-    {
-        let channel_id = ChannelId::new(vc.channel_id as u64);
-        // Update cache
-        ctx.data().set_channel_owner(vc.channel_id as u64, user_id);
-        return Ok(channel_id);
-    }
-
-    Err(Error::custom(
-        "You don't own a voice channel. Create one by joining a Join-to-Create channel.",
-    ))
-}
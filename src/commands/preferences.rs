@@ -0,0 +1,62 @@
+use crate::bot::data::Context;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::tags::format_status;
+use crate::db::queries::user_vc_preference;
+use crate::services::jtc::channel_creator::default_channel_name;
+
+/// Your saved room preferences
+#[poise::command(slash_command, subcommands("preview"), guild_only)]
+pub async fn preferences(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/preferences preview`").await?;
+    Ok(())
+}
+
+/// Preview the name and tags your next casual and debate rooms will get
+#[poise::command(slash_command, guild_only)]
+pub async fn preview(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let mut embed = embeds::standard_embed()
+        .title("Your Next Rooms")
+        .description(
+            "Saved preferences are applied automatically when you join a Join-to-Create channel. \
+            Renaming or retagging a room updates them.",
+        );
+
+    for (is_casual, label) in [(true, "Casual"), (false, "Debate")] {
+        let channel_type = if is_casual { "casual" } else { "debate" };
+        let preference = user_vc_preference::get(
+            &ctx.data().pool,
+            guild_id.get() as i64,
+            ctx.author().id.get() as i64,
+            channel_type,
+        )
+        .await?;
+
+        let value = match preference {
+            Some(pref) => {
+                let name = pref
+                    .preferred_name
+                    .unwrap_or_else(|| default_channel_name(is_casual).to_string());
+                let tags = if pref.preferred_tags.is_empty() {
+                    "None".to_string()
+                } else {
+                    format_status(&pref.preferred_tags)
+                };
+                format!("**Name:** {}\n**Tags:** {}", name, tags)
+            }
+            None => format!(
+                "**Name:** {}\nNo saved preferences, so you'll be asked to name the room.",
+                default_channel_name(is_casual)
+            ),
+        };
+
+        embed = embed.field(label, value, false);
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
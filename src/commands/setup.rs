@@ -1,19 +1,81 @@
-use poise::serenity_prelude::Channel;
+use poise::serenity_prelude::{Channel, Permissions, Role};
 
 use crate::bot::data::Context;
 use crate::bot::error::Error;
 use crate::constants::embeds;
-use crate::db::queries::guild_config;
+use crate::constants::messages::MAX_TEMPLATE_LENGTH;
+use crate::constants::tags::{
+    available_tags, is_available_tag, normalize_tags, EmojiTag, MAX_EMOJI_TAGS,
+};
+use crate::constants::timeouts::{
+    MAX_EMPTY_CHANNEL_GRACE_SECONDS, MAX_RECREATE_COOLDOWN_SECONDS, OWNER_HOP_HOLD_SECONDS,
+    RAID_LOCK_WINDOW_SECONDS, ROOM_NOTICE_SECONDS, VC_NAMING_DEADLINE_SECONDS,
+};
+use crate::db::models::{MessageTemplate, OwnerHopPolicy, SpamPromptTarget, SpamThresholds};
+use crate::db::queries::{banned_word, guild_config, room_permission};
+use crate::services::jtc::channel_creator::{MAX_OVERFLOW_CATEGORIES, MAX_ROOM_PERMISSION_ROLES};
+use crate::utils::permissions;
+use crate::utils::profanity::{
+    normalize_banned_word, MAX_CHANNEL_NAME_LENGTH, MAX_GUILD_BANNED_WORDS,
+};
 
+// Discord allows 25 options per command, so most settings sit in groups below the channel setup
 /// Setup commands for configuring the bot
 #[poise::command(
     slash_command,
-    subcommands("jtc_channel", "category", "rules_channel"),
+    subcommands("jtc_channel", "category", "overflow_categories", "rules_channel", "message", "appeal_channel", "mod_log", "owner_role", "room_permissions", "rooms", "moderation", "spam", "tags", "announcements", "banword"),
     required_permissions = "ADMINISTRATOR",
     guild_only
 )]
 pub async fn setup(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.say("Use one of the subcommands: `/setup jtc-channel`, `/setup category`, `/setup rules-channel`").await?;
+    ctx.say("Use one of the subcommands: `/setup jtc-channel`, `/setup category`, `/setup overflow-categories`, `/setup rules-channel`, `/setup message`, `/setup appeal-channel`, `/setup mod-log`, `/setup owner-role`, `/setup room-permissions`, `/setup rooms`, `/setup moderation`, `/setup spam`, `/setup tags`, `/setup announcements`, `/setup banword`").await?;
+    Ok(())
+}
+
+/// How rooms behave once they exist
+#[poise::command(
+    slash_command,
+    subcommands("discussion_channels", "owner_hop", "empty_grace", "recreate_cooldown", "max_name_length", "keep_streaming"),
+    guild_only
+)]
+pub async fn rooms(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup rooms discussion-channels`, `/setup rooms owner-hop`, `/setup rooms empty-grace`, `/setup rooms recreate-cooldown`, `/setup rooms max-name-length`, `/setup rooms keep-streaming`").await?;
+    Ok(())
+}
+
+/// Mutes, raid locks and moderation notices
+#[poise::command(
+    slash_command,
+    subcommands("keep_mutes", "raid_lock", "global_mute_alerts", "room_notices"),
+    guild_only
+)]
+pub async fn moderation(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup moderation keep-mutes`, `/setup moderation raid-lock`, `/setup moderation global-mute-alerts`, `/setup moderation room-notices`").await?;
+    Ok(())
+}
+
+/// The join spam detector
+#[poise::command(slash_command, subcommands("spam_thresholds", "spam_prompt"), guild_only)]
+pub async fn spam(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup spam thresholds`, `/setup spam prompt`").await?;
+    Ok(())
+}
+
+/// Tags offered on and required for new rooms
+#[poise::command(
+    slash_command,
+    subcommands("default_tags", "emoji_tags", "require_tags"),
+    guild_only
+)]
+pub async fn tags(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup tags default`, `/setup tags emoji`, `/setup tags require`").await?;
+    Ok(())
+}
+
+/// Messages posted about new rooms and their owners
+#[poise::command(slash_command, subcommands("welcome_dm", "room_announcements"), guild_only)]
+pub async fn announcements(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup announcements welcome-dm`, `/setup announcements new-rooms`").await?;
     Ok(())
 }
 
@@ -87,6 +149,69 @@ pub async fn category(
     Ok(())
 }
 
+/// Set extra categories used once the main category is full
+#[poise::command(slash_command, rename = "overflow-categories", guild_only)]
+pub async fn overflow_categories(
+    ctx: Context<'_>,
+    #[description = "Channel type"] channel_type: ChannelTypeChoice,
+    #[description = "First overflow category (leave all empty to clear)"]
+    #[channel_types("Category")]
+    first: Option<Channel>,
+    #[description = "Second overflow category"]
+    #[channel_types("Category")]
+    second: Option<Channel>,
+    #[description = "Third overflow category"]
+    #[channel_types("Category")]
+    third: Option<Channel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let is_casual = matches!(channel_type, ChannelTypeChoice::Casual);
+
+    let mut category_ids: Vec<i64> = Vec::with_capacity(MAX_OVERFLOW_CATEGORIES);
+    for category in [first, second, third].into_iter().flatten() {
+        let id = category.id().get() as i64;
+        if !category_ids.contains(&id) {
+            category_ids.push(id);
+        }
+    }
+
+    guild_config::set_overflow_categories(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        is_casual,
+        &category_ids,
+    )
+    .await?;
+
+    let kind = if is_casual { "casual" } else { "debate" };
+    let embed = if category_ids.is_empty() {
+        embeds::success_embed()
+            .title("Overflow Categories Cleared")
+            .description(format!(
+                "New {} rooms will only be created in the main category.",
+                kind
+            ))
+    } else {
+        embeds::success_embed()
+            .title("Overflow Categories Set")
+            .description(format!(
+                "Once the main {} category is full, new rooms go to: {}",
+                kind,
+                category_ids
+                    .iter()
+                    .map(|id| format!("<#{}>", id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
 /// Set the rules channel for a channel type
 #[poise::command(slash_command, rename = "rules-channel", guild_only)]
 pub async fn rules_channel(
@@ -122,9 +247,1128 @@ pub async fn rules_channel(
     Ok(())
 }
 
+/// Customize the wording of a bot message (omit the text to restore the default)
+#[poise::command(slash_command, guild_only)]
+pub async fn message(
+    ctx: Context<'_>,
+    #[description = "Message to customize"] kind: MessageTemplateChoice,
+    #[description = "New text. Placeholders: {user} {owner} {channel} {seconds}"]
+    text: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let template = kind.template();
+    // Slash command options can't contain line breaks, so accept a literal \n
+    let text = text.map(|t| t.replace("\\n", "\n")).filter(|t| !t.trim().is_empty());
+
+    if let Some(ref t) = text {
+        if t.chars().count() > MAX_TEMPLATE_LENGTH {
+            return Err(Error::custom(format!(
+                "Message text must be {} characters or less.",
+                MAX_TEMPLATE_LENGTH
+            )));
+        }
+    }
+
+    guild_config::set_message_template(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        template,
+        text.as_deref(),
+    )
+    .await?;
+
+    let embed = match text {
+        Some(ref t) => embeds::success_embed()
+            .title("Message Updated")
+            .description(format!("The {} now reads:\n\n{}", kind.label(), t)),
+        None => embeds::success_embed()
+            .title("Message Reset")
+            .description(format!(
+                "The {} has been restored to the default:\n\n{}",
+                kind.label(),
+                template.default_text()
+            )),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set the channel where ban appeals are posted for review (omit to disable appeals)
+#[poise::command(slash_command, rename = "appeal-channel", guild_only)]
+pub async fn appeal_channel(
+    ctx: Context<'_>,
+    #[description = "Mod-review channel for ban appeals"]
+    #[channel_types("Text")]
+    channel: Option<Channel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_appeal_channel(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        channel.as_ref().map(|c| c.id().get() as i64),
+    )
+    .await?;
+
+    let embed = match channel {
+        Some(channel) => embeds::success_embed()
+            .title("Appeal Channel Set")
+            .description(format!(
+                "Banned users can now appeal, and appeals will be posted to <#{}>",
+                channel.id()
+            )),
+        None => embeds::success_embed()
+            .title("Appeals Disabled")
+            .description("Banned users will no longer be offered an appeal."),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set the channel for moderator notifications (omit to disable)
+#[poise::command(slash_command, rename = "mod-log", guild_only)]
+pub async fn mod_log(
+    ctx: Context<'_>,
+    #[description = "Channel for moderator notifications"]
+    #[channel_types("Text")]
+    channel: Option<Channel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_mod_log_channel(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        channel.as_ref().map(|c| c.id().get() as i64),
+    )
+    .await?;
+
+    let embed = match channel {
+        Some(channel) => embeds::success_embed()
+            .title("Mod Log Set")
+            .description(format!("Moderator notifications will be posted to <#{}>", channel.id())),
+        None => embeds::success_embed()
+            .title("Mod Log Disabled")
+            .description("Moderator notifications have been turned off."),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle mod log alerts when a globally-muted user joins voice
+#[poise::command(slash_command, rename = "global-mute-alerts", guild_only)]
+pub async fn global_mute_alerts(
+    ctx: Context<'_>,
+    #[description = "Post to the mod log when a globally-muted user joins voice"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let config = guild_config::set_announce_global_mute_joins(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        enabled,
+    )
+    .await?;
+
+    let embed = if !enabled {
+        embeds::success_embed()
+            .title("Global Mute Alerts Disabled")
+            .description("Globally-muted users joining voice will no longer be announced.")
+    } else if config.mod_log_channel_id.is_some() {
+        embeds::success_embed()
+            .title("Global Mute Alerts Enabled")
+            .description("The mod log will be notified when a globally-muted user joins voice.")
+    } else {
+        embeds::warning_embed()
+            .title("Global Mute Alerts Enabled")
+            .description("Alerts are on, but no mod log channel is set. Use `/setup mod-log` to choose one.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle creating a paired text channel with each voice room
+#[poise::command(slash_command, rename = "discussion-channels", guild_only)]
+pub async fn discussion_channels(
+    ctx: Context<'_>,
+    #[description = "Create a linked text channel alongside each new voice room"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_create_discussion_channels(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        enabled,
+    )
+    .await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Discussion Channels Enabled")
+            .description("New voice rooms will get a linked text channel in the same category, deleted along with the room.")
+    } else {
+        embeds::success_embed()
+            .title("Discussion Channels Disabled")
+            .description("New voice rooms will no longer get a linked text channel. Existing ones are kept until their room closes.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle short-lived notices in a room's chat when its owner mutes or bans someone
+#[poise::command(slash_command, rename = "room-notices", guild_only)]
+pub async fn room_notices(
+    ctx: Context<'_>,
+    #[description = "Post a notice in the room when its owner mutes, unmutes, bans or unbans someone"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_announce_room_mod_actions(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        enabled,
+    )
+    .await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Room Notices Enabled")
+            .description(format!(
+                "Owner mutes and bans will be announced in the room's chat. Notices are deleted after {} seconds.",
+                ROOM_NOTICE_SECONDS
+            ))
+    } else {
+        embeds::success_embed()
+            .title("Room Notices Disabled")
+            .description("Owner mutes and bans will no longer be announced in the room.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Auto-lock rooms that get flooded with joins
+#[poise::command(slash_command, rename = "raid-lock", guild_only)]
+pub async fn raid_lock(
+    ctx: Context<'_>,
+    #[description = "Joins within 20 seconds that lock a room (leave empty to disable)"]
+    #[min = 3]
+    #[max = 50]
+    joins: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_raid_lock_joins(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        joins.map(|j| j as i32),
+    )
+    .await?;
+
+    let embed = match joins {
+        Some(joins) => embeds::success_embed()
+            .title("Raid Lock Enabled")
+            .description(format!(
+                "Rooms that get {} joins within {} seconds are locked to new joins, and their owner can unlock them from the notice.",
+                joins, RAID_LOCK_WINDOW_SECONDS
+            )),
+        None => embeds::success_embed()
+            .title("Raid Lock Disabled")
+            .description("Rooms will no longer be locked automatically."),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose what happens to a room when its owner hops to another managed channel
+#[poise::command(slash_command, rename = "owner-hop", guild_only)]
+pub async fn owner_hop(
+    ctx: Context<'_>,
+    #[description = "What to do with the room the owner left"] policy: OwnerHopPolicyChoice,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let policy = policy.policy();
+    guild_config::set_owner_hop_policy(&ctx.data().pool, guild_id.get() as i64, policy).await?;
+
+    let hold_minutes = OWNER_HOP_HOLD_SECONDS / 60;
+    let description = match policy {
+        OwnerHopPolicy::Release => "Rooms are handed over (or closed) as soon as their owner hops to another room.".to_string(),
+        OwnerHopPolicy::Hold => format!(
+            "Rooms are held for {} minutes when their owner hops to another room, then handed over if they don't come back.",
+            hold_minutes
+        ),
+        OwnerHopPolicy::Prompt => format!(
+            "Rooms are held for {} minutes when their owner hops to another room, and the owner gets a DM with a button to release it early.",
+            hold_minutes
+        ),
+    };
+
+    let embed = embeds::success_embed()
+        .title("Owner Hop Policy Updated")
+        .description(description);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Keep emptied rooms around for a while before deleting them
+#[poise::command(slash_command, rename = "empty-grace", guild_only)]
+pub async fn empty_grace(
+    ctx: Context<'_>,
+    #[description = "Seconds to keep an empty room before deleting it (0 = delete right away)"]
+    #[max = 600]
+    seconds: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let seconds = seconds.min(MAX_EMPTY_CHANNEL_GRACE_SECONDS);
+    guild_config::set_empty_channel_grace_seconds(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        seconds as i32,
+    )
+    .await?;
+
+    let description = if seconds == 0 {
+        "Rooms are deleted as soon as the last person leaves.".to_string()
+    } else {
+        format!(
+            "Rooms are kept for {} seconds after the last person leaves. Anyone who joins in that time keeps the room going.",
+            seconds
+        )
+    };
+
+    let embed = embeds::success_embed()
+        .title("Empty Room Grace Updated")
+        .description(description);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Make users wait after their room is deleted before they can make another
+#[poise::command(slash_command, rename = "recreate-cooldown", guild_only)]
+pub async fn recreate_cooldown(
+    ctx: Context<'_>,
+    #[description = "Seconds a user waits after their room is deleted before making another (0 = off)"]
+    #[max = 1800]
+    seconds: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let seconds = seconds.min(MAX_RECREATE_COOLDOWN_SECONDS);
+    guild_config::set_recreate_cooldown_seconds(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        seconds as i32,
+    )
+    .await?;
+
+    let description = if seconds == 0 {
+        "Users can create a new room as soon as their last one is deleted.".to_string()
+    } else {
+        format!(
+            "Users wait {} seconds after their room is deleted before they can create another. \
+            Anyone who joins a Join-to-Create channel sooner is moved out and told how long \
+            is left.",
+            seconds
+        )
+    };
+
+    let embed = embeds::success_embed()
+        .title("Recreate Cooldown Updated")
+        .description(description);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set the longest name owners can give their rooms
+#[poise::command(slash_command, rename = "max-name-length", guild_only)]
+pub async fn max_name_length(
+    ctx: Context<'_>,
+    #[description = "Maximum characters in a room name (Discord allows up to 100)"]
+    #[min = 2]
+    #[max = 100]
+    length: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let length = (length as usize).min(MAX_CHANNEL_NAME_LENGTH);
+    guild_config::set_max_name_length(&ctx.data().pool, guild_id.get() as i64, length as i32)
+        .await?;
+
+    let embed = embeds::success_embed()
+        .title("Name Length Updated")
+        .description(format!(
+            "Room names can now be at most {} characters. Existing names are left as they are.",
+            length
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set a role given to members while they own a room (omit to stop assigning one)
+#[poise::command(slash_command, rename = "owner-role", guild_only)]
+pub async fn owner_role(
+    ctx: Context<'_>,
+    #[description = "Role for current room owners"] role: Option<Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_owner_role(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        role.as_ref().map(|r| r.id.get() as i64),
+    )
+    .await?;
+
+    let embed = match role {
+        Some(role) => embeds::success_embed()
+            .title("Owner Role Set")
+            .description(format!(
+                "New room owners get <@&{}> until they lose their room. \
+                The bot's own role must be above it for this to work.",
+                role.id
+            )),
+        None => embeds::success_embed()
+            .title("Owner Role Disabled")
+            .description("Room owners will no longer be given a role."),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose whether owner mutes outlive the room they were given in
+#[poise::command(slash_command, rename = "keep-mutes", guild_only)]
+pub async fn keep_mutes(
+    ctx: Context<'_>,
+    #[description = "Keep people muted after the room they were muted in is deleted"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_keep_mutes_on_channel_delete(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        enabled,
+    )
+    .await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Mutes Kept After Deletion")
+            .description(
+                "Owner mutes stay active after their room is deleted, keeping the user server-muted \
+                until a moderator lifts it or `/admin forgive` clears it.",
+            )
+    } else {
+        embeds::success_embed()
+            .title("Mutes Cleared On Deletion")
+            .description("Owner mutes end when their room is deleted. Admin and global mutes are unaffected.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set tags applied to new rooms whose owner has no saved tag preference
+#[poise::command(slash_command, rename = "default", guild_only)]
+pub async fn default_tags(
+    ctx: Context<'_>,
+    #[description = "Channel type"] channel_type: ChannelTypeChoice,
+    #[description = "Comma-separated tags, e.g. \"Gaming, Chill\" (leave empty to clear)"]
+    tags: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let is_casual = matches!(channel_type, ChannelTypeChoice::Casual);
+    let case = ctx.data().settings.tag_case;
+    let config = guild_config::get_or_create(&ctx.data().pool, guild_id.get() as i64).await?;
+    let available = available_tags(is_casual, config.emoji_tags(is_casual));
+
+    let raw: Vec<&str> = tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    let tags = normalize_tags(&raw, case).map_err(Error::InvalidOperation)?;
+
+    if let Some(unknown) = tags.iter().find(|t| !is_available_tag(t, &available, case)) {
+        return Err(Error::InvalidOperation(format!(
+            "`{}` isn't an available tag. Choose from: {}",
+            unknown,
+            available.join(", ")
+        )));
+    }
+
+    guild_config::set_default_tags(&ctx.data().pool, guild_id.get() as i64, is_casual, &tags)
+        .await?;
+
+    let kind = if is_casual { "casual" } else { "debate" };
+    let embed = if tags.is_empty() {
+        embeds::success_embed()
+            .title("Default Tags Cleared")
+            .description(format!(
+                "New {} rooms start without tags unless the owner has saved some.",
+                kind
+            ))
+    } else {
+        embeds::success_embed()
+            .title("Default Tags Set")
+            .description(format!(
+                "New {} rooms start tagged **{}** unless the owner has saved their own tags.",
+                kind,
+                tags.join(", ")
+            ))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose whether first-time room owners get a DM explaining the room controls
+#[poise::command(slash_command, rename = "welcome-dm", guild_only)]
+pub async fn welcome_dm(
+    ctx: Context<'_>,
+    #[description = "DM people how their room works the first time they create one"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_send_welcome_dm(&ctx.data().pool, guild_id.get() as i64, enabled).await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Welcome DM Enabled")
+            .description(
+                "People creating their first room here get a one-time DM about the owner panel, \
+                muting, banning and renaming. Customize it with `/setup message`.",
+            )
+    } else {
+        embeds::success_embed()
+            .title("Welcome DM Disabled")
+            .description("First-time room owners will no longer be sent a DM.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose where spam alerts with ban/ignore buttons are sent
+#[poise::command(slash_command, rename = "prompt", guild_only)]
+pub async fn spam_prompt(
+    ctx: Context<'_>,
+    #[description = "Where to send spam alerts"] target: SpamPromptTargetChoice,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let target = target.target();
+    let config =
+        guild_config::set_spam_prompt_target(&ctx.data().pool, guild_id.get() as i64, target)
+            .await?;
+
+    let description = match target {
+        SpamPromptTarget::Channel => "Spam alerts are posted in the room's chat for the owner to act on.",
+        SpamPromptTarget::OwnerDm => {
+            "Spam alerts are DMed to the room owner. If their DMs are closed, the alert is posted in the room's chat."
+        }
+        SpamPromptTarget::ModChannel => {
+            "Spam alerts are posted in the mod log channel, where the owner or anyone with Manage Channels can act on them."
+        }
+    };
+
+    let mut embed = embeds::success_embed()
+        .title("Spam Alerts Updated")
+        .description(description);
+    if target == SpamPromptTarget::ModChannel && config.mod_log_channel_id.is_none() {
+        embed = embed.field(
+            "No mod log channel",
+            "Set one with `/setup mod-log`; until then alerts are posted in the room's chat.",
+            false,
+        );
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Add emoji to the tags offered for a channel type
+#[poise::command(slash_command, rename = "emoji", guild_only)]
+pub async fn emoji_tags(
+    ctx: Context<'_>,
+    #[description = "Channel type"] channel_type: ChannelTypeChoice,
+    #[description = "Space-separated emoji from this server or unicode emoji (leave empty to clear)"]
+    emojis: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let mut tags: Vec<EmojiTag> = Vec::new();
+    for raw in emojis.as_deref().unwrap_or_default().split_whitespace() {
+        let tag = EmojiTag::parse(raw).ok_or_else(|| {
+            Error::InvalidOperation(format!("`{}` isn't an emoji", raw))
+        })?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    if tags.len() > MAX_EMOJI_TAGS {
+        return Err(Error::InvalidOperation(format!(
+            "At most {} emoji tags per channel type, got {}",
+            MAX_EMOJI_TAGS,
+            tags.len()
+        )));
+    }
+
+    // The bot can only show custom emoji from servers it's in, so keep them to this one
+    if tags.iter().any(|t| matches!(t, EmojiTag::Custom { .. })) {
+        let guild_emojis = guild_id.emojis(ctx.http()).await?;
+        let foreign = tags.iter().find(|t| match t {
+            EmojiTag::Custom { id, .. } => !guild_emojis.iter().any(|e| e.id == *id),
+            EmojiTag::Unicode(_) => false,
+        });
+        if let Some(foreign) = foreign {
+            return Err(Error::InvalidOperation(format!(
+                "{} isn't an emoji from this server",
+                foreign.as_tag()
+            )));
+        }
+    }
+
+    let is_casual = matches!(channel_type, ChannelTypeChoice::Casual);
+    let tags: Vec<String> = tags.iter().map(EmojiTag::as_tag).collect();
+    guild_config::set_emoji_tags(&ctx.data().pool, guild_id.get() as i64, is_casual, &tags)
+        .await?;
+
+    let kind = if is_casual { "casual" } else { "debate" };
+    let embed = if tags.is_empty() {
+        embeds::success_embed()
+            .title("Emoji Tags Cleared")
+            .description(format!("New {} rooms only offer the built-in tags.", kind))
+    } else {
+        embeds::success_embed()
+            .title("Emoji Tags Updated")
+            .description(format!(
+                "New {} rooms also offer these tags: {}",
+                kind,
+                tags.join(" ")
+            ))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose whether rooms stay up while their owner is streaming or on camera
+#[poise::command(slash_command, rename = "keep-streaming", guild_only)]
+pub async fn keep_streaming(
+    ctx: Context<'_>,
+    #[description = "Keep a room instead of deleting it while its owner is streaming or on camera"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_keep_rooms_while_streaming(&ctx.data().pool, guild_id.get() as i64, enabled)
+        .await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Streaming Rooms Kept")
+            .description(
+                "When an owner leaves their room while streaming or on camera, the room is held \
+                for them instead of deleted, for as long as the stream or camera stays on.",
+            )
+    } else {
+        embeds::success_embed()
+            .title("Streaming Rooms Not Kept")
+            .description("Rooms are deleted when their owner leaves, whether or not they're streaming.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set the permissions a role gets in new rooms (omit the role to list, omit both lists to clear)
+#[poise::command(slash_command, rename = "room-permissions", guild_only)]
+pub async fn room_permissions(
+    ctx: Context<'_>,
+    #[description = "Role to set permissions for (omit to list the current ones)"]
+    role: Option<Role>,
+    #[description = "Permissions to allow, e.g. \"connect, speak\""] allow: Option<String>,
+    #[description = "Permissions to deny, e.g. \"view_channel\""] deny: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let pool = &ctx.data().pool;
+
+    let Some(role) = role else {
+        let templates = room_permission::list(pool, guild_id.get() as i64).await?;
+        let description = if templates.is_empty() {
+            "New rooms only get the owner's permissions on top of their category's.".to_string()
+        } else {
+            templates
+                .iter()
+                .map(|t| {
+                    let allow = Permissions::from_bits_truncate(t.allow as u64);
+                    let deny = Permissions::from_bits_truncate(t.deny as u64);
+                    format!(
+                        "<@&{}>: allow {}; deny {}",
+                        t.role_id,
+                        permissions::format_permissions(allow),
+                        permissions::format_permissions(deny)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let embed = embeds::info_embed()
+            .title("Room Permissions")
+            .description(description);
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    };
+
+    if allow.is_none() && deny.is_none() {
+        let removed =
+            room_permission::remove(pool, guild_id.get() as i64, role.id.get() as i64).await?;
+        let embed = if removed {
+            embeds::success_embed()
+                .title("Room Permissions Removed")
+                .description(format!("New rooms no longer get an overwrite for <@&{}>.", role.id))
+        } else {
+            embeds::secondary_embed()
+                .title("Nothing To Remove")
+                .description(format!("New rooms don't set permissions for <@&{}>.", role.id))
+        };
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let allow = permissions::parse_room_permissions(allow.as_deref().unwrap_or_default())
+        .map_err(Error::InvalidOperation)?;
+    let deny = permissions::parse_room_permissions(deny.as_deref().unwrap_or_default())
+        .map_err(Error::InvalidOperation)?;
+    if allow.intersects(deny) {
+        return Err(Error::InvalidOperation(format!(
+            "A permission can't be both allowed and denied: {}",
+            permissions::format_permissions(allow & deny)
+        )));
+    }
+
+    let existing = room_permission::list(pool, guild_id.get() as i64).await?;
+    let is_new = existing.iter().all(|t| t.role_id != role.id.get() as i64);
+    if is_new && existing.len() >= MAX_ROOM_PERMISSION_ROLES {
+        return Err(Error::InvalidOperation(format!(
+            "New rooms can set permissions for at most {} roles. Remove one first.",
+            MAX_ROOM_PERMISSION_ROLES
+        )));
+    }
+
+    room_permission::set(
+        pool,
+        guild_id.get() as i64,
+        role.id.get() as i64,
+        allow.bits() as i64,
+        deny.bits() as i64,
+    )
+    .await?;
+
+    let embed = embeds::success_embed()
+        .title("Room Permissions Set")
+        .description(format!(
+            "New rooms give <@&{}> these permissions. Existing rooms are unchanged.",
+            role.id
+        ))
+        .field("Allow", permissions::format_permissions(allow), true)
+        .field("Deny", permissions::format_permissions(deny), true);
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set a channel where new rooms are announced (omit to disable)
+#[poise::command(slash_command, rename = "new-rooms", guild_only)]
+pub async fn room_announcements(
+    ctx: Context<'_>,
+    #[description = "Channel that gets a post whenever a new room is created"]
+    #[channel_types("Text", "News")]
+    channel: Option<Channel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_channel_created_notify_channel(
+        &ctx.data().pool,
+        guild_id.get() as i64,
+        channel.as_ref().map(|c| c.id().get() as i64),
+    )
+    .await?;
+
+    let embed = match channel {
+        Some(channel) => embeds::success_embed()
+            .title("Room Announcements On")
+            .description(format!(
+                "New rooms will be announced in <#{}> with their owner and a link to join.",
+                channel.id()
+            )),
+        None => embeds::success_embed()
+            .title("Room Announcements Off")
+            .description("New rooms will no longer be announced."),
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Set how sensitive the spam detector is (omit everything to go back to the defaults)
+#[poise::command(slash_command, rename = "thresholds", guild_only)]
+pub async fn spam_thresholds(
+    ctx: Context<'_>,
+    #[description = "Joins/leaves in the window before the owner is asked to act"]
+    #[min = 2]
+    #[max = 100]
+    prompt_at: Option<u32>,
+    #[description = "Joins/leaves in the window before the user is timed out"]
+    #[min = 3]
+    #[max = 200]
+    timeout_at: Option<u32>,
+    #[description = "Seconds of activity counted"]
+    #[min = 10]
+    #[max = 600]
+    window: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let pool = &ctx.data().pool;
+    let defaults = ctx.data().settings.spam_thresholds();
+
+    // Anything not given keeps its current value; giving nothing resets all three
+    let reset = prompt_at.is_none() && timeout_at.is_none() && window.is_none();
+    let (prompt_at, timeout_at, window) = if reset {
+        (None, None, None)
+    } else {
+        let current = guild_config::get_or_create(pool, guild_id.get() as i64).await?;
+        (
+            prompt_at.map(|n| n as i32).or(current.spam_prompt_threshold),
+            timeout_at.map(|n| n as i32).or(current.spam_timeout_threshold),
+            window.map(|n| n as i32).or(current.spam_window_seconds),
+        )
+    };
+
+    let thresholds = SpamThresholds {
+        prompt: prompt_at.map_or(defaults.prompt, |n| n as u32),
+        timeout: timeout_at.map_or(defaults.timeout, |n| n as u32),
+        window_seconds: window.map_or(defaults.window_seconds, |n| n as u64),
+    };
+    if let Err(reason) = thresholds.validate() {
+        return Err(Error::InvalidOperation(reason));
+    }
+
+    guild_config::set_spam_thresholds(pool, guild_id.get() as i64, prompt_at, timeout_at, window)
+        .await?;
+
+    let embed = embeds::success_embed()
+        .title(if reset { "Spam Detection Reset" } else { "Spam Detection Updated" })
+        .description(format!(
+            "Within any {} seconds in a room, owners are asked to act after {} joins/leaves \
+            and the user is timed out after {}.",
+            thresholds.window_seconds, thresholds.prompt, thresholds.timeout
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// This server's own banned words for room names
+#[poise::command(
+    slash_command,
+    subcommands("banword_add", "banword_remove", "banword_list"),
+    guild_only
+)]
+pub async fn banword(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use one of the subcommands: `/setup banword add`, `/setup banword remove`, `/setup banword list`").await?;
+    Ok(())
+}
+
+/// Stop rooms from being named with a word, on top of the built-in filter
+#[poise::command(slash_command, rename = "add", guild_only)]
+pub async fn banword_add(
+    ctx: Context<'_>,
+    #[description = "Word to block in room names"] word: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+    let pool = &ctx.data().pool;
+
+    let word = normalize_banned_word(&word).map_err(Error::InvalidOperation)?;
+    let existing = banned_word::list(pool, guild_id.get() as i64).await?;
+    if existing.len() >= MAX_GUILD_BANNED_WORDS && !existing.contains(&word) {
+        return Err(Error::InvalidOperation(format!(
+            "This server already has the maximum of {} banned words. Remove one first.",
+            MAX_GUILD_BANNED_WORDS
+        )));
+    }
+
+    let added =
+        banned_word::add(pool, guild_id.get() as i64, &word, ctx.author().id.get() as i64).await?;
+
+    let embed = if added {
+        embeds::success_embed()
+            .title("Word Banned")
+            .description(format!(
+                "New room names containing `{}` will be rejected. Existing names are left as they are.",
+                word
+            ))
+    } else {
+        embeds::secondary_embed()
+            .title("Already Banned")
+            .description(format!("`{}` is already on this server's list.", word))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Allow a word this server banned again (the built-in filter still applies)
+#[poise::command(slash_command, rename = "remove", guild_only)]
+pub async fn banword_remove(
+    ctx: Context<'_>,
+    #[description = "Word to allow again"] word: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let word = word.trim().to_lowercase();
+    let removed = banned_word::remove(&ctx.data().pool, guild_id.get() as i64, &word).await?;
+
+    let embed = if removed {
+        embeds::success_embed()
+            .title("Word Allowed")
+            .description(format!("`{}` is no longer on this server's list.", word))
+    } else {
+        embeds::secondary_embed()
+            .title("Not Banned")
+            .description(format!("`{}` isn't on this server's list.", word))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// List the words this server has banned from room names
+#[poise::command(slash_command, rename = "list", guild_only)]
+pub async fn banword_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    let words = banned_word::list(&ctx.data().pool, guild_id.get() as i64).await?;
+
+    let embed = if words.is_empty() {
+        embeds::secondary_embed()
+            .title("No Banned Words")
+            .description("Room names are only checked against the built-in filter.")
+    } else {
+        let listed: Vec<String> = words.iter().map(|w| format!("`{}`", w)).collect();
+        embeds::info_embed()
+            .title(format!("Banned Words ({}/{})", words.len(), MAX_GUILD_BANNED_WORDS))
+            .description(format!(
+                "Checked on top of the built-in filter:\n{}",
+                listed.join(", ")
+            ))
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Choose whether new rooms need a tag before they count as configured
+#[poise::command(slash_command, rename = "require", guild_only)]
+pub async fn require_tags(
+    ctx: Context<'_>,
+    #[description = "Delete new rooms that get a name but no tags before the naming deadline"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(Error::custom("Not in a guild"))?;
+
+    guild_config::set_require_tags(&ctx.data().pool, guild_id.get() as i64, enabled).await?;
+
+    let embed = if enabled {
+        embeds::success_embed()
+            .title("Tags Required")
+            .description(format!(
+                "New rooms need a name and at least one tag. Owners who only set a name get \
+                another {} seconds to pick a tag before the room is deleted.",
+                VC_NAMING_DEADLINE_SECONDS
+            ))
+    } else {
+        embeds::success_embed()
+            .title("Tags Optional")
+            .description("New rooms count as configured once they have a name. Tags are optional.")
+    };
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// Owner hop policy choice for commands
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum OwnerHopPolicyChoice {
+    #[name = "Release right away"]
+    Release,
+    #[name = "Hold for the owner"]
+    Hold,
+    #[name = "Hold and DM the owner"]
+    Prompt,
+}
+
+impl OwnerHopPolicyChoice {
+    fn policy(&self) -> OwnerHopPolicy {
+        match self {
+            OwnerHopPolicyChoice::Release => OwnerHopPolicy::Release,
+            OwnerHopPolicyChoice::Hold => OwnerHopPolicy::Hold,
+            OwnerHopPolicyChoice::Prompt => OwnerHopPolicy::Prompt,
+        }
+    }
+}
+
+/// Spam prompt target choice for commands
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum SpamPromptTargetChoice {
+    #[name = "Room chat"]
+    Channel,
+    #[name = "DM the owner"]
+    OwnerDm,
+    #[name = "Mod log channel"]
+    ModChannel,
+}
+
+impl SpamPromptTargetChoice {
+    fn target(&self) -> SpamPromptTarget {
+        match self {
+            SpamPromptTargetChoice::Channel => SpamPromptTarget::Channel,
+            SpamPromptTargetChoice::OwnerDm => SpamPromptTarget::OwnerDm,
+            SpamPromptTargetChoice::ModChannel => SpamPromptTarget::ModChannel,
+        }
+    }
+}
+
+/// Customizable message choice for commands
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum MessageTemplateChoice {
+    #[name = "Spam alert"]
+    SpamPrompt,
+    #[name = "Naming deadline DM"]
+    DeadlineDm,
+    #[name = "Configure prompt"]
+    NamingPrompt,
+    #[name = "First room welcome DM"]
+    WelcomeDm,
+}
+
+impl MessageTemplateChoice {
+    fn template(&self) -> MessageTemplate {
+        match self {
+            MessageTemplateChoice::SpamPrompt => MessageTemplate::SpamPrompt,
+            MessageTemplateChoice::DeadlineDm => MessageTemplate::DeadlineDm,
+            MessageTemplateChoice::NamingPrompt => MessageTemplate::NamingPrompt,
+            MessageTemplateChoice::WelcomeDm => MessageTemplate::WelcomeDm,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            MessageTemplateChoice::SpamPrompt => "spam alert",
+            MessageTemplateChoice::DeadlineDm => "naming deadline DM",
+            MessageTemplateChoice::NamingPrompt => "configure prompt",
+            MessageTemplateChoice::WelcomeDm => "first room welcome DM",
+        }
+    }
+}
+
 /// Channel type choice for commands
 #[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
 pub enum ChannelTypeChoice {
     Casual,
     Debate,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Discord rejects the whole command set if any command or group has more than 25 options
+    const MAX_SUBCOMMANDS: usize = 25;
+
+    #[test]
+    fn setup_fits_discords_option_limit() {
+        let command = setup();
+        assert!(command.subcommands.len() <= MAX_SUBCOMMANDS, "{}", command.subcommands.len());
+
+        for group in &command.subcommands {
+            assert!(group.subcommands.len() <= MAX_SUBCOMMANDS, "/setup {}", group.name);
+            // Groups can only hold subcommands, not further groups
+            for leaf in &group.subcommands {
+                assert!(leaf.subcommands.is_empty(), "/setup {} {}", group.name, leaf.name);
+            }
+        }
+    }
+}
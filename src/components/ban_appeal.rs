@@ -0,0 +1,390 @@
+use std::sync::Arc;
+
+use serenity::all::{
+    ActionRowComponent, ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow,
+    CreateButton, CreateEmbed, CreateInputText, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreateModal, GuildId, InputTextStyle,
+    ModalInteraction, UserId,
+};
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::timeouts::{APPEAL_RATE_MAX, APPEAL_RATE_WINDOW_SECONDS};
+use crate::db::models::{AppealStatus, BanAppeal, BanRecord};
+use crate::db::queries::{appeal, ban, guild_config};
+use crate::handlers::interaction::{defer_then_edit, send_component_error, send_modal_error, DeferMode};
+use crate::services::moderation::ban_service;
+use crate::utils::permissions;
+
+const MAX_APPEAL_LENGTH: u16 = 1000;
+
+/// Route appeal buttons: appeal_open_{ban_id}, appeal_approve_{appeal_id}, appeal_deny_{appeal_id}
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let parts: Vec<&str> = custom_id.split('_').collect();
+    if parts.len() < 3 {
+        send_component_error(ctx, component, "Invalid button state").await?;
+        return Ok(());
+    }
+
+    let id = match Uuid::parse_str(parts[2]) {
+        Ok(id) => id,
+        Err(_) => {
+            send_component_error(ctx, component, "Invalid appeal ID").await?;
+            return Ok(());
+        }
+    };
+
+    match parts[1] {
+        "open" => open_appeal(ctx, data, component, id).await,
+        "approve" => review_appeal(ctx, data, component, id, AppealStatus::Approved).await,
+        "deny" => review_appeal(ctx, data, component, id, AppealStatus::Denied).await,
+        _ => {
+            send_component_error(ctx, component, "Unknown action").await?;
+            Ok(())
+        }
+    }
+}
+
+/// Check whether a ban can still be appealed by this user
+/// Returns the ban record, or the reason the appeal can't be made
+async fn check_appealable(
+    data: &Arc<Data>,
+    ban_id: Uuid,
+    user_id: UserId,
+) -> Result<Result<BanRecord, String>, Error> {
+    let record = match ban::get_by_id(&data.pool, ban_id).await? {
+        Some(record) if record.banned_user_id == user_id.get() as i64 => record,
+        _ => return Ok(Err("This ban no longer exists — you may already have been unbanned.".to_string())),
+    };
+
+    if appeal::get_by_ban(&data.pool, ban_id).await?.is_some() {
+        return Ok(Err("You have already appealed this ban.".to_string()));
+    }
+
+    let recent = appeal::count_recent_by_user(
+        &data.pool,
+        user_id.get() as i64,
+        APPEAL_RATE_WINDOW_SECONDS,
+    )
+    .await?;
+    if recent >= APPEAL_RATE_MAX {
+        return Ok(Err(format!(
+            "You can only submit {} appeals per day. Try again later.",
+            APPEAL_RATE_MAX
+        )));
+    }
+
+    Ok(Ok(record))
+}
+
+/// Open the appeal modal for a banned user
+async fn open_appeal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+    ban_id: Uuid,
+) -> Result<(), Error> {
+    if let Err(reason) = check_appealable(data, ban_id, component.user.id).await? {
+        send_component_error(ctx, component, &reason).await?;
+        return Ok(());
+    }
+
+    let modal = CreateModal::new(format!("appeal_modal_{}", ban_id), "Appeal Ban")
+        .components(vec![CreateActionRow::InputText(
+            CreateInputText::new(InputTextStyle::Paragraph, "Why should you be unbanned?", "appeal_reason")
+                .required(true)
+                .min_length(10)
+                .max_length(MAX_APPEAL_LENGTH),
+        )]);
+
+    component
+        .create_response(ctx, CreateInteractionResponse::Modal(modal))
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the appeal modal submission — record the appeal and post it for review
+pub async fn handle_modal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    modal: &ModalInteraction,
+) -> Result<(), Error> {
+    let custom_id = &modal.data.custom_id;
+
+    // Parse custom_id: appeal_modal_{ban_id}
+    let parts: Vec<&str> = custom_id.split('_').collect();
+    let ban_id = match parts.get(2).and_then(|id| Uuid::parse_str(id).ok()) {
+        Some(id) => id,
+        None => {
+            send_modal_error(ctx, modal, "Invalid modal state").await?;
+            return Ok(());
+        }
+    };
+
+    let record = match check_appealable(data, ban_id, modal.user.id).await? {
+        Ok(record) => record,
+        Err(reason) => {
+            send_modal_error(ctx, modal, &reason).await?;
+            return Ok(());
+        }
+    };
+
+    let review_channel = guild_config::get(&data.pool, record.guild_id)
+        .await?
+        .and_then(|config| config.appeal_channel_id);
+    let review_channel = match review_channel {
+        Some(id) => ChannelId::new(id as u64),
+        None => {
+            send_modal_error(ctx, modal, "This server is no longer accepting ban appeals.").await?;
+            return Ok(());
+        }
+    };
+
+    let reason = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| {
+            if let ActionRowComponent::InputText(input) = component {
+                if input.custom_id == "appeal_reason" {
+                    return input.value.clone();
+                }
+            }
+            None
+        })
+        .unwrap_or_default();
+    let reason = reason.trim();
+
+    if reason.is_empty() {
+        send_modal_error(ctx, modal, "Your appeal can't be empty.").await?;
+        return Ok(());
+    }
+
+    let appeal = match appeal::create(
+        &data.pool,
+        record.id,
+        record.guild_id,
+        record.channel_id,
+        record.banned_user_id,
+        reason,
+    )
+    .await?
+    {
+        Some(appeal) => appeal,
+        None => {
+            send_modal_error(ctx, modal, "You have already appealed this ban.").await?;
+            return Ok(());
+        }
+    };
+
+    // An appeal the moderators never saw doesn't count, so the user can try again
+    if let Err(e) = post_for_review(ctx, review_channel, &record, &appeal).await {
+        error!("Failed to post appeal {} for review: {:?}", appeal.id, e);
+        if let Err(e) = appeal::delete(&data.pool, appeal.id).await {
+            error!("Failed to drop undelivered appeal {}: {:?}", appeal.id, e);
+        }
+        send_modal_error(
+            ctx,
+            modal,
+            "Your appeal couldn't be delivered to the moderators. Please try again later.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    info!(
+        "User {} appealed ban {} on channel {}",
+        record.banned_user_id, record.id, record.channel_id
+    );
+
+    let embed = embeds::success_embed()
+        .title("Appeal Submitted")
+        .description("Your appeal has been sent to the moderators. You'll get a DM once it's reviewed.");
+
+    modal
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Post an appeal to the mod-review channel with Approve/Deny buttons
+async fn post_for_review(
+    ctx: &Context,
+    review_channel: ChannelId,
+    record: &BanRecord,
+    appeal: &BanAppeal,
+) -> Result<(), Error> {
+    let embed = embeds::info_embed()
+        .title("Ban Appeal")
+        .field("User", format!("<@{}>", record.banned_user_id), true)
+        .field("Channel", format!("<#{}>", record.channel_id), true)
+        .field("Banned By", format!("<@{}>", record.banned_by_user_id), true)
+        .field(
+            "Ban Reason",
+            record.reason.as_deref().unwrap_or("No reason given"),
+            false,
+        )
+        .field("Appeal", &appeal.reason, false);
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("appeal_approve_{}", appeal.id))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("appeal_deny_{}", appeal.id))
+            .label("Deny")
+            .style(ButtonStyle::Danger),
+    ]);
+
+    review_channel
+        .send_message(ctx, CreateMessage::new().embed(embed).components(vec![buttons]))
+        .await?;
+
+    Ok(())
+}
+
+/// Approve or deny an appeal from the mod-review channel
+async fn review_appeal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+    appeal_id: Uuid,
+    decision: AppealStatus,
+) -> Result<(), Error> {
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This command only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if !permissions::can_moderate(ctx, guild_id, component.user.id).await {
+        send_component_error(ctx, component, "Only moderators can review appeals").await?;
+        return Ok(());
+    }
+
+    defer_then_edit(
+        ctx,
+        component,
+        DeferMode::UpdateMessage,
+        resolve_appeal(ctx, data, guild_id, component.user.id, appeal_id, decision),
+    )
+    .await
+}
+
+async fn resolve_appeal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    reviewer_id: UserId,
+    appeal_id: Uuid,
+    decision: AppealStatus,
+) -> CreateEmbed {
+    let appeal = match appeal::resolve(&data.pool, appeal_id, decision, reviewer_id.get() as i64).await {
+        Ok(Some(appeal)) if appeal.guild_id == guild_id.get() as i64 => appeal,
+        Ok(_) => {
+            return embeds::warning_embed()
+                .title("Already Reviewed")
+                .description("This appeal has already been reviewed.");
+        }
+        Err(e) => {
+            error!("Failed to resolve appeal {}: {:?}", appeal_id, e);
+            return embeds::error_embed()
+                .title("Error")
+                .description(format!("Failed to resolve appeal: {}", e));
+        }
+    };
+
+    let channel_id = ChannelId::new(appeal.channel_id as u64);
+    let user_id = UserId::new(appeal.user_id as u64);
+
+    let mut description = format!(
+        "Appeal from <@{}> for <#{}> was **{}** by <@{}>.",
+        user_id,
+        channel_id,
+        decision.as_str(),
+        reviewer_id
+    );
+
+    if decision == AppealStatus::Approved {
+        if let Err(e) = ban_service::unban_user(ctx, data, channel_id, user_id).await {
+            // The user isn't told it was approved while they're still banned
+            error!("Failed to unban user {} after approved appeal: {:?}", user_id, e);
+            description.push_str(&format!(
+                "\n\nThe unban failed, so the user wasn't notified: {}\nThe room owner can still lift it with `/vcunban`.",
+                e
+            ));
+            return embeds::error_embed()
+                .title("Unban Failed")
+                .description(description)
+                .field("Appeal", appeal.reason, false);
+        }
+    }
+
+    notify_decision(ctx, user_id, channel_id, decision).await;
+
+    info!(
+        "Appeal {} {} by {}",
+        appeal_id,
+        decision.as_str(),
+        reviewer_id
+    );
+
+    let embed = if decision == AppealStatus::Approved {
+        embeds::success_embed().title("Appeal Approved")
+    } else {
+        embeds::secondary_embed().title("Appeal Denied")
+    };
+
+    embed
+        .description(description)
+        .field("Appeal", appeal.reason, false)
+}
+
+/// DM the user the outcome of their appeal - fail silently if DMs are closed
+async fn notify_decision(
+    ctx: &Context,
+    user_id: UserId,
+    channel_id: ChannelId,
+    decision: AppealStatus,
+) {
+    let embed = if decision == AppealStatus::Approved {
+        embeds::success_embed()
+            .title("Appeal Approved")
+            .description(format!("Your ban from <#{}> has been lifted.", channel_id))
+    } else {
+        embeds::secondary_embed()
+            .title("Appeal Denied")
+            .description(format!("Your appeal for <#{}> was denied.", channel_id))
+    };
+
+    match user_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(ctx, CreateMessage::new().embed(embed)).await {
+                debug!("Could not DM user {} about their appeal: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for user {}: {:?}", user_id, e);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use serenity::all::{
+    ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow,
+    CreateEmbed, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    EditInteractionResponse, UserId,
+};
+use tracing::error;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::db::models::BanRecord;
+use crate::db::queries::ban;
+use crate::handlers::interaction::{defer_then_edit_response, send_component_error, DeferMode};
+use crate::services::moderation::ban_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
+use crate::utils::formatting::truncate;
+
+/// Discord's limit on options in one select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+/// Reasons are cut short so a full list stays inside the embed description limit
+const MAX_LISTED_REASON_LENGTH: usize = 100;
+
+/// Build the ban list for a room, with a menu to unban from it when anyone is banned
+pub fn build(
+    ctx: &Context,
+    channel_id: ChannelId,
+    bans: &[BanRecord],
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+    if bans.is_empty() {
+        let embed = embeds::secondary_embed()
+            .title("No Bans")
+            .description("Nobody is banned from your room.");
+        return (embed, vec![]);
+    }
+
+    let shown = &bans[..bans.len().min(MAX_SELECT_OPTIONS)];
+    let mut description: String = shown
+        .iter()
+        .map(|record| {
+            let mut line = format!(
+                "<@{}> - banned <t:{}:R>",
+                record.banned_user_id,
+                record.banned_at.timestamp()
+            );
+            if let Some(ref reason) = record.reason {
+                line.push_str(&format!(": {}", truncate(reason, MAX_LISTED_REASON_LENGTH)));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if bans.len() > shown.len() {
+        description.push_str(&format!(
+            "\n\n...and {} more. Use `/vcunban` for anyone not listed.",
+            bans.len() - shown.len()
+        ));
+    }
+
+    let options: Vec<CreateSelectMenuOption> = shown
+        .iter()
+        .map(|record| {
+            let user_id = UserId::new(record.banned_user_id as u64);
+            let name = ctx
+                .cache
+                .user(user_id)
+                .map(|user| user.name.clone())
+                .unwrap_or_else(|| user_id.to_string());
+            CreateSelectMenuOption::new(name, user_id.to_string())
+        })
+        .collect();
+    let option_count = options.len() as u8;
+
+    let select_menu = CreateSelectMenu::new(
+        format!("banlist_{}", channel_id),
+        CreateSelectMenuKind::String { options },
+    )
+    .placeholder("Select users to unban")
+    .min_values(1)
+    .max_values(option_count);
+
+    let embed = embeds::warning_embed()
+        .title(format!("Banned From Your Room ({})", bans.len()))
+        .description(description);
+
+    (embed, vec![CreateActionRow::SelectMenu(select_menu)])
+}
+
+/// Handle the ban list's unban menu: banlist_{channel_id}
+pub async fn handle_selection(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let channel_id = match custom_id
+        .strip_prefix("banlist_")
+        .and_then(|id| id.parse::<u64>().ok())
+    {
+        Some(id) => ChannelId::new(id),
+        None => {
+            send_component_error(ctx, component, "Invalid selector state").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = data.require_owner(channel_id.get(), component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    let user_ids: Vec<UserId> = match &component.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values
+            .iter()
+            .filter_map(|value| value.parse::<u64>().ok())
+            .map(UserId::new)
+            .collect(),
+        _ => {
+            send_component_error(ctx, component, "Unexpected interaction type").await?;
+            return Ok(());
+        }
+    };
+
+    // The list is redrawn in place so further unbans can be made from it
+    defer_then_edit_response(ctx, component, DeferMode::UpdateMessage, async {
+        let mut unbanned = Vec::new();
+        let mut failed = false;
+        for user_id in user_ids {
+            match ban_service::unban_user(ctx, data, channel_id, user_id).await {
+                Ok(true) => {
+                    room_notice::announce(ctx, data, guild_id, channel_id, RoomAction::Unban, user_id)
+                        .await;
+                    unbanned.push(format!("<@{}>", user_id));
+                }
+                // Already unbanned some other way since the list was shown
+                Ok(false) => {}
+                Err(e) => {
+                    error!("Failed to unban {} from channel {}: {:?}", user_id, channel_id, e);
+                    failed = true;
+                }
+            }
+        }
+
+        let bans = match ban::get_bans_for_channel(&data.pool, channel_id.get() as i64).await {
+            Ok(bans) => bans,
+            Err(e) => {
+                error!("Failed to reload bans for channel {}: {:?}", channel_id, e);
+                return EditInteractionResponse::new()
+                    .embed(
+                        embeds::error_embed()
+                            .title("Ban List Unavailable")
+                            .description("Couldn't reload the ban list. Run `/banlist` again."),
+                    )
+                    .components(vec![]);
+            }
+        };
+
+        let (mut embed, components) = build(ctx, channel_id, &bans);
+        if !unbanned.is_empty() {
+            embed = embed.field("Unbanned", unbanned.join(", "), false);
+        }
+        if failed {
+            embed = embed.field("Not Unbanned", "Some unbans failed; try again.", false);
+        }
+
+        EditInteractionResponse::new().embed(embed).components(components)
+    })
+    .await
+}
@@ -1,17 +1,22 @@
 use std::sync::Arc;
 
 use serenity::all::{
-    ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow,
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateSelectMenu,
-    CreateSelectMenuKind, CreateSelectMenuOption, GuildId, UserId,
+    ActionRowComponent, ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context,
+    CreateActionRow, CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, CreateModal, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    EditInteractionResponse, GuildId, InputTextStyle, ModalInteraction, UserId,
 };
 use tracing::{debug, error};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::constants::embeds;
-use crate::handlers::interaction::send_component_error;
+use crate::handlers::interaction::{send_component_error, send_modal_error};
 use crate::services::moderation::ban_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
+
+/// Longest ban reason accepted from the reason modal
+const MAX_BAN_REASON_LENGTH: u16 = 500;
 
 /// Create a ban selector message for the channel owner
 pub async fn create_selector(
@@ -83,19 +88,14 @@ pub async fn handle_selection(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
+    let owner_id = match data.require_owner(channel_id, component.user.id.get()) {
+        Ok(id) => id,
+        Err(e) => {
+            send_component_error(ctx, component, &e.to_string()).await?;
             return Ok(());
         }
     };
 
-    if component.user.id.get() != owner_id {
-        send_component_error(ctx, component, "This isn't your channel, buddy. Trying to ban people from rooms you don't own is a bold strategy — too bad it doesn't work.").await?;
-        return Ok(());
-    }
-
     // Get selected user
     let selected_user_id: u64 = match &component.data.kind {
         ComponentInteractionDataKind::StringSelect { values } => {
@@ -113,60 +113,157 @@ pub async fn handle_selection(
         }
     };
 
-    let guild_id = match component.guild_id {
+    debug!(
+        "Ban selected: owner {} banning user {} from channel {}",
+        owner_id, selected_user_id, channel_id
+    );
+
+    // Ask for an optional reason; the ban happens when the modal is submitted
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Modal(reason_modal(
+                ChannelId::new(channel_id),
+                UserId::new(selected_user_id),
+            )),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Build the modal that asks for an optional ban reason
+/// Submitting it performs the ban; dismissing it cancels
+pub fn reason_modal(channel_id: ChannelId, user_id: UserId) -> CreateModal {
+    CreateModal::new(format!("ban_modal_{}_{}", channel_id, user_id), "Ban User")
+        .components(vec![CreateActionRow::InputText(
+            CreateInputText::new(InputTextStyle::Paragraph, "Reason (optional)", "ban_reason")
+                .required(false)
+                .max_length(MAX_BAN_REASON_LENGTH),
+        )])
+}
+
+/// Handle the ban reason modal submission
+pub async fn handle_reason_modal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    modal: &ModalInteraction,
+) -> Result<(), Error> {
+    let custom_id = &modal.data.custom_id;
+
+    // Parse custom_id: ban_modal_{channel_id}_{user_id}
+    let parts: Vec<&str> = custom_id.split('_').collect();
+    let ids = match (parts.get(2), parts.get(3)) {
+        (Some(channel), Some(user)) => channel.parse::<u64>().ok().zip(user.parse::<u64>().ok()),
+        _ => None,
+    };
+    let (channel_id, target_id) = match ids {
+        Some(ids) => ids,
+        None => {
+            send_modal_error(ctx, modal, "Invalid modal state").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match modal.guild_id {
         Some(id) => id,
         None => {
-            send_component_error(ctx, component, "This command only works in a server").await?;
+            send_modal_error(ctx, modal, "This only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    // Ownership may have changed while the modal was open
+    let owner_id = match data.require_owner(channel_id, modal.user.id.get()) {
+        Ok(id) => id,
+        Err(e) => {
+            send_modal_error(ctx, modal, &e.to_string()).await?;
             return Ok(());
         }
     };
 
+    let reason = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| {
+            if let ActionRowComponent::InputText(input) = component {
+                if input.custom_id == "ban_reason" {
+                    return input.value.clone();
+                }
+            }
+            None
+        })
+        .unwrap_or_default();
+    let reason = reason.trim();
+    let reason = (!reason.is_empty()).then_some(reason);
+
     debug!(
-        "Ban selected: owner {} banning user {} from channel {}",
-        owner_id, selected_user_id, channel_id
+        "Ban confirmed: owner {} banning user {} from channel {} (reason: {:?})",
+        owner_id, target_id, channel_id, reason
     );
 
-    // Perform the ban
+    // Acknowledge first: the ban does DB work plus several API calls
+    modal
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Defer(
+                CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await?;
+
     let ban_result = ban_service::ban_user(
         ctx,
         data,
         guild_id,
         ChannelId::new(channel_id),
-        UserId::new(selected_user_id),
+        UserId::new(target_id),
         UserId::new(owner_id),
-        None,
+        reason,
     )
     .await;
 
-    // Create response based on result
-    let embed = match &ban_result {
-        Ok(_) => {
-            debug!("Successfully banned user {} from channel {}", selected_user_id, channel_id);
-            embeds::success_embed()
-                .title("User Banned")
-                .description(format!(
-                    "<@{}> has been banned from this voice channel.",
-                    selected_user_id
-                ))
-        }
+    if let Ok(true) = ban_result {
+        room_notice::announce(
+            ctx,
+            data,
+            guild_id,
+            ChannelId::new(channel_id),
+            RoomAction::Ban,
+            UserId::new(target_id),
+        )
+        .await;
+    }
+
+    let mut embed = match &ban_result {
+        Ok(true) => embeds::success_embed()
+            .title("User Banned")
+            .description(format!(
+                "<@{}> has been banned from this voice channel.",
+                target_id
+            )),
+        Ok(false) => embeds::warning_embed()
+            .title("Already Banned")
+            .description(format!(
+                "<@{}> was already banned from this voice channel.",
+                target_id
+            )),
         Err(e) => {
-            error!("Ban failed for user {} from channel {}: {:?}", selected_user_id, channel_id, e);
+            error!("Ban failed for user {} from channel {}: {:?}", target_id, channel_id, e);
             embeds::error_embed()
                 .title("Ban Failed")
                 .description(format!("Failed to ban user: {}", e))
         }
     };
 
-    // Acknowledge and update message
-    component
-        .create_response(
-            ctx,
-            CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new()
-                    .embed(embed)
-                    .components(vec![]),
-            ),
-        )
+    if let (Ok(true), Some(reason)) = (&ban_result, reason) {
+        embed = embed.field("Reason", reason, false);
+    }
+
+    modal
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
         .await?;
 
     Ok(())
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, ComponentInteraction, Context, EditInteractionResponse, Permissions};
+use tracing::{error, info};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::components::confirm::{self, ConfirmResult};
+use crate::constants::embeds;
+use crate::db::queries::voice_channel;
+use crate::handlers::interaction::send_component_error;
+use crate::services::jtc::channel_deleter::{self, DeleteOutcome};
+use crate::utils::permissions;
+
+/// Handle moderator force-delete buttons from `/admin user-channels`
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    // Parse custom_id: chanadmin_delete_{channel_id}
+    let parts: Vec<&str> = custom_id.split('_').collect();
+    if parts.len() < 3 || parts[1] != "delete" {
+        send_component_error(ctx, component, "Invalid button state").await?;
+        return Ok(());
+    }
+
+    let channel_id: u64 = match parts[2].parse() {
+        Ok(id) => id,
+        Err(_) => {
+            send_component_error(ctx, component, "Invalid channel ID").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if !permissions::has_permission(ctx, guild_id, component.user.id, Permissions::MANAGE_CHANNELS)
+        .await
+    {
+        send_component_error(ctx, component, "You need Manage Channels to delete channels").await?;
+        return Ok(());
+    }
+
+    let vc = match voice_channel::get(&data.pool, channel_id as i64).await? {
+        Some(vc) if vc.guild_id == guild_id.get() as i64 => vc,
+        _ => {
+            send_component_error(ctx, component, "That channel no longer exists").await?;
+            return Ok(());
+        }
+    };
+
+    let result = confirm::prompt(
+        ctx,
+        data,
+        component,
+        &format!(
+            "Delete <#{}> owned by <@{}>? Everyone in it will be disconnected.",
+            channel_id, vc.owner_id
+        ),
+    )
+    .await?;
+
+    if result != ConfirmResult::Confirmed {
+        return Ok(());
+    }
+
+    let embed = match channel_deleter::delete_channel(ctx, data, ChannelId::new(channel_id)).await {
+        Ok(DeleteOutcome::Deleted) => {
+            info!(
+                "Moderator {} force-deleted channel {} owned by {}",
+                component.user.id, channel_id, vc.owner_id
+            );
+            embeds::success_embed()
+                .title("Channel Deleted")
+                .description(format!("Deleted the channel owned by <@{}>.", vc.owner_id))
+        }
+        Ok(DeleteOutcome::Failed) => embeds::error_embed()
+            .title("Delete Failed")
+            .description("Discord refused to delete the channel. Try again in a moment."),
+        Ok(DeleteOutcome::Forbidden) => embeds::error_embed()
+            .title("Delete Failed")
+            .description("I don't have permission to delete that channel, so I've stopped managing it."),
+        Err(e) => {
+            error!("Force delete of channel {} failed: {:?}", channel_id, e);
+            embeds::error_embed()
+                .title("Delete Failed")
+                .description(format!("Failed to delete channel: {}", e))
+        }
+    };
+
+    component
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await?;
+
+    Ok(())
+}
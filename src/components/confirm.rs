@@ -0,0 +1,254 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use poise::{CreateReply, ReplyHandle};
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, ComponentInteractionCollector, Context, CreateActionRow,
+    CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+    EditInteractionResponse, UserId,
+};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::bot::data::{Context as PoiseContext, Data};
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::handlers::interaction::send_component_error;
+
+/// How long the user has to press Confirm or Cancel
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Outcome of a confirmation prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmResult {
+    Confirmed,
+    Cancelled,
+    TimedOut,
+}
+
+/// Ask the user who triggered `component` to confirm an action
+/// Replies with an ephemeral Confirm/Cancel prompt and waits for their choice.
+/// On Confirmed the prompt's buttons are removed and the caller should edit in its
+/// result with `component.edit_response`; Cancelled and TimedOut are reported here.
+pub async fn prompt(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+    message: &str,
+) -> Result<ConfirmResult, Error> {
+    let prompt = Prompt::new(message);
+
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(prompt.embed.clone())
+                    .components(vec![prompt.buttons.clone()])
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    let result = prompt.wait(ctx, data, component.user.id).await?;
+
+    let mut edit = EditInteractionResponse::new().components(vec![]);
+    if let Some(embed) = outcome_embed(result) {
+        edit = edit.embed(embed);
+    }
+    component.edit_response(ctx, edit).await?;
+
+    Ok(result)
+}
+
+/// Like `prompt`, but for a slash command
+/// On Confirmed the caller should edit in its result through the returned reply handle
+pub async fn prompt_command<'a>(
+    ctx: PoiseContext<'a>,
+    message: &str,
+) -> Result<(ConfirmResult, ReplyHandle<'a>), Error> {
+    let prompt = Prompt::new(message);
+
+    let handle = ctx
+        .send(
+            CreateReply::default()
+                .embed(prompt.embed.clone())
+                .components(vec![prompt.buttons.clone()])
+                .ephemeral(true),
+        )
+        .await?;
+
+    let result = prompt
+        .wait(ctx.serenity_context(), ctx.data(), ctx.author().id)
+        .await?;
+
+    let mut edit = CreateReply::default().components(vec![]);
+    if let Some(embed) = outcome_embed(result) {
+        edit = edit.embed(embed);
+    }
+    handle.edit(ctx, edit).await?;
+
+    Ok((result, handle))
+}
+
+/// A Confirm/Cancel prompt identified by a one-off nonce
+struct Prompt {
+    nonce: String,
+    confirm_id: String,
+    cancel_id: String,
+    embed: CreateEmbed,
+    buttons: CreateActionRow,
+}
+
+impl Prompt {
+    fn new(message: &str) -> Self {
+        let nonce = Uuid::new_v4().simple().to_string();
+        let confirm_id = format!("confirm_yes_{}", nonce);
+        let cancel_id = format!("confirm_no_{}", nonce);
+
+        let embed = embeds::warning_embed()
+            .title("Are you sure?")
+            .description(message);
+
+        let buttons = CreateActionRow::Buttons(vec![
+            CreateButton::new(confirm_id.clone())
+                .label("Confirm")
+                .style(ButtonStyle::Danger),
+            CreateButton::new(cancel_id.clone())
+                .label("Cancel")
+                .style(ButtonStyle::Secondary),
+        ]);
+
+        Self {
+            nonce,
+            confirm_id,
+            cancel_id,
+            embed,
+            buttons,
+        }
+    }
+
+    /// Wait for `user_id` to press one of the buttons, then acknowledge the press
+    async fn wait(
+        self,
+        ctx: &Context,
+        data: &Arc<Data>,
+        user_id: UserId,
+    ) -> Result<ConfirmResult, Error> {
+        data.pending_confirmations
+            .insert(self.nonce.clone(), std::time::Instant::now());
+
+        let collector = ComponentInteractionCollector::new(ctx)
+            .author_id(user_id)
+            .custom_ids(vec![self.confirm_id.clone(), self.cancel_id]);
+
+        let mut click = None;
+        let result = wait_for_choice(
+            async {
+                click = collector.next().await;
+                click.as_ref().map(|c| c.data.custom_id == self.confirm_id)
+            },
+            CONFIRM_TIMEOUT,
+        )
+        .await;
+
+        // The router sees the same click; keep the nonce around briefly so it doesn't report it as stale
+        let pending = Arc::clone(data);
+        let stale_nonce = self.nonce.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            pending.pending_confirmations.remove(&stale_nonce);
+        });
+
+        // Acknowledge the button press itself; the prompt is updated via the original interaction
+        if let Some(click) = click {
+            click
+                .create_response(ctx, CreateInteractionResponse::Acknowledge)
+                .await?;
+        }
+
+        debug!("Confirmation {} resolved as {:?}", self.nonce, result);
+
+        Ok(result)
+    }
+}
+
+/// Embed that replaces the prompt when nothing is going to happen
+fn outcome_embed(result: ConfirmResult) -> Option<CreateEmbed> {
+    match result {
+        ConfirmResult::Confirmed => None,
+        ConfirmResult::Cancelled => Some(
+            embeds::secondary_embed()
+                .title("Cancelled")
+                .description("Nothing was changed."),
+        ),
+        ConfirmResult::TimedOut => Some(
+            embeds::secondary_embed()
+                .title("Timed Out")
+                .description("No response, so nothing was changed."),
+        ),
+    }
+}
+
+/// Wait for a choice (Some(true) = confirm, Some(false) = cancel) with a timeout
+/// A response source that ends without a choice counts as a timeout
+async fn wait_for_choice(
+    choice: impl Future<Output = Option<bool>>,
+    timeout: Duration,
+) -> ConfirmResult {
+    match tokio::time::timeout(timeout, choice).await {
+        Ok(Some(true)) => ConfirmResult::Confirmed,
+        Ok(Some(false)) => ConfirmResult::Cancelled,
+        Ok(None) | Err(_) => ConfirmResult::TimedOut,
+    }
+}
+
+/// Handle a confirm button seen by the interaction router
+/// Live prompts are answered by their collector; anything else is a stale prompt
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    // Parse custom_id: confirm_{yes|no}_{nonce}
+    let nonce = component.data.custom_id.rsplit('_').next().unwrap_or_default();
+
+    if data.pending_confirmations.contains_key(nonce) {
+        return Ok(());
+    }
+
+    send_component_error(ctx, component, "This confirmation has expired. Please try again.")
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn no_response_times_out() {
+        let result = wait_for_choice(std::future::pending(), Duration::from_millis(10)).await;
+        assert_eq!(result, ConfirmResult::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn collector_ending_counts_as_timeout() {
+        let result = wait_for_choice(async { None }, Duration::from_secs(1)).await;
+        assert_eq!(result, ConfirmResult::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn choice_before_timeout_is_returned() {
+        assert_eq!(
+            wait_for_choice(async { Some(true) }, Duration::from_secs(1)).await,
+            ConfirmResult::Confirmed
+        );
+        assert_eq!(
+            wait_for_choice(async { Some(false) }, Duration::from_secs(1)).await,
+            ConfirmResult::Cancelled
+        );
+    }
+}
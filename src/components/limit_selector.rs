@@ -4,17 +4,20 @@ use std::time::{Duration, Instant};
 use serenity::all::{
     ActionRowComponent, ChannelId, ComponentInteraction, Context, CreateActionRow,
     CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage, CreateModal,
-    EditChannel, InputTextStyle, ModalInteraction,
+    EditChannel, EditMessage, GuildId, InputTextStyle, ModalInteraction, UserId,
 };
 use tracing::{debug, error};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::constants::embeds;
+use crate::constants::timeouts::LIMIT_RATE_WINDOW_SECONDS;
+use crate::db::queries::voice_channel;
 use crate::handlers::interaction::{send_component_error, send_modal_error};
+use crate::services::jtc::welcome_embed;
 
-const MAX_USER_LIMIT: u32 = 69;
-const LIMIT_RATE_WINDOW: Duration = Duration::from_secs(60 * 60); // 1 hour
+pub const MAX_USER_LIMIT: u32 = 69;
+const LIMIT_RATE_WINDOW: Duration = Duration::from_secs(LIMIT_RATE_WINDOW_SECONDS);
 const LIMIT_RATE_MAX_USES: usize = 3;
 
 /// Handle the "Set Limit" button — open a modal for the owner to type a number
@@ -41,22 +44,8 @@ pub async fn handle_button(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot")
-                .await?;
-            return Ok(());
-        }
-    };
-
-    if component.user.id.get() != owner_id {
-        send_component_error(
-            ctx,
-            component,
-            "You don't own this channel. Setting limits on other people's rooms is not a personality trait.",
-        )
-        .await?;
+    if let Err(e) = data.require_owner(channel_id, component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
         return Ok(());
     }
 
@@ -104,21 +93,8 @@ pub async fn handle_modal(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_modal_error(ctx, modal, "This channel is not managed by the bot").await?;
-            return Ok(());
-        }
-    };
-
-    if modal.user.id.get() != owner_id {
-        send_modal_error(
-            ctx,
-            modal,
-            "You don't own this channel. Setting limits on other people's rooms is not a personality trait.",
-        )
-        .await?;
+    if let Err(e) = data.require_owner(channel_id, modal.user.id.get()) {
+        send_modal_error(ctx, modal, &e.to_string()).await?;
         return Ok(());
     }
 
@@ -169,59 +145,37 @@ pub async fn handle_modal(
         }
     };
 
-    // Rate limit: max 3 changes per hour
-    let key = (modal.user.id.get(), channel_id);
-    let now = Instant::now();
-    {
-        let mut entry = data.limit_change_timestamps.entry(key).or_insert_with(Vec::new);
-        // Prune old timestamps outside the window
-        entry.retain(|ts| now.duration_since(*ts) < LIMIT_RATE_WINDOW);
-
-        if entry.len() >= LIMIT_RATE_MAX_USES {
-            let oldest = entry[0];
-            let reset_in = LIMIT_RATE_WINDOW - now.duration_since(oldest);
-            let mins = reset_in.as_secs() / 60;
-            send_modal_error(
-                ctx,
-                modal,
-                &format!(
-                    "You've already changed the user limit 3 times this hour. Try again in {} minute{}.",
-                    mins + 1,
-                    if mins == 0 { "" } else { "s" }
-                ),
-            )
-            .await?;
-            return Ok(());
-        }
-
-        entry.push(now);
+    if let Err(reason) = record_limit_change(data, modal.user.id.get(), channel_id) {
+        send_modal_error(ctx, modal, &reason).await?;
+        return Ok(());
     }
 
-    debug!("Setting user limit for channel {} to {}", channel_id, limit);
-
-    // Apply the user limit to the Discord channel (0 = unlimited)
     let channel_id_obj = ChannelId::new(channel_id);
-    if let Err(e) = channel_id_obj
-        .edit(ctx, EditChannel::new().user_limit(limit))
-        .await
-    {
-        error!(
-            "Failed to set user limit on channel {}: {:?}",
-            channel_id, e
-        );
+    if let Err(e) = apply_limit(ctx, channel_id_obj, limit).await {
         send_modal_error(ctx, modal, &format!("Failed to set user limit: {}", e)).await?;
         return Ok(());
     }
 
-    let description = if limit == 0 {
-        "User limit removed — your channel is now unlimited.".to_string()
-    } else {
-        format!("User limit set to **{}**.", limit)
-    };
+    // Keep the panel the button lives on showing the current limit
+    if let Some(ref panel) = modal.message {
+        if let Some(record) = voice_channel::get(&data.pool, channel_id as i64).await? {
+            let embed = welcome_embed::panel_embed(
+                record.channel_type.is_casual(),
+                UserId::new(record.owner_id as u64),
+                limit,
+            );
+            if let Err(e) = channel_id_obj
+                .edit_message(ctx, panel.id, EditMessage::new().embed(embed))
+                .await
+            {
+                debug!("Failed to refresh panel for channel {}: {:?}", channel_id, e);
+            }
+        }
+    }
 
     let embed = embeds::success_embed()
         .title("User Limit Updated")
-        .description(description);
+        .description(limit_summary(ctx, modal.guild_id, channel_id_obj, limit));
 
     modal
         .create_response(
@@ -236,3 +190,75 @@ pub async fn handle_modal(
 
     Ok(())
 }
+
+/// Count a limit change against the owner's hourly allowance (max 3 changes per hour)
+/// Returns the message to show when the allowance is used up
+pub fn record_limit_change(data: &Data, user_id: u64, channel_id: u64) -> Result<(), String> {
+    let now = Instant::now();
+    let mut entry = data
+        .limit_change_timestamps
+        .entry((user_id, channel_id))
+        .or_insert_with(Vec::new);
+    // Prune old timestamps outside the window
+    entry.retain(|ts| now.duration_since(*ts) < LIMIT_RATE_WINDOW);
+
+    if entry.len() >= LIMIT_RATE_MAX_USES {
+        let oldest = entry[0];
+        let reset_in = LIMIT_RATE_WINDOW - now.duration_since(oldest);
+        let mins = reset_in.as_secs() / 60;
+        return Err(format!(
+            "You've already changed the user limit 3 times this hour. Try again in {} minute{}.",
+            mins + 1,
+            if mins == 0 { "" } else { "s" }
+        ));
+    }
+
+    entry.push(now);
+    Ok(())
+}
+
+/// Apply the user limit to the Discord channel (0 = unlimited)
+pub async fn apply_limit(ctx: &Context, channel_id: ChannelId, limit: u32) -> Result<(), Error> {
+    debug!("Setting user limit for channel {} to {}", channel_id, limit);
+
+    if let Err(e) = channel_id.edit(ctx, EditChannel::new().user_limit(limit)).await {
+        error!("Failed to set user limit on channel {}: {:?}", channel_id, e);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Describe a newly set limit, warning when it already locks newcomers out
+pub fn limit_summary(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    limit: u32,
+) -> String {
+    let mut description = if limit == 0 {
+        "User limit removed — your channel is now unlimited.".to_string()
+    } else {
+        format!("User limit set to **{}**.", limit)
+    };
+
+    // A limit below the current headcount locks everyone else out until people leave
+    let occupancy = guild_id
+        .and_then(|guild_id| ctx.cache.guild(guild_id))
+        .map(|guild| {
+            guild
+                .voice_states
+                .values()
+                .filter(|vs| vs.channel_id == Some(channel_id))
+                .count()
+        })
+        .unwrap_or(0);
+    if limit > 0 && occupancy >= limit as usize {
+        description.push_str(&format!(
+            "\n\nThe room already has **{}** people in it, so nobody new can join until someone leaves.",
+            occupancy
+        ));
+    }
+
+    description
+}
@@ -1,7 +1,15 @@
+pub mod ban_appeal;
+pub mod ban_list;
 pub mod ban_selector;
+pub mod channel_admin;
+pub mod confirm;
 pub mod limit_selector;
 pub mod naming_prompt;
 pub mod owner_actions;
+pub mod raid_lock;
+pub mod room_hold;
+pub mod room_lock;
 pub mod spam_prompt;
 pub mod tag_selector;
 pub mod topic_modal;
+pub mod unmute_all;
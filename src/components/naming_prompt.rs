@@ -1,37 +1,50 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use serenity::all::{
     ActionRowComponent, ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow,
     CreateButton, CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage,
-    CreateMessage, CreateModal, InputTextStyle, ModalInteraction, UserId,
+    CreateMessage, CreateModal, GuildId, InputTextStyle, ModalInteraction, UserId,
 };
 use tracing::{debug, error};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::components::tag_selector;
-use crate::constants::embeds::{self, BULLET};
+use crate::constants::embeds;
 use crate::constants::timeouts::VC_NAMING_DEADLINE_SECONDS;
-use crate::db::queries::{user_vc_preference, voice_channel};
+use crate::db::models::{GuildConfig, MessageTemplate};
+use crate::db::queries::{banned_word, guild_config, user_vc_preference, voice_channel};
 use crate::handlers::interaction::send_component_error;
+use crate::services::component_expiry::Expiry;
 use crate::services::jtc::channel_creator;
+use crate::utils::formatting::fill_template;
 use crate::utils::profanity;
 
 /// Send a naming prompt to the channel
 pub async fn send_prompt(
     ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
     channel_id: ChannelId,
     owner_id: UserId,
 ) -> Result<(), Error> {
+    let template = guild_config::get_message_template(
+        &data.pool,
+        guild_id.get() as i64,
+        MessageTemplate::NamingPrompt,
+    )
+    .await?;
+
     let embed = embeds::secondary_embed()
         .title("Configure Your Channel")
-        .description(format!(
-            "Welcome <@{}>.\n\n\
-            {} You have **{} seconds** to set up your channel\n\
-            {} Click the button below to choose a name\n\
-            {} Your preference will be saved for next time\n\n\
-            If not configured, this channel will be deleted.",
-            owner_id, BULLET, VC_NAMING_DEADLINE_SECONDS, BULLET, BULLET
+        .description(fill_template(
+            &template,
+            &[
+                ("owner", format!("<@{}>", owner_id)),
+                ("channel", format!("<#{}>", channel_id)),
+                ("seconds", VC_NAMING_DEADLINE_SECONDS.to_string()),
+            ],
         ));
 
     let button = CreateButton::new(format!("naming_configure_{}", channel_id))
@@ -68,20 +81,14 @@ pub async fn handle_configure_button(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
+    let owner_id = match data.require_owner(channel_id, component.user.id.get()) {
+        Ok(id) => id,
+        Err(e) => {
+            send_component_error(ctx, component, &e.to_string()).await?;
             return Ok(());
         }
     };
 
-    if component.user.id.get() != owner_id {
-        send_component_error(ctx, component, "Only the channel owner can configure the channel")
-            .await?;
-        return Ok(());
-    }
-
     // Extend the deadline by another minute since user is actively configuring
     let guild_id = component.guild_id.unwrap_or_default();
     let new_deadline = chrono::Utc::now() + chrono::Duration::seconds(VC_NAMING_DEADLINE_SECONDS as i64);
@@ -95,6 +102,10 @@ pub async fn handle_configure_button(
 
     debug!("Extended deadline for channel {} by {} seconds", channel_id, VC_NAMING_DEADLINE_SECONDS);
 
+    let max_length = guild_config::get_or_create(&data.pool, guild_id.get() as i64)
+        .await?
+        .max_name_length();
+
     // Show the naming modal
     let modal = CreateModal::new(
         format!("naming_modal_{}", channel_id),
@@ -103,7 +114,7 @@ pub async fn handle_configure_button(
     .components(vec![CreateActionRow::InputText(
         CreateInputText::new(InputTextStyle::Short, "Channel Name", "naming_channel_name")
             .placeholder("Enter a name for your channel")
-            .max_length(100)
+            .max_length(max_length as u16)
             .required(true),
     )]);
 
@@ -132,29 +143,26 @@ pub async fn handle_reconfigure_button(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
-            return Ok(());
-        }
-    };
-
-    if component.user.id.get() != owner_id {
-        send_component_error(ctx, component, "Only the channel owner can reconfigure the channel")
-            .await?;
+    if let Err(e) = data.require_owner(channel_id, component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
         return Ok(());
     }
 
     // Get current channel name to pre-fill the modal
-    let current_name = match crate::db::queries::voice_channel::get(&data.pool, channel_id as i64).await? {
-        Some(vc) => vc.topic.unwrap_or_default(),
+    let vc = match crate::db::queries::voice_channel::get(&data.pool, channel_id as i64).await? {
+        Some(vc) => vc,
         None => {
             send_component_error(ctx, component, "Channel not found in database").await?;
             return Ok(());
         }
     };
 
+    let max_length = guild_config::get_or_create(&data.pool, vc.guild_id)
+        .await?
+        .max_name_length();
+    // Discord rejects a pre-filled value longer than the input allows
+    let current_name: String = vc.topic.unwrap_or_default().chars().take(max_length).collect();
+
     // Show the naming modal (same as configure, but for reconfigure)
     let modal = CreateModal::new(
         format!("naming_modal_{}", channel_id),
@@ -163,7 +171,7 @@ pub async fn handle_reconfigure_button(
     .components(vec![CreateActionRow::InputText(
         CreateInputText::new(InputTextStyle::Short, "Channel Name", "naming_channel_name")
             .placeholder("Enter a name for your channel")
-            .max_length(100)
+            .max_length(max_length as u16)
             .value(current_name)
             .required(true),
     )]);
@@ -231,8 +239,14 @@ pub async fn handle_naming_modal(
         None => return Ok(()),
     };
 
-    // Check for profanity
-    if let Err(reason) = profanity::validate_channel_name(&channel_name) {
+    let config = guild_config::get_or_create(&data.pool, guild_id.get() as i64).await?;
+    let max_length = config.max_name_length();
+    let banned_words = banned_word::list(&data.pool, guild_id.get() as i64).await?;
+    let filter = data.word_filters.get(guild_id.get(), banned_words);
+
+    // Check for profanity and the guild's length limit
+    if let Err(reason) = profanity::validate_channel_name(&channel_name, max_length, &filter)
+    {
         // Extend deadline to give user another chance
         let new_deadline = chrono::Utc::now() + chrono::Duration::seconds(VC_NAMING_DEADLINE_SECONDS as i64);
         let _ = user_vc_preference::create_deadline(
@@ -244,7 +258,7 @@ pub async fn handle_naming_modal(
         ).await;
 
         let embed = embeds::error_embed()
-            .title("Inappropriate Name")
+            .title("Invalid Name")
             .description(format!(
                 "{}\n\n\
                 You have been given an extra **{} seconds** to choose a different name.\n\
@@ -278,19 +292,32 @@ pub async fn handle_naming_modal(
     channel_creator::update_channel_topic(ctx, data, ChannelId::new(channel_id), &channel_name)
         .await?;
 
-    // Save user preferences for next time (without tags for now - they'll be added via tag selector)
-    user_vc_preference::upsert(
+    // Save the name for next time; tags saved earlier stay until the tag selector changes them
+    user_vc_preference::save_name(
         &data.pool,
         guild_id.get() as i64,
         modal.user.id.get() as i64,
         &channel_type_str,
-        Some(&channel_name),
-        &[],
+        &channel_name,
     )
     .await?;
 
-    // Remove the deadline since user configured the channel
-    user_vc_preference::remove_deadline(&data.pool, channel_id as i64).await?;
+    // A name configures the room, unless the guild also wants a tag and it has none yet;
+    // then the deadline starts over so there's time to pick one
+    let needs_tag = config.require_tags && vc.tags.is_empty();
+    if needs_tag {
+        let new_deadline = chrono::Utc::now() + chrono::Duration::seconds(VC_NAMING_DEADLINE_SECONDS as i64);
+        user_vc_preference::create_deadline(
+            &data.pool,
+            channel_id as i64,
+            guild_id.get() as i64,
+            modal.user.id.get() as i64,
+            new_deadline,
+        )
+        .await?;
+    } else {
+        user_vc_preference::remove_deadline(&data.pool, channel_id as i64).await?;
+    }
 
     debug!(
         "User {} configured channel {} with name '{}'",
@@ -298,12 +325,23 @@ pub async fn handle_naming_modal(
     );
 
     // Acknowledge the modal with ephemeral message
-    let embed = embeds::success_embed()
-        .title("Name Set")
-        .description(format!(
-            "Your channel has been renamed to **{}**.",
-            channel_name
-        ));
+    let embed = if needs_tag {
+        embeds::success_embed()
+            .title("Name Set")
+            .description(format!(
+                "Your channel has been renamed to **{}**.\n\n\
+                This server requires at least one tag. Pick one from the menu in your channel \
+                within **{} seconds** to finish setting it up.",
+                channel_name, VC_NAMING_DEADLINE_SECONDS
+            ))
+    } else {
+        embeds::success_embed()
+            .title("Name Set")
+            .description(format!(
+                "Your channel has been renamed to **{}**.",
+                channel_name
+            ))
+    };
 
     modal
         .create_response(
@@ -316,11 +354,32 @@ pub async fn handle_naming_modal(
         )
         .await?;
 
-    // Send tag selector to the channel
-    let tag_message = tag_selector::create_selector(ChannelId::new(channel_id), is_casual);
-    if let Err(e) = ChannelId::new(channel_id).send_message(ctx, tag_message).await {
-        error!("Failed to send tag selector: {:?}", e);
-    }
+    send_tag_selector(ctx, data, &config, ChannelId::new(channel_id), is_casual).await;
 
     Ok(())
 }
+
+/// Send the tag selector to a room
+pub async fn send_tag_selector(
+    ctx: &Context,
+    data: &Arc<Data>,
+    config: &GuildConfig,
+    channel_id: ChannelId,
+    is_casual: bool,
+) {
+    let tag_message = tag_selector::create_selector(
+        channel_id,
+        is_casual,
+        config.emoji_tags(is_casual),
+        config.require_tags,
+    );
+    match channel_id.send_message(ctx, tag_message).await {
+        // Tags are part of setting up the room, so the menu goes when the naming deadline would
+        Ok(message) => data.expiring_components.track(
+            &message,
+            Duration::from_secs(VC_NAMING_DEADLINE_SECONDS),
+            Expiry::Delete,
+        ),
+        Err(e) => error!("Failed to send tag selector: {:?}", e),
+    }
+}
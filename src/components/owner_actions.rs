@@ -1,19 +1,26 @@
 use std::sync::Arc;
 
 use serenity::all::{
-    ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context,
-    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, UserId,
+    ButtonStyle, ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context,
+    CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse,
+    EditInteractionResponse, GuildId, UserId,
 };
 use tracing::{debug, error};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
+use crate::components::ban_selector;
 use crate::constants::embeds;
-use crate::db::queries::voice_channel;
-use crate::handlers::interaction::send_component_error;
-use crate::services::moderation::{ban_service, mute_service};
+use crate::db::queries::{mute, voice_channel};
+use crate::services::jtc::{channel_creator, owner_role};
+use crate::handlers::interaction::{
+    defer_then_edit, defer_then_edit_response, send_component_error, DeferMode,
+};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
+use crate::services::moderation::mute_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
 
-/// Handle owner action select menus (mute, unmute, transfer, ban)
+/// Handle owner action select menus (mute, unmute, transfer, kick, ban via reason modal) and the follow-up deafen button
 pub async fn handle_selection(
     ctx: &Context,
     data: &Arc<Data>,
@@ -29,6 +36,10 @@ pub async fn handle_selection(
     }
 
     let action = parts[1];
+    if !matches!(action, "mute" | "unmute" | "transfer" | "kick" | "ban" | "deafen") {
+        send_component_error(ctx, component, "Unknown action").await?;
+        return Ok(());
+    }
     let channel_id: u64 = match parts[2].parse() {
         Ok(id) => id,
         Err(_) => {
@@ -46,17 +57,31 @@ pub async fn handle_selection(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
+    let owner_id = match data.require_owner(channel_id, component.user.id.get()) {
+        Ok(id) => id,
+        Err(e) => {
+            send_component_error(ctx, component, &e.to_string()).await?;
             return Ok(());
         }
     };
 
-    if component.user.id.get() != owner_id {
-        send_component_error(ctx, component, "Whoa there, you don't own this channel. Did you really think you could server mute people in someone else's room? Embarrassing.").await?;
-        return Ok(());
+    // The deafen button carries its target: vc_deafen_{channel_id}_{user_id}
+    if action == "deafen" {
+        let target_id = match parts.get(3).and_then(|id| id.parse::<u64>().ok()) {
+            Some(id) => UserId::new(id),
+            None => {
+                send_component_error(ctx, component, "Invalid user ID").await?;
+                return Ok(());
+            }
+        };
+
+        return defer_then_edit(
+            ctx,
+            component,
+            DeferMode::UpdateMessage,
+            handle_deafen(ctx, data, guild_id, channel_id, target_id),
+        )
+        .await;
     }
 
     // Get selected user
@@ -107,15 +132,88 @@ pub async fn handle_selection(
         return Ok(());
     }
 
+    // Banning asks for an optional reason first; the ban happens on modal submit
+    if action == "ban" {
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Modal(ban_selector::reason_modal(
+                    ChannelId::new(channel_id),
+                    selected_user_id,
+                )),
+            )
+            .await?;
+        return Ok(());
+    }
+
     // Route to appropriate handler
-    match action {
-        "mute" => handle_mute(ctx, data, component, guild_id, channel_id, owner_id, selected_user_id).await,
-        "unmute" => handle_unmute(ctx, data, component, guild_id, channel_id, selected_user_id).await,
-        "transfer" => handle_transfer(ctx, data, component, guild_id, channel_id, owner_id, selected_user_id).await,
-        "ban" => handle_ban(ctx, data, component, guild_id, channel_id, owner_id, selected_user_id).await,
-        _ => {
-            send_component_error(ctx, component, "Unknown action").await?;
-            Ok(())
+    // Acknowledge first: these actions do DB work plus several API calls and can exceed 3 seconds
+    defer_then_edit_response(ctx, component, DeferMode::Ephemeral, async {
+        let embed = match action {
+            // The panel's mute menu toggles, so picking someone already muted unmutes them
+            "mute" if is_muted(data, channel_id, selected_user_id).await => {
+                handle_unmute(ctx, data, guild_id, channel_id, owner_id, selected_user_id).await
+            }
+            "mute" => {
+                return handle_mute(ctx, data, guild_id, channel_id, owner_id, selected_user_id).await
+            }
+            "unmute" => handle_unmute(ctx, data, guild_id, channel_id, owner_id, selected_user_id).await,
+            "kick" => handle_kick(ctx, data, guild_id, channel_id, owner_id, selected_user_id).await,
+            "transfer" => handle_transfer(ctx, data, guild_id, channel_id, owner_id, selected_user_id).await,
+            _ => embeds::error_embed()
+                .title("Error")
+                .description("Unknown action"),
+        };
+        EditInteractionResponse::new().embed(embed)
+    })
+    .await
+}
+
+/// Whether a user has an active mute in this channel
+async fn is_muted(data: &Data, channel_id: u64, user_id: UserId) -> bool {
+    match mute::get_active_mute(&data.pool, channel_id as i64, user_id.get() as i64).await {
+        Ok(record) => record.is_some(),
+        Err(e) => {
+            error!("Failed to look up mute for {} in {}: {:?}", user_id, channel_id, e);
+            false
+        }
+    }
+}
+
+async fn handle_kick(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: u64,
+    owner_id: u64,
+    target_id: UserId,
+) -> CreateEmbed {
+    debug!("Kick action: owner {} kicking {} from channel {}", owner_id, target_id, channel_id);
+
+    // Only a disconnect - no ban is recorded, so they can rejoin right away
+    match guild_id.disconnect_member(ctx, target_id).await {
+        Ok(_) => {
+            room_notice::announce(
+                ctx,
+                data,
+                guild_id,
+                ChannelId::new(channel_id),
+                RoomAction::Kick,
+                target_id,
+            )
+            .await;
+            embeds::success_embed()
+                .title("User Kicked")
+                .description(format!(
+                    "<@{}> has been disconnected. They can rejoin; ban them to keep them out.",
+                    target_id
+                ))
+        }
+        Err(e) => {
+            error!("Kick failed: {:?}", e);
+            embeds::error_embed()
+                .title("Kick Failed")
+                .description(format!("Failed to kick user: {}", e))
         }
     }
 }
@@ -123,12 +221,11 @@ pub async fn handle_selection(
 async fn handle_mute(
     ctx: &Context,
     data: &Arc<Data>,
-    component: &ComponentInteraction,
     guild_id: GuildId,
     channel_id: u64,
     owner_id: u64,
     target_id: UserId,
-) -> Result<(), Error> {
+) -> EditInteractionResponse {
     debug!("Mute action: owner {} muting {} in channel {}", owner_id, target_id, channel_id);
 
     let result = mute_service::mute_user(
@@ -139,32 +236,86 @@ async fn handle_mute(
         target_id,
         UserId::new(owner_id),
         false, // Not an admin mute - room owner mute
+        false, // Deafen is offered as a follow-up button
+        None,  // Timed mutes are only offered through /mute
     )
     .await;
 
-    let embed = match result {
-        Ok(_) => embeds::success_embed()
-            .title("User Muted")
-            .description(format!("<@{}> has been server muted.", target_id)),
+    match result {
+        Ok(_) => {
+            room_notice::announce(
+                ctx,
+                data,
+                guild_id,
+                ChannelId::new(channel_id),
+                RoomAction::Mute,
+                target_id,
+            )
+            .await;
+            let embed = embeds::success_embed()
+                .title("User Muted")
+                .description(format!("<@{}> has been server muted.", target_id));
+            // Offer to escalate to a deafen for persistent disruptors
+            let deafen_button = CreateButton::new(format!("vc_deafen_{}_{}", channel_id, target_id))
+                .label("Also Deafen")
+                .style(ButtonStyle::Danger);
+            EditInteractionResponse::new()
+                .embed(embed)
+                .components(vec![CreateActionRow::Buttons(vec![deafen_button])])
+        }
         Err(e) => {
             error!("Mute failed: {:?}", e);
-            embeds::error_embed()
-                .title("Mute Failed")
-                .description(format!("Failed to mute user: {}", e))
+            EditInteractionResponse::new().embed(
+                embeds::error_embed()
+                    .title("Mute Failed")
+                    .description(format!("Failed to mute user: {}", e)),
+            )
         }
-    };
+    }
+}
+
+async fn handle_deafen(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: u64,
+    target_id: UserId,
+) -> CreateEmbed {
+    debug!("Deafen action: deafening {} in channel {}", target_id, channel_id);
 
-    send_ephemeral_response(ctx, component, embed).await
+    let result = mute_service::deafen_user(
+        ctx,
+        data,
+        guild_id,
+        ChannelId::new(channel_id),
+        target_id,
+    )
+    .await;
+
+    match result {
+        Ok(true) => embeds::success_embed()
+            .title("User Muted and Deafened")
+            .description(format!("<@{}> has been server muted and deafened.", target_id)),
+        Ok(false) => embeds::warning_embed()
+            .title("Not Muted")
+            .description(format!("<@{}> is no longer muted in this channel.", target_id)),
+        Err(e) => {
+            error!("Deafen failed: {:?}", e);
+            embeds::error_embed()
+                .title("Deafen Failed")
+                .description(format!("Failed to deafen user: {}", e))
+        }
+    }
 }
 
 async fn handle_unmute(
     ctx: &Context,
     data: &Arc<Data>,
-    component: &ComponentInteraction,
     guild_id: GuildId,
     channel_id: u64,
+    owner_id: u64,
     target_id: UserId,
-) -> Result<(), Error> {
+) -> CreateEmbed {
     debug!("Unmute action: unmuting {} in channel {}", target_id, channel_id);
 
     let result = mute_service::unmute_user(
@@ -173,9 +324,22 @@ async fn handle_unmute(
         guild_id,
         ChannelId::new(channel_id),
         target_id,
+        UserId::new(owner_id),
     )
     .await;
 
+    if let Ok(true) = result {
+        room_notice::announce(
+            ctx,
+            data,
+            guild_id,
+            ChannelId::new(channel_id),
+            RoomAction::Unmute,
+            target_id,
+        )
+        .await;
+    }
+
     let embed = match result {
         Ok(true) => embeds::success_embed()
             .title("User Unmuted")
@@ -191,20 +355,28 @@ async fn handle_unmute(
         }
     };
 
-    send_ephemeral_response(ctx, component, embed).await
+    embed
 }
 
 async fn handle_transfer(
     ctx: &Context,
     data: &Arc<Data>,
-    component: &ComponentInteraction,
     guild_id: GuildId,
     channel_id: u64,
     old_owner_id: u64,
     new_owner_id: UserId,
-) -> Result<(), Error> {
+) -> CreateEmbed {
     debug!("Transfer action: {} transferring channel {} to {}", old_owner_id, channel_id, new_owner_id);
 
+    if let Err(reason) = data
+        .transfer_history
+        .record(channel_id, old_owner_id, new_owner_id.get())
+    {
+        return embeds::error_embed()
+            .title("Transfer Cooldown")
+            .description(reason);
+    }
+
     // Update database
     let result = voice_channel::update_owner(
         &data.pool,
@@ -214,15 +386,37 @@ async fn handle_transfer(
     .await;
 
     let embed = match result {
-        Ok(_) => {
+        Ok(vc) => {
             // Update cache
             data.set_channel_owner(channel_id, new_owner_id.get());
+            owner_role::hand_over(
+                &ctx.http,
+                &data.pool,
+                guild_id,
+                UserId::new(old_owner_id),
+                new_owner_id,
+            )
+            .await;
 
             // Try to update channel permissions
             let channel_id_obj = ChannelId::new(channel_id);
             if let Err(e) = update_channel_permissions(ctx, guild_id, channel_id_obj, UserId::new(old_owner_id), new_owner_id).await {
                 error!("Failed to update channel permissions: {:?}", e);
             }
+            if let Some(vc) = vc {
+                if let Err(e) = channel_creator::transfer_discussion_channel(ctx, &vc, UserId::new(old_owner_id), new_owner_id).await {
+                    error!("Failed to update discussion channel permissions: {:?}", e);
+                }
+            }
+
+            let entry = LogEntry {
+                action: LogAction::Transfer,
+                actor: Some(UserId::new(old_owner_id)),
+                target: new_owner_id,
+                channel_id: Some(ChannelId::new(channel_id)),
+                reason: Some("Handed over by the owner".to_string()),
+            };
+            mod_log::record(ctx, data, guild_id, entry).await;
 
             embeds::success_embed()
                 .title("Ownership Transferred")
@@ -239,91 +433,26 @@ async fn handle_transfer(
         }
     };
 
-    send_ephemeral_response(ctx, component, embed).await
-}
-
-async fn handle_ban(
-    ctx: &Context,
-    data: &Arc<Data>,
-    component: &ComponentInteraction,
-    guild_id: GuildId,
-    channel_id: u64,
-    owner_id: u64,
-    target_id: UserId,
-) -> Result<(), Error> {
-    debug!("Ban action: owner {} banning {} from channel {}", owner_id, target_id, channel_id);
-
-    let result = ban_service::ban_user(
-        ctx,
-        data,
-        guild_id,
-        ChannelId::new(channel_id),
-        target_id,
-        UserId::new(owner_id),
-        None,
-    )
-    .await;
-
-    let embed = match result {
-        Ok(_) => embeds::success_embed()
-            .title("User Banned")
-            .description(format!(
-                "<@{}> has been banned from this voice channel.",
-                target_id
-            )),
-        Err(e) => {
-            error!("Ban failed: {:?}", e);
-            embeds::error_embed()
-                .title("Ban Failed")
-                .description(format!("Failed to ban user: {}", e))
-        }
-    };
-
-    send_ephemeral_response(ctx, component, embed).await
+    embed
 }
 
 /// Update channel permissions when ownership is transferred
-async fn update_channel_permissions(
+pub(crate) async fn update_channel_permissions(
     ctx: &Context,
     _guild_id: GuildId,
     channel_id: ChannelId,
     old_owner: UserId,
     new_owner: UserId,
 ) -> Result<(), Error> {
-    use serenity::all::{PermissionOverwrite, PermissionOverwriteType, Permissions};
+    use serenity::all::PermissionOverwriteType;
 
     // Remove old owner's permission overwrite
     let _ = channel_id
         .delete_permission(ctx, PermissionOverwriteType::Member(old_owner))
         .await;
 
-    // Add new owner's permission overwrite - only mute
-    let new_owner_perms = PermissionOverwrite {
-        allow: Permissions::MUTE_MEMBERS,
-        deny: Permissions::empty(),
-        kind: PermissionOverwriteType::Member(new_owner),
-    };
-
-    channel_id.create_permission(ctx, new_owner_perms).await?;
-
-    Ok(())
-}
-
-/// Send an ephemeral response for a component interaction
-async fn send_ephemeral_response(
-    ctx: &Context,
-    component: &ComponentInteraction,
-    embed: serenity::all::CreateEmbed,
-) -> Result<(), Error> {
-    component
-        .create_response(
-            ctx,
-            CreateInteractionResponse::Message(
-                CreateInteractionResponseMessage::new()
-                    .embed(embed)
-                    .ephemeral(true),
-            ),
-        )
+    channel_id
+        .create_permission(ctx, channel_creator::owner_overwrite(new_owner))
         .await?;
 
     Ok(())
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, ComponentInteraction, Context};
+use tracing::error;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::handlers::interaction::{defer_then_edit, send_component_error, DeferMode};
+use crate::services::spam::raid_guard;
+
+/// Handle the unlock button on a raid lock notice: raidlock_unlock_{channel_id}
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let channel_id = match custom_id
+        .strip_prefix("raidlock_unlock_")
+        .and_then(|id| id.parse::<u64>().ok())
+    {
+        Some(id) => ChannelId::new(id),
+        None => {
+            send_component_error(ctx, component, "Invalid button state").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This button only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = data.require_owner(channel_id.get(), component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    defer_then_edit(ctx, component, DeferMode::UpdateMessage, async {
+        match raid_guard::unlock(ctx, data, guild_id, channel_id).await {
+            Ok(true) => embeds::success_embed()
+                .title("Room Unlocked")
+                .description("Anyone can join this room again."),
+            Ok(false) => embeds::secondary_embed()
+                .title("Not Locked")
+                .description("This room isn't locked anymore."),
+            Err(e) => {
+                error!("Failed to unlock channel {}: {:?}", channel_id, e);
+                embeds::error_embed()
+                    .title("Unlock Failed")
+                    .description(format!("Failed to unlock the room: {}", e))
+            }
+        }
+    })
+    .await
+}
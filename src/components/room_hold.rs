@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateMessage, GuildId, UserId,
+};
+use tracing::{debug, error};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::timeouts::OWNER_HOP_HOLD_SECONDS;
+use crate::handlers::interaction::{defer_then_edit, send_component_error, DeferMode};
+use crate::services::component_expiry::Expiry;
+use crate::services::jtc::channel_deleter;
+
+/// DM an owner that their room is being held, with a button to release it early
+pub async fn send_hold_notice(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) {
+    let embed = embeds::info_embed()
+        .title("Your Room Is Being Held")
+        .description(format!(
+            "You left <#{}> for another channel. It's held for you for {} minutes - \
+            hop back in to keep it, or release it now so it can be handed over.",
+            channel_id,
+            OWNER_HOP_HOLD_SECONDS / 60
+        ));
+
+    let button = CreateActionRow::Buttons(vec![CreateButton::new(format!(
+        "roomhold_release_{}_{}",
+        guild_id, channel_id
+    ))
+    .label("Release Now")
+    .style(ButtonStyle::Secondary)]);
+
+    let message = CreateMessage::new().embed(embed).components(vec![button]);
+
+    match owner_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            match dm_channel.send_message(ctx, message).await {
+                // The button does nothing once the hold is over
+                Ok(sent) => data.expiring_components.track(
+                    &sent,
+                    Duration::from_secs(OWNER_HOP_HOLD_SECONDS),
+                    Expiry::Disable,
+                ),
+                Err(e) => debug!("Could not DM owner {} about their held room: {:?}", owner_id, e),
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for owner {}: {:?}", owner_id, e);
+        }
+    }
+}
+
+/// Handle the release button: roomhold_release_{guild_id}_{channel_id}
+/// Sent in DMs, so the guild comes from the custom_id
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let parts: Vec<&str> = custom_id.split('_').collect();
+    let ids = match (parts.get(2), parts.get(3)) {
+        (Some(guild), Some(channel)) => guild.parse::<u64>().ok().zip(channel.parse::<u64>().ok()),
+        _ => None,
+    };
+    let (guild_id, channel_id) = match ids {
+        Some((guild, channel)) => (GuildId::new(guild), ChannelId::new(channel)),
+        None => {
+            send_component_error(ctx, component, "Invalid button state").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = data.require_owner(channel_id.get(), component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    data.expiring_components.forget(component.message.id);
+    defer_then_edit(ctx, component, DeferMode::UpdateMessage, async {
+        match channel_deleter::release_held_room(ctx, data, guild_id, channel_id, component.user.id)
+            .await
+        {
+            Ok(true) => embeds::success_embed()
+                .title("Room Released")
+                .description("Your room has been handed over, or closed if nobody was left in it."),
+            Ok(false) => embeds::secondary_embed()
+                .title("Nothing to Release")
+                .description("Your room isn't being held anymore."),
+            Err(e) => {
+                error!("Failed to release held channel {}: {:?}", channel_id, e);
+                embeds::error_embed()
+                    .title("Release Failed")
+                    .description(format!("Failed to release your room: {}", e))
+            }
+        }
+    })
+    .await
+}
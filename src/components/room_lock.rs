@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, ComponentInteraction, Context};
+use tracing::error;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::handlers::interaction::{defer_then_edit, send_component_error, DeferMode};
+use crate::services::jtc::channel_lock;
+
+/// Handle the panel's lock buttons: roomlock_toggle_{channel_id}, and roomlock_lock_{channel_id}
+/// and roomlock_unlock_{channel_id} from older panels
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let (action, channel_id) = match custom_id.strip_prefix("roomlock_").and_then(|rest| {
+        let (action, id) = rest.split_once('_')?;
+        Some((action, id.parse::<u64>().ok()?))
+    }) {
+        Some((action, id)) => (action, ChannelId::new(id)),
+        None => {
+            send_component_error(ctx, component, "Invalid button state").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This button only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = data.require_owner(channel_id.get(), component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    // Answered privately so the panel stays in place
+    defer_then_edit(ctx, component, DeferMode::Ephemeral, async {
+        let result = match action {
+            "lock" => channel_lock::lock_channel(ctx, data, guild_id, channel_id)
+                .await
+                .map(|changed| (true, changed)),
+            "unlock" => channel_lock::unlock_channel(ctx, data, guild_id, channel_id)
+                .await
+                .map(|changed| (false, changed)),
+            // The toggle locks an open room and opens a locked one
+            _ => match channel_lock::lock_channel(ctx, data, guild_id, channel_id).await {
+                Ok(true) => Ok((true, true)),
+                Ok(false) => channel_lock::unlock_channel(ctx, data, guild_id, channel_id)
+                    .await
+                    .map(|changed| (false, changed)),
+                Err(e) => Err(e),
+            },
+        };
+
+        match result {
+            Ok((true, true)) => embeds::success_embed()
+                .title("Room Locked")
+                .description("People already here can stay, but nobody new can join."),
+            Ok((true, false)) => embeds::secondary_embed()
+                .title("Already Locked")
+                .description("This room is already locked."),
+            Ok((false, true)) => embeds::success_embed()
+                .title("Room Unlocked")
+                .description("Anyone can join this room again."),
+            Ok((false, false)) => embeds::secondary_embed()
+                .title("Not Locked")
+                .description("This room isn't locked."),
+            Err(e) => {
+                error!("Failed to change lock on channel {}: {:?}", channel_id, e);
+                embeds::error_embed()
+                    .title("Lock Failed")
+                    .description(format!("Failed to change the room's lock: {}", e))
+            }
+        }
+    })
+    .await
+}
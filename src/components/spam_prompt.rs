@@ -1,31 +1,50 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use serenity::all::{
     ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow, CreateButton,
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, UserId,
+    CreateInteractionResponse, CreateMessage, EditMessage, GuildId, Permissions, UserId,
 };
 use tracing::debug;
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::constants::embeds;
+use crate::db::models::{MessageTemplate, SpamPromptTarget};
+use crate::db::queries::{guild_config, voice_channel};
 use crate::handlers::interaction::send_component_error;
+use crate::services::component_expiry::Expiry;
 use crate::services::moderation::ban_service;
+use crate::services::moderation::room_notice::{self, RoomAction};
+use crate::utils::formatting::fill_template;
+use crate::utils::permissions;
 
-/// Send a spam prompt to the channel owner
+/// Send a spam prompt for the channel owner (or mods) to act on
+/// Falls back to the room's chat when the owner can't be DMed or no mod log channel is set
 pub async fn send_prompt(
     ctx: &Context,
-    _data: &Arc<Data>,
+    data: &Arc<Data>,
+    guild_id: GuildId,
     channel_id: ChannelId,
-    _owner_id: UserId,
+    owner_id: UserId,
     suspicious_user_id: UserId,
+    target: SpamPromptTarget,
 ) -> Result<(), Error> {
+    let config = guild_config::get(&data.pool, guild_id.get() as i64).await?;
+    let template = config
+        .as_ref()
+        .map(|c| c.message_template(MessageTemplate::SpamPrompt))
+        .unwrap_or_else(|| MessageTemplate::SpamPrompt.default_text());
+
     let embed = embeds::warning_embed()
         .title("Spam Detection Alert")
-        .description(format!(
-            "<@{}> has been joining and leaving rapidly.\n\n\
-            Would you like to ban them from this channel?",
-            suspicious_user_id
+        .description(fill_template(
+            template,
+            &[
+                ("user", format!("<@{}>", suspicious_user_id)),
+                ("owner", format!("<@{}>", owner_id)),
+                ("channel", format!("<#{}>", channel_id)),
+            ],
         ));
 
     let buttons = CreateActionRow::Buttons(vec![
@@ -38,9 +57,36 @@ pub async fn send_prompt(
     ]);
 
     let message = CreateMessage::new().embed(embed).components(vec![buttons]);
+    // Unanswered alerts stay as a record, without the buttons
+    let lifetime = Duration::from_secs(data.settings.component_lifetime_seconds);
+
+    match target {
+        SpamPromptTarget::Channel => {}
+        SpamPromptTarget::OwnerDm => match owner_id.create_dm_channel(ctx).await {
+            Ok(dm_channel) => match dm_channel.send_message(ctx, message.clone()).await {
+                Ok(sent) => {
+                    data.expiring_components.track(&sent, lifetime, Expiry::Disable);
+                    return Ok(());
+                }
+                Err(e) => debug!("Could not DM spam prompt to owner {}: {:?}", owner_id, e),
+            },
+            Err(e) => debug!("Could not create DM channel for owner {}: {:?}", owner_id, e),
+        },
+        SpamPromptTarget::ModChannel => {
+            match config.as_ref().and_then(|c| c.mod_log_channel_id) {
+                Some(id) => {
+                    let sent = ChannelId::new(id as u64).send_message(ctx, message).await?;
+                    data.expiring_components.track(&sent, lifetime, Expiry::Disable);
+                    return Ok(());
+                }
+                None => debug!("No mod log channel for spam prompt in guild {}", guild_id),
+            }
+        }
+    }
 
     // Send to the voice channel's text chat
-    channel_id.send_message(ctx, message).await?;
+    let sent = channel_id.send_message(ctx, message).await?;
+    data.expiring_components.track(&sent, lifetime, Expiry::Disable);
 
     Ok(())
 }
@@ -76,26 +122,35 @@ pub async fn handle_response(
         }
     };
 
-    // Verify the responder is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
+    // Prompts DMed to the owner carry no guild, so take it from the room
+    let guild_id = match component.guild_id {
         Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
-            return Ok(());
-        }
+        None => match voice_channel::get(&data.pool, channel_id as i64).await? {
+            Some(vc) => GuildId::new(vc.guild_id as u64),
+            None => {
+                send_component_error(ctx, component, "This channel no longer exists").await?;
+                return Ok(());
+            }
+        },
     };
 
-    if component.user.id.get() != owner_id {
-        send_component_error(ctx, component, "Only the channel owner can respond to this")
-            .await?;
-        return Ok(());
-    }
-
-    let guild_id = match component.guild_id {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This command only works in a server").await?;
-            return Ok(());
+    // The owner can act on the prompt, and so can mods when it was posted to the mod channel
+    let acted_by = match data.require_owner(channel_id, component.user.id.get()) {
+        Ok(id) => id,
+        Err(e) => {
+            let is_mod = component.guild_id.is_some()
+                && permissions::has_permission(
+                    ctx,
+                    guild_id,
+                    component.user.id,
+                    Permissions::MANAGE_CHANNELS,
+                )
+                .await;
+            if !is_mod || matches!(e, Error::ChannelNotManaged) {
+                send_component_error(ctx, component, &e.to_string()).await?;
+                return Ok(());
+            }
+            component.user.id.get()
         }
     };
 
@@ -104,44 +159,60 @@ pub async fn handle_response(
         action, channel_id, user_id
     );
 
+    if action != "ban" && action != "ignore" {
+        send_component_error(ctx, component, "Unknown action").await?;
+        return Ok(());
+    }
+
+    // Acknowledge right away - banning takes several API calls
+    component
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
     let embed = match action {
         "ban" => {
             // Ban the user
-            ban_service::ban_user(
+            let is_new_ban = ban_service::ban_user(
                 ctx,
                 data,
                 guild_id,
                 ChannelId::new(channel_id),
                 UserId::new(user_id),
-                UserId::new(owner_id),
+                UserId::new(acted_by),
                 Some("Spam detected"),
             )
             .await?;
 
+            if is_new_ban {
+                room_notice::announce(
+                    ctx,
+                    data,
+                    guild_id,
+                    ChannelId::new(channel_id),
+                    RoomAction::Ban,
+                    UserId::new(user_id),
+                )
+                .await;
+            }
+
             embeds::success_embed()
                 .title("User Banned")
                 .description(format!("<@{}> has been banned for spam.", user_id))
         }
-        "ignore" => {
+        _ => {
             embeds::standard_embed()
                 .title("Ignored")
                 .description("The spam alert has been dismissed.")
         }
-        _ => {
-            send_component_error(ctx, component, "Unknown action").await?;
-            return Ok(());
-        }
     };
 
+    // Edit the alert by message ID rather than through the interaction token,
+    // so a slow ban can't outlive the token window
+    data.expiring_components.forget(component.message.id);
     component
-        .create_response(
-            ctx,
-            CreateInteractionResponse::UpdateMessage(
-                CreateInteractionResponseMessage::new()
-                    .embed(embed)
-                    .components(vec![]),
-            ),
-        )
+        .message
+        .clone()
+        .edit(ctx, EditMessage::new().embed(embed).components(vec![]))
         .await?;
 
     Ok(())
@@ -2,27 +2,40 @@ use std::sync::Arc;
 
 use serenity::all::{
     ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow,
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateSelectMenu,
-    CreateSelectMenuKind, CreateSelectMenuOption, EditMessage,
+    CreateEmbed, CreateMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    EditMessage,
 };
 use tracing::{debug, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::constants::embeds;
-use crate::constants::tags::{get_tags, MAX_TAGS};
+use crate::constants::tags::{available_tags, normalize_tags, EmojiTag, MAX_TAGS};
+use crate::constants::timeouts::VC_NAMING_DEADLINE_SECONDS;
 use crate::db::queries::{user_vc_preference, voice_channel};
-use crate::handlers::interaction::send_component_error;
+use crate::handlers::interaction::{defer_then_edit, send_component_error, DeferMode};
 use crate::services::jtc::channel_creator;
 
-/// Create a tag selector message
-pub fn create_selector(channel_id: ChannelId, is_casual: bool) -> CreateMessage {
-    let tags = get_tags(is_casual);
+/// Discord's limit on options in one select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Create a tag selector message, offering the built-in tags and the guild's emoji tags
+/// `required` makes the owner pick at least one tag, for guilds that require tags
+pub fn create_selector(
+    channel_id: ChannelId,
+    is_casual: bool,
+    emoji_tags: &[String],
+    required: bool,
+) -> CreateMessage {
     let channel_type = if is_casual { "casual" } else { "debate" };
 
-    let options: Vec<CreateSelectMenuOption> = tags
-        .iter()
-        .map(|tag| CreateSelectMenuOption::new(*tag, *tag))
+    let options: Vec<CreateSelectMenuOption> = available_tags(is_casual, emoji_tags)
+        .into_iter()
+        .take(MAX_SELECT_OPTIONS)
+        .map(|tag| match EmojiTag::parse(tag) {
+            Some(emoji) => CreateSelectMenuOption::new(emoji.label(), tag).emoji(emoji.reaction()),
+            None => CreateSelectMenuOption::new(tag, tag),
+        })
         .collect();
 
     let select_menu = CreateSelectMenu::new(
@@ -30,15 +43,25 @@ pub fn create_selector(channel_id: ChannelId, is_casual: bool) -> CreateMessage
         CreateSelectMenuKind::String { options },
     )
     .placeholder(format!("Select up to {} tags", MAX_TAGS))
-    .min_values(0)
+    .min_values(required as u8)
     .max_values(MAX_TAGS as u8);
 
-    let embed = embeds::standard_embed()
-        .title("Select Channel Tags")
-        .description(format!(
+    let description = if required {
+        format!(
+            "Choose up to {} tags for your channel to help others find it.\n\n\
+            This server requires at least one tag. Pick one within **{} seconds** or your channel \
+            will be deleted.",
+            MAX_TAGS, VC_NAMING_DEADLINE_SECONDS
+        )
+    } else {
+        format!(
             "Choose up to {} tags for your channel to help others find it.",
             MAX_TAGS
-        ));
+        )
+    };
+    let embed = embeds::standard_embed()
+        .title("Select Channel Tags")
+        .description(description);
 
     CreateMessage::new()
         .embed(embed)
@@ -70,40 +93,95 @@ pub async fn handle_selection(
     };
 
     // Verify the user is the channel owner
-    let owner_id = match data.get_channel_owner(channel_id) {
-        Some(id) => id,
-        None => {
-            send_component_error(ctx, component, "This channel is not managed by the bot").await?;
+    if let Err(e) = data.require_owner(channel_id, component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    // Extract selected tags - don't rely on the menu's max_values alone
+    let values: &[String] = match &component.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values,
+        _ => &[],
+    };
+    let selected_tags = match normalize_tags(values, data.settings.tag_case) {
+        Ok(tags) => tags,
+        Err(e) => {
+            send_component_error(ctx, component, &e).await?;
             return Ok(());
         }
     };
 
-    if component.user.id.get() != owner_id {
+    // A room still waiting out its deadline is in a guild that requires tags; the menu asks for
+    // one, but an empty pick mustn't slip through and take the selector with it
+    if selected_tags.is_empty()
+        && user_vc_preference::has_deadline(&data.pool, channel_id as i64).await?
+    {
         send_component_error(
             ctx,
             component,
-            "Nice try. You don't own this channel — go touch grass instead of messing with other people's rooms.",
+            "This server requires at least one tag. Pick one to finish setting up your channel.",
         )
         .await?;
         return Ok(());
     }
 
-    // Extract selected tags
-    let selected_tags: Vec<String> = match &component.data.kind {
-        ComponentInteractionDataKind::StringSelect { values } => {
-            values.iter().take(MAX_TAGS).cloned().collect()
-        }
-        _ => vec![],
-    };
-
     debug!(
         "Tags selected for channel {}: {:?}",
         channel_id, selected_tags
     );
 
+    // Renaming the channel status can be slow, so acknowledge first and edit in the result
+    defer_then_edit(
+        ctx,
+        component,
+        DeferMode::Ephemeral,
+        apply_tags(ctx, data, component, channel_id, selected_tags),
+    )
+    .await?;
+
+    // Clean up the selector via its message ID - unlike the interaction token, it never expires
+    data.expiring_components.forget(component.message.id);
+    if let Err(e) = component.message.clone().edit(ctx, EditMessage::new().components(vec![])).await {
+        warn!("Failed to clear tag selector components: {:?}", e);
+    }
+
+    // Try to delete the message entirely
+    if let Err(e) = component.message.delete(ctx).await {
+        debug!("Could not delete tag selector message: {:?}", e);
+    }
+
+    Ok(())
+}
+
+/// Apply the selected tags and save them as the owner's preference
+async fn apply_tags(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+    channel_id: u64,
+    selected_tags: Vec<String>,
+) -> CreateEmbed {
     // Update channel tags
-    channel_creator::update_channel_tags(ctx, data, ChannelId::new(channel_id), selected_tags.clone())
-        .await?;
+    if let Err(e) = channel_creator::update_channel_tags(
+        ctx,
+        data,
+        ChannelId::new(channel_id),
+        selected_tags.clone(),
+    )
+    .await
+    {
+        warn!("Failed to update tags for channel {}: {:?}", channel_id, e);
+        return embeds::error_embed()
+            .title("Error")
+            .description(format!("Failed to update tags: {}", e));
+    }
+
+    // A tag finishes setting up a room in guilds that require one
+    if !selected_tags.is_empty() {
+        if let Err(e) = user_vc_preference::remove_deadline(&data.pool, channel_id as i64).await {
+            warn!("Failed to clear naming deadline for channel {}: {:?}", channel_id, e);
+        }
+    }
 
     // Save to user preferences for next time
     if let Some(guild_id) = component.guild_id {
@@ -128,8 +206,7 @@ pub async fn handle_selection(
         }
     }
 
-    // Acknowledge with ephemeral message
-    let embed = embeds::success_embed()
+    embeds::success_embed()
         .title("Tags Updated")
         .description(if selected_tags.is_empty() {
             "No tags selected.".to_string()
@@ -142,28 +219,5 @@ pub async fn handle_selection(
                     .collect::<Vec<_>>()
                     .join(" ")
             )
-        });
-
-    component
-        .create_response(
-            ctx,
-            CreateInteractionResponse::Message(
-                CreateInteractionResponseMessage::new()
-                    .embed(embed)
-                    .ephemeral(true),
-            ),
-        )
-        .await?;
-
-    // Delete the original tag selector message
-    if let Err(e) = component.message.clone().edit(ctx, EditMessage::new().components(vec![])).await {
-        warn!("Failed to clear tag selector components: {:?}", e);
-    }
-
-    // Try to delete the message entirely
-    if let Err(e) = component.message.delete(ctx).await {
-        debug!("Could not delete tag selector message: {:?}", e);
-    }
-
-    Ok(())
+        })
 }
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, ComponentInteraction, Context};
+use tracing::error;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::commands::owner::mute::unmute_all_summary;
+use crate::constants::embeds;
+use crate::handlers::interaction::{defer_then_edit, send_component_error, DeferMode};
+use crate::services::moderation::mute_service;
+
+/// Handle the panel's Unmute All button: unmuteall_{channel_id}
+pub async fn handle_button(
+    ctx: &Context,
+    data: &Arc<Data>,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = &component.data.custom_id;
+
+    let channel_id = match custom_id
+        .strip_prefix("unmuteall_")
+        .and_then(|id| id.parse::<u64>().ok())
+    {
+        Some(id) => ChannelId::new(id),
+        None => {
+            send_component_error(ctx, component, "Invalid button state").await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = match component.guild_id {
+        Some(id) => id,
+        None => {
+            send_component_error(ctx, component, "This button only works in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = data.require_owner(channel_id.get(), component.user.id.get()) {
+        send_component_error(ctx, component, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    // Lifting many mutes is spaced out, so acknowledge first
+    defer_then_edit(ctx, component, DeferMode::Ephemeral, async {
+        match mute_service::unmute_all_in_channel(ctx, data, guild_id, channel_id).await {
+            Ok(lifted) => embeds::success_embed()
+                .title("Mutes Lifted")
+                .description(unmute_all_summary(lifted)),
+            Err(e) => {
+                error!("Failed to lift mutes in channel {}: {:?}", channel_id, e);
+                embeds::error_embed()
+                    .title("Unmute Failed")
+                    .description(format!("Failed to lift the mutes: {}", e))
+            }
+        }
+    })
+    .await
+}
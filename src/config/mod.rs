@@ -1,3 +1,4 @@
+pub mod provision;
 mod settings;
 
-pub use settings::Settings;
+pub use settings::{BackupSettings, Settings};
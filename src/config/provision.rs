@@ -0,0 +1,387 @@
+//! Offline guild provisioning for operators hosting many guilds
+//! Applies a JSON spec straight to the database with the same setters `/setup` uses,
+//! without connecting to Discord. Run with `jarvis --setup-guild <spec.json>`.
+//!
+//! A spec is one guild object or an array of them:
+//! ```json
+//! {
+//!   "guild_id": 123456789012345678,
+//!   "casual": { "jtc_channel_id": 1, "category_id": 2, "rules_channel_id": 3 },
+//!   "debate": { "jtc_channel_id": 4, "category_id": 5 },
+//!   "mod_log_channel_id": 6,
+//!   "owner_hop_policy": "hold"
+//! }
+//! ```
+//! Omitted fields are left as they are.
+
+use serde::Deserialize;
+use serenity::all::Permissions;
+use sqlx::PgPool;
+
+use crate::constants::tags::{get_tags, is_available_tag, normalize_tags, TagCase};
+use crate::constants::timeouts::{
+    DEFAULT_SPAM_PROMPT_THRESHOLD, DEFAULT_SPAM_TIMEOUT_THRESHOLD, DEFAULT_SPAM_WINDOW_SECONDS,
+    MAX_EMPTY_CHANNEL_GRACE_SECONDS, MAX_RECREATE_COOLDOWN_SECONDS,
+};
+use crate::db::models::{GuildConfig, OwnerHopPolicy, SpamPromptTarget, SpamThresholds};
+use crate::db::queries::{guild_config, room_permission};
+use crate::services::jtc::channel_creator::{MAX_OVERFLOW_CATEGORIES, MAX_ROOM_PERMISSION_ROLES};
+use crate::utils::permissions::{format_permissions, parse_room_permissions};
+use crate::utils::profanity::{MAX_CHANNEL_NAME_LENGTH, MIN_CHANNEL_NAME_LENGTH};
+
+/// Settings for one guild
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GuildSpec {
+    pub guild_id: u64,
+    pub casual: Option<ChannelTypeSpec>,
+    pub debate: Option<ChannelTypeSpec>,
+    pub appeal_channel_id: Option<u64>,
+    pub mod_log_channel_id: Option<u64>,
+    pub announce_global_mute_joins: Option<bool>,
+    pub create_discussion_channels: Option<bool>,
+    pub owner_hop_policy: Option<String>,
+    pub empty_channel_grace_seconds: Option<u32>,
+    pub announce_room_mod_actions: Option<bool>,
+    pub raid_lock_joins: Option<u32>,
+    pub max_name_length: Option<u32>,
+    pub owner_role_id: Option<u64>,
+    pub keep_mutes_on_channel_delete: Option<bool>,
+    pub send_welcome_dm: Option<bool>,
+    pub spam_prompt_target: Option<String>,
+    pub keep_rooms_while_streaming: Option<bool>,
+    pub room_permissions: Option<Vec<RoomPermissionSpec>>,
+    pub channel_created_notify_channel_id: Option<u64>,
+    pub spam_prompt_threshold: Option<u32>,
+    pub spam_timeout_threshold: Option<u32>,
+    pub spam_window_seconds: Option<u32>,
+    pub require_tags: Option<bool>,
+    pub recreate_cooldown_seconds: Option<u32>,
+}
+
+/// Permissions a role gets in every new room, as permission names like "connect, speak"
+/// Roles can't be checked offline; ones that don't exist are skipped when rooms are created
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoomPermissionSpec {
+    pub role_id: u64,
+    pub allow: Option<String>,
+    pub deny: Option<String>,
+}
+
+impl RoomPermissionSpec {
+    /// The parsed (allow, deny) permissions
+    fn permissions(&self) -> Result<(Permissions, Permissions), String> {
+        let allow = parse_room_permissions(self.allow.as_deref().unwrap_or_default())?;
+        let deny = parse_room_permissions(self.deny.as_deref().unwrap_or_default())?;
+        if allow.intersects(deny) {
+            return Err(format!(
+                "role {} both allows and denies: {}",
+                self.role_id,
+                format_permissions(allow & deny)
+            ));
+        }
+        Ok((allow, deny))
+    }
+}
+
+/// JTC setup for one channel type (casual or debate)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelTypeSpec {
+    pub jtc_channel_id: Option<u64>,
+    pub category_id: Option<u64>,
+    pub rules_channel_id: Option<u64>,
+    pub overflow_category_ids: Option<Vec<u64>>,
+    pub default_tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SpecFile {
+    One(Box<GuildSpec>),
+    Many(Vec<GuildSpec>),
+}
+
+/// Parse and validate a spec file (a single guild or an array of guilds)
+pub fn parse_specs(json: &str) -> Result<Vec<GuildSpec>, String> {
+    // Untagged enums hide the real error, so try each shape for a useful message
+    let specs = match serde_json::from_str::<SpecFile>(json) {
+        Ok(SpecFile::One(spec)) => vec![*spec],
+        Ok(SpecFile::Many(specs)) => specs,
+        Err(_) => {
+            let trimmed = json.trim_start();
+            let err = if trimmed.starts_with('[') {
+                serde_json::from_str::<Vec<GuildSpec>>(json).err()
+            } else {
+                serde_json::from_str::<GuildSpec>(json).err()
+            };
+            return Err(format!(
+                "Invalid guild spec: {}",
+                err.map(|e| e.to_string()).unwrap_or_default()
+            ));
+        }
+    };
+
+    if specs.is_empty() {
+        return Err("Guild spec contains no guilds".to_string());
+    }
+
+    for spec in &specs {
+        for kind in [&spec.casual, &spec.debate].into_iter().flatten() {
+            let overflow = kind.overflow_category_ids.as_ref().map_or(0, Vec::len);
+            if overflow > MAX_OVERFLOW_CATEGORIES {
+                return Err(format!(
+                    "Guild {}: at most {} overflow categories per channel type, got: {}",
+                    spec.guild_id, MAX_OVERFLOW_CATEGORIES, overflow
+                ));
+            }
+        }
+        for (casual, kind) in [(true, &spec.casual), (false, &spec.debate)] {
+            let Some(tags) = kind.as_ref().and_then(|k| k.default_tags.as_ref()) else {
+                continue;
+            };
+            let tags = normalize_tags(tags, TagCase::Title)
+                .map_err(|e| format!("Guild {}: {}", spec.guild_id, e))?;
+            if let Some(unknown) = tags
+                .iter()
+                .find(|t| !is_available_tag(t, get_tags(casual), TagCase::Title))
+            {
+                return Err(format!(
+                    "Guild {}: unknown default tag: {}",
+                    spec.guild_id, unknown
+                ));
+            }
+        }
+        if let Some(ref roles) = spec.room_permissions {
+            if roles.len() > MAX_ROOM_PERMISSION_ROLES {
+                return Err(format!(
+                    "Guild {}: at most {} room permission roles, got: {}",
+                    spec.guild_id,
+                    MAX_ROOM_PERMISSION_ROLES,
+                    roles.len()
+                ));
+            }
+            for role in roles {
+                role.permissions().map_err(|e| format!("Guild {}: {}", spec.guild_id, e))?;
+            }
+        }
+        if let Some(ref policy) = spec.owner_hop_policy {
+            if OwnerHopPolicy::parse(policy).is_none() {
+                return Err(format!(
+                    "Guild {}: owner_hop_policy must be 'release', 'hold' or 'prompt', got: {}",
+                    spec.guild_id, policy
+                ));
+            }
+        }
+        if let Some(ref target) = spec.spam_prompt_target {
+            if SpamPromptTarget::parse(target).is_none() {
+                return Err(format!(
+                    "Guild {}: spam_prompt_target must be 'channel', 'owner_dm' or 'mod_channel', got: {}",
+                    spec.guild_id, target
+                ));
+            }
+        }
+        if let Some(seconds) = spec.empty_channel_grace_seconds {
+            if seconds > MAX_EMPTY_CHANNEL_GRACE_SECONDS {
+                return Err(format!(
+                    "Guild {}: empty_channel_grace_seconds must be at most {}, got: {}",
+                    spec.guild_id, MAX_EMPTY_CHANNEL_GRACE_SECONDS, seconds
+                ));
+            }
+        }
+        if let Some(seconds) = spec.recreate_cooldown_seconds {
+            if seconds > MAX_RECREATE_COOLDOWN_SECONDS {
+                return Err(format!(
+                    "Guild {}: recreate_cooldown_seconds must be at most {}, got: {}",
+                    spec.guild_id, MAX_RECREATE_COOLDOWN_SECONDS, seconds
+                ));
+            }
+        }
+        if spec.spam_prompt_threshold.is_some()
+            || spec.spam_timeout_threshold.is_some()
+            || spec.spam_window_seconds.is_some()
+        {
+            // Unset values are checked against the built-in defaults, since the bot's own
+            // environment isn't known offline
+            let thresholds = SpamThresholds {
+                prompt: spec.spam_prompt_threshold.unwrap_or(DEFAULT_SPAM_PROMPT_THRESHOLD),
+                timeout: spec.spam_timeout_threshold.unwrap_or(DEFAULT_SPAM_TIMEOUT_THRESHOLD),
+                window_seconds: spec
+                    .spam_window_seconds
+                    .map_or(DEFAULT_SPAM_WINDOW_SECONDS, u64::from),
+            };
+            if let Err(reason) = thresholds.validate() {
+                return Err(format!("Guild {}: {}", spec.guild_id, reason));
+            }
+        }
+        if let Some(length) = spec.max_name_length {
+            let length = length as usize;
+            if !(MIN_CHANNEL_NAME_LENGTH..=MAX_CHANNEL_NAME_LENGTH).contains(&length) {
+                return Err(format!(
+                    "Guild {}: max_name_length must be between {} and {}, got: {}",
+                    spec.guild_id, MIN_CHANNEL_NAME_LENGTH, MAX_CHANNEL_NAME_LENGTH, length
+                ));
+            }
+        }
+    }
+
+    Ok(specs)
+}
+
+/// Apply a guild spec, returning the resulting config
+pub async fn apply(pool: &PgPool, spec: &GuildSpec) -> Result<GuildConfig, sqlx::Error> {
+    let guild_id = spec.guild_id as i64;
+    let mut config = guild_config::get_or_create(pool, guild_id).await?;
+
+    for (casual, kind) in [(true, &spec.casual), (false, &spec.debate)] {
+        let Some(kind) = kind else {
+            continue;
+        };
+        if let Some(id) = kind.jtc_channel_id {
+            config = guild_config::set_jtc_channel(pool, guild_id, casual, id as i64).await?;
+        }
+        if let Some(id) = kind.category_id {
+            config = guild_config::set_category(pool, guild_id, casual, id as i64).await?;
+        }
+        if let Some(id) = kind.rules_channel_id {
+            config = guild_config::set_rules_channel(pool, guild_id, casual, id as i64).await?;
+        }
+        if let Some(ref ids) = kind.overflow_category_ids {
+            let ids: Vec<i64> = ids.iter().map(|&id| id as i64).collect();
+            config = guild_config::set_overflow_categories(pool, guild_id, casual, &ids).await?;
+        }
+        if let Some(ref tags) = kind.default_tags {
+            // Validated by parse_specs; stored in the same form the tag selector uses
+            let tags = normalize_tags(tags, TagCase::Title).unwrap_or_default();
+            config = guild_config::set_default_tags(pool, guild_id, casual, &tags).await?;
+        }
+    }
+
+    if let Some(id) = spec.appeal_channel_id {
+        config = guild_config::set_appeal_channel(pool, guild_id, Some(id as i64)).await?;
+    }
+    if let Some(id) = spec.mod_log_channel_id {
+        config = guild_config::set_mod_log_channel(pool, guild_id, Some(id as i64)).await?;
+    }
+    if let Some(enabled) = spec.announce_global_mute_joins {
+        config = guild_config::set_announce_global_mute_joins(pool, guild_id, enabled).await?;
+    }
+    if let Some(enabled) = spec.create_discussion_channels {
+        config = guild_config::set_create_discussion_channels(pool, guild_id, enabled).await?;
+    }
+    if let Some(policy) = spec.owner_hop_policy.as_deref().and_then(OwnerHopPolicy::parse) {
+        config = guild_config::set_owner_hop_policy(pool, guild_id, policy).await?;
+    }
+    if let Some(seconds) = spec.empty_channel_grace_seconds {
+        config =
+            guild_config::set_empty_channel_grace_seconds(pool, guild_id, seconds as i32).await?;
+    }
+    if let Some(enabled) = spec.announce_room_mod_actions {
+        config = guild_config::set_announce_room_mod_actions(pool, guild_id, enabled).await?;
+    }
+    if let Some(joins) = spec.raid_lock_joins {
+        // 0 disables the lock
+        let joins = (joins > 0).then_some(joins as i32);
+        config = guild_config::set_raid_lock_joins(pool, guild_id, joins).await?;
+    }
+    if let Some(length) = spec.max_name_length {
+        config = guild_config::set_max_name_length(pool, guild_id, length as i32).await?;
+    }
+    if let Some(id) = spec.owner_role_id {
+        config = guild_config::set_owner_role(pool, guild_id, Some(id as i64)).await?;
+    }
+    if let Some(keep) = spec.keep_mutes_on_channel_delete {
+        config = guild_config::set_keep_mutes_on_channel_delete(pool, guild_id, keep).await?;
+    }
+    if let Some(enabled) = spec.send_welcome_dm {
+        config = guild_config::set_send_welcome_dm(pool, guild_id, enabled).await?;
+    }
+    if let Some(target) = spec.spam_prompt_target.as_deref().and_then(SpamPromptTarget::parse) {
+        config = guild_config::set_spam_prompt_target(pool, guild_id, target).await?;
+    }
+    if let Some(keep) = spec.keep_rooms_while_streaming {
+        config = guild_config::set_keep_rooms_while_streaming(pool, guild_id, keep).await?;
+    }
+    if let Some(id) = spec.channel_created_notify_channel_id {
+        config =
+            guild_config::set_channel_created_notify_channel(pool, guild_id, Some(id as i64))
+                .await?;
+    }
+    if spec.spam_prompt_threshold.is_some()
+        || spec.spam_timeout_threshold.is_some()
+        || spec.spam_window_seconds.is_some()
+    {
+        config = guild_config::set_spam_thresholds(
+            pool,
+            guild_id,
+            spec.spam_prompt_threshold.map(|n| n as i32),
+            spec.spam_timeout_threshold.map(|n| n as i32),
+            spec.spam_window_seconds.map(|n| n as i32),
+        )
+        .await?;
+    }
+    if let Some(required) = spec.require_tags {
+        config = guild_config::set_require_tags(pool, guild_id, required).await?;
+    }
+    if let Some(seconds) = spec.recreate_cooldown_seconds {
+        config =
+            guild_config::set_recreate_cooldown_seconds(pool, guild_id, seconds as i32).await?;
+    }
+    for role in spec.room_permissions.iter().flatten() {
+        // Validated by parse_specs
+        let (allow, deny) = role.permissions().unwrap_or_default();
+        room_permission::set(
+            pool,
+            guild_id,
+            role.role_id as i64,
+            allow.bits() as i64,
+            deny.bits() as i64,
+        )
+        .await?;
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_guild() {
+        let specs = parse_specs(
+            r#"{"guild_id": 1, "casual": {"jtc_channel_id": 2, "category_id": 3}, "owner_hop_policy": "hold"}"#,
+        )
+        .unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].guild_id, 1);
+        assert_eq!(specs[0].casual.as_ref().unwrap().category_id, Some(3));
+        assert!(specs[0].debate.is_none());
+    }
+
+    #[test]
+    fn parses_guild_array() {
+        let specs = parse_specs(r#"[{"guild_id": 1}, {"guild_id": 2, "mod_log_channel_id": 9}]"#).unwrap();
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[1].mod_log_channel_id, Some(9));
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        assert!(parse_specs("[]").is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "owner_hop_policy": "never"}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "empty_channel_grace_seconds": 3600}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "recreate_cooldown_seconds": 7200}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "max_name_length": 150}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "spam_prompt_threshold": 20}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "spam_window_seconds": 3600}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "spam_prompt_threshold": 3, "spam_timeout_threshold": 6}"#).is_ok());
+        assert!(parse_specs(r#"{"guild_id": 1, "spam_prompt_target": "mods"}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "room_permissions": [{"role_id": 2, "allow": "connect", "deny": "connect"}]}"#).is_err());
+        assert!(parse_specs(r#"{"guild_id": 1, "casual": {"default_tags": ["Not A Tag"]}}"#).is_err());
+        // Typos are caught rather than silently ignored
+        let err = parse_specs(r#"{"guild_id": 1, "mod_log_chanel_id": 9}"#).unwrap_err();
+        assert!(err.contains("mod_log_chanel_id"), "{}", err);
+    }
+}
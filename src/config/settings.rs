@@ -1,7 +1,13 @@
 use std::env;
 
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::constants::tags::TagCase;
+use crate::db::models::SpamThresholds;
 use crate::constants::timeouts::{
-    DEFAULT_SPAM_PROMPT_THRESHOLD, DEFAULT_SPAM_TIMEOUT_THRESHOLD, DEFAULT_SPAM_WINDOW_SECONDS,
+    DEFAULT_BACKUP_INTERVAL_HOURS, DEFAULT_COMPONENT_LIFETIME_SECONDS,
+    DEFAULT_EMPTY_SWEEP_GRACE_SECONDS, DEFAULT_SPAM_PROMPT_THRESHOLD, DEFAULT_SPAM_TIMEOUT_THRESHOLD,
+    DEFAULT_SPAM_WINDOW_SECONDS, DEFAULT_STARTUP_MAX_ATTEMPTS, DEFAULT_UNMUTE_GRACE_SECONDS,
 };
 
 #[derive(Debug, Clone)]
@@ -10,21 +16,93 @@ pub struct Settings {
     pub database_url: String,
     pub donate_link: Option<String>,
     pub guild_id: Option<u64>,
+    /// Bot operators on top of the application's owners, allowed to run operator-only commands
+    pub owner_ids: Vec<u64>,
     /// Spam detection: events before prompting owner
     pub spam_prompt_threshold: u32,
     /// Spam detection: events before auto-timeout
     pub spam_timeout_threshold: u32,
     /// Spam detection: rolling window in seconds
     pub spam_window_seconds: u64,
+    /// Minimum channel age in seconds before the empty-channel sweep may delete it
+    pub empty_sweep_grace_seconds: u64,
+    /// Casing applied when normalizing channel tags
+    pub tag_case: TagCase,
+    /// Seconds before a user who left a channel they were muted in is unmuted
+    pub unmute_grace_seconds: u64,
+    /// Attempts at the initial Discord connection before exiting
+    pub startup_max_attempts: u32,
+    /// Delete a member's saved VC preferences when they leave a guild
+    pub prune_preferences_on_leave: bool,
+    /// Seconds before unanswered spam alert buttons are removed
+    pub component_lifetime_seconds: u64,
+    /// Scheduled table backups, if an S3-compatible bucket is configured
+    pub backup: Option<BackupSettings>,
+}
+
+/// Where and how often to upload table backups
+#[derive(Debug, Clone)]
+pub struct BackupSettings {
+    /// Base URL of the S3-compatible service, e.g. https://s3.us-east-1.amazonaws.com
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Key prefix each run's timestamped folder is written under
+    pub prefix: String,
+    pub interval_hours: u64,
+}
+
+impl BackupSettings {
+    /// Read `BACKUP_S3_*` settings; `None` when no bucket is set
+    fn from_env() -> Result<Option<Self>, String> {
+        let Some(bucket) = env::var("BACKUP_S3_BUCKET").ok().filter(|s| !s.is_empty()) else {
+            return Ok(None);
+        };
+
+        let required = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("{} must be set when BACKUP_S3_BUCKET is set", name))
+        };
+
+        Ok(Some(Self {
+            endpoint: required("BACKUP_S3_ENDPOINT")?,
+            bucket,
+            region: env::var("BACKUP_S3_REGION")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "us-east-1".to_string()),
+            access_key: required("BACKUP_S3_ACCESS_KEY")?,
+            secret_key: required("BACKUP_S3_SECRET_KEY")?,
+            prefix: env::var("BACKUP_S3_PREFIX")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "jarvis".to_string()),
+            interval_hours: env::var("BACKUP_INTERVAL_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_BACKUP_INTERVAL_HOURS),
+        }))
+    }
 }
 
 impl Settings {
+    /// Resolve the database URL alone, for offline tools that don't need a bot token
+    pub fn database_url_from_env() -> Result<String, String> {
+        match env::var("DATABASE_URL").ok().filter(|s| !s.is_empty()) {
+            Some(url) => Ok(url),
+            None => database_url_from_components(),
+        }
+    }
+
     pub fn from_env() -> Result<Self, String> {
         let discord_token = env::var("DISCORD_TOKEN")
             .map_err(|_| "DISCORD_TOKEN environment variable not set")?;
 
-        let database_url = env::var("DATABASE_URL")
-            .map_err(|_| "DATABASE_URL environment variable not set")?;
+        let database_url = Self::database_url_from_env()?;
 
         let donate_link = env::var("DONATE_LINK").ok().filter(|s| !s.is_empty());
 
@@ -32,6 +110,12 @@ impl Settings {
             .ok()
             .and_then(|s| s.parse::<u64>().ok());
 
+        let owner_ids = match env::var("OWNER_IDS") {
+            Ok(s) => parse_id_list(&s)
+                .map_err(|id| format!("OWNER_IDS has an invalid user ID: {}", id))?,
+            Err(_) => Vec::new(),
+        };
+
         let spam_prompt_threshold = env::var("SPAM_PROMPT_THRESHOLD")
             .ok()
             .and_then(|s| s.parse().ok())
@@ -47,14 +131,162 @@ impl Settings {
             .and_then(|s| s.parse().ok())
             .unwrap_or(DEFAULT_SPAM_WINDOW_SECONDS);
 
+        let empty_sweep_grace_seconds = env::var("EMPTY_SWEEP_GRACE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_EMPTY_SWEEP_GRACE_SECONDS);
+
+        let tag_case = match env::var("TAG_CASE").ok().filter(|s| !s.is_empty()) {
+            Some(s) => TagCase::parse(&s)
+                .ok_or_else(|| format!("TAG_CASE must be 'title' or 'lower', got: {}", s))?,
+            None => TagCase::Title,
+        };
+
+        let unmute_grace_seconds = env::var("UNMUTE_GRACE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_UNMUTE_GRACE_SECONDS);
+
+        let startup_max_attempts = env::var("STARTUP_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_STARTUP_MAX_ATTEMPTS);
+
+        let prune_preferences_on_leave = env::var("PRUNE_PREFERENCES_ON_LEAVE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false);
+
+        let component_lifetime_seconds = env::var("COMPONENT_LIFETIME_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_COMPONENT_LIFETIME_SECONDS);
+
+        let backup = BackupSettings::from_env()?;
+
         Ok(Self {
             discord_token,
             database_url,
             donate_link,
             guild_id,
+            owner_ids,
             spam_prompt_threshold,
             spam_timeout_threshold,
             spam_window_seconds,
+            empty_sweep_grace_seconds,
+            tag_case,
+            unmute_grace_seconds,
+            startup_max_attempts,
+            prune_preferences_on_leave,
+            component_lifetime_seconds,
+            backup,
         })
     }
+
+    /// The bot-wide spam thresholds, used where a guild hasn't set its own
+    pub fn spam_thresholds(&self) -> SpamThresholds {
+        SpamThresholds {
+            prompt: self.spam_prompt_threshold,
+            timeout: self.spam_timeout_threshold,
+            window_seconds: self.spam_window_seconds,
+        }
+    }
+}
+
+/// Characters left unescaped in URL userinfo components (RFC 3986 unreserved set)
+const USERINFO_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Assemble a connection string from the discrete `PG*` variables
+/// Used when credentials are injected separately (e.g. Kubernetes secrets)
+fn database_url_from_components() -> Result<String, String> {
+    let host = env::var("PGHOST").map_err(|_| {
+        "Neither DATABASE_URL nor PGHOST environment variable is set".to_string()
+    })?;
+    let user = env::var("PGUSER").map_err(|_| "PGUSER environment variable not set")?;
+    let database = env::var("PGDATABASE").map_err(|_| "PGDATABASE environment variable not set")?;
+    let password = env::var("PGPASSWORD").ok();
+    let port = match env::var("PGPORT") {
+        Ok(p) => Some(
+            p.parse::<u16>()
+                .map_err(|_| format!("PGPORT is not a valid port: {}", p))?,
+        ),
+        Err(_) => None,
+    };
+
+    Ok(build_database_url(&host, port, &user, password.as_deref(), &database))
+}
+
+/// Parse a comma-separated list of Discord IDs, failing with the first entry that isn't one
+fn parse_id_list(s: &str) -> Result<Vec<u64>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<u64>().ok().filter(|&n| n > 0).ok_or_else(|| id.to_string()))
+        .collect()
+}
+
+/// Build a postgres:// URL, percent-encoding the user, password and database name
+fn build_database_url(
+    host: &str,
+    port: Option<u16>,
+    user: &str,
+    password: Option<&str>,
+    database: &str,
+) -> String {
+    let mut url = format!("postgres://{}", utf8_percent_encode(user, USERINFO_ENCODE_SET));
+
+    if let Some(password) = password.filter(|p| !p.is_empty()) {
+        url.push(':');
+        url.push_str(&utf8_percent_encode(password, USERINFO_ENCODE_SET).to_string());
+    }
+
+    url.push('@');
+    url.push_str(host);
+
+    if let Some(port) = port {
+        url.push_str(&format!(":{}", port));
+    }
+
+    url.push('/');
+    url.push_str(&utf8_percent_encode(database, USERINFO_ENCODE_SET).to_string());
+
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_database_url() {
+        assert_eq!(
+            build_database_url("db", Some(5432), "jarvis", Some("secret"), "jarvis"),
+            "postgres://jarvis:secret@db:5432/jarvis"
+        );
+        assert_eq!(
+            build_database_url("db", None, "jarvis", None, "jarvis"),
+            "postgres://jarvis@db/jarvis"
+        );
+    }
+
+    #[test]
+    fn test_parse_id_list() {
+        assert_eq!(parse_id_list("1, 22,333"), Ok(vec![1, 22, 333]));
+        assert_eq!(parse_id_list(" 1,, 2, "), Ok(vec![1, 2]));
+        assert_eq!(parse_id_list(""), Ok(vec![]));
+        assert_eq!(parse_id_list("1, alice"), Err("alice".to_string()));
+        assert_eq!(parse_id_list("0"), Err("0".to_string()));
+    }
+
+    #[test]
+    fn test_password_is_encoded() {
+        assert_eq!(
+            build_database_url("db", None, "jarvis", Some("p@ss:w/rd#1"), "jarvis"),
+            "postgres://jarvis:p%40ss%3Aw%2Frd%231@db/jarvis"
+        );
+    }
 }
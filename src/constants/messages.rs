@@ -0,0 +1,29 @@
+//! Default wording for user-facing messages that guilds can override
+//! Placeholders in `{braces}` are filled in by `formatting::fill_template`
+
+/// Maximum length of a custom message template
+pub const MAX_TEMPLATE_LENGTH: usize = 1500;
+
+/// Spam alert sent to the channel. Placeholders: `{user}`, `{owner}`, `{channel}`
+pub const DEFAULT_SPAM_PROMPT: &str = "{user} has been joining and leaving rapidly.\n\n\
+    Would you like to ban them from this channel?";
+
+/// DM sent when an unconfigured channel is deleted. Placeholders: `{user}`, `{seconds}`
+pub const DEFAULT_DEADLINE_DM: &str = "Your voice channel was automatically deleted because it wasn't configured in time.\n\n\
+    When you create a new channel, you have {seconds} seconds to set a name. \
+    If you don't configure it, the channel is removed to keep things tidy.\n\n\
+    Simply join the channel again to create a new one.";
+
+/// Naming prompt posted in a new channel. Placeholders: `{owner}`, `{channel}`, `{seconds}`
+pub const DEFAULT_NAMING_PROMPT: &str = "Welcome {owner}.\n\n\
+    • You have **{seconds} seconds** to set up your channel\n\
+    • Click the button below to choose a name\n\
+    • Your preference will be saved for next time\n\n\
+    If not configured, this channel will be deleted.";
+
+/// One-time DM sent to a first-time room owner. Placeholders: `{user}`, `{channel}`
+pub const DEFAULT_WELCOME_DM: &str = "Welcome {user}, {channel} is your first room here. A few things worth knowing:\n\n\
+    • **Owner panel** - the message in the room's chat has menus and buttons to manage it\n\
+    • **Mute & ban** - pick a user in the panel menus, or use `/mute` and `/vcban`\n\
+    • **Rename** - use `/rename` or **Reconfigure**; your name and tags are saved for next time\n\n\
+    The room is deleted once everyone has left.";
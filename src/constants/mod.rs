@@ -1,3 +1,4 @@
 pub mod embeds;
+pub mod messages;
 pub mod tags;
 pub mod timeouts;
@@ -1,3 +1,7 @@
+use serenity::all::{EmojiId, ReactionType};
+use serenity::utils::parse_emoji;
+use unicode_normalization::UnicodeNormalization;
+
 /// Maximum number of tags a user can select
 pub const MAX_TAGS: usize = 4;
 
@@ -47,3 +51,294 @@ pub fn get_tags(casual: bool) -> &'static [&'static str] {
         DEBATE_TAGS
     }
 }
+
+/// Maximum length of a single tag (keeps the channel status well under Discord's limit)
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// Most emoji tags a guild can add per channel type (a select menu holds 25 options)
+pub const MAX_EMOJI_TAGS: usize = 10;
+
+/// Discord's limit on a voice channel status
+pub const MAX_STATUS_LENGTH: usize = 500;
+
+/// Built-in tags for a channel type followed by the guild's emoji tags
+pub fn available_tags(casual: bool, emoji_tags: &[String]) -> Vec<&str> {
+    get_tags(casual)
+        .iter()
+        .copied()
+        .chain(emoji_tags.iter().map(String::as_str))
+        .collect()
+}
+
+/// A tag made of a single emoji: a custom guild emoji or a unicode emoji
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmojiTag {
+    Custom { id: EmojiId, name: String, animated: bool },
+    Unicode(String),
+}
+
+impl EmojiTag {
+    /// Parse a custom emoji mention (`<:name:id>`) or a unicode emoji
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        if let Some(emoji) = parse_emoji(raw) {
+            return Some(EmojiTag::Custom {
+                id: emoji.id,
+                name: emoji.name,
+                animated: emoji.animated,
+            });
+        }
+        is_unicode_emoji(raw).then(|| EmojiTag::Unicode(raw.to_string()))
+    }
+
+    /// The stored form, which is also how it renders in messages and the channel status
+    pub fn as_tag(&self) -> String {
+        match self {
+            EmojiTag::Custom { id, name, animated } => {
+                format!("<{}:{}:{}>", if *animated { "a" } else { "" }, name, id)
+            }
+            EmojiTag::Unicode(emoji) => emoji.clone(),
+        }
+    }
+
+    /// Short text for places that can't show custom emoji, like select menu labels
+    pub fn label(&self) -> String {
+        match self {
+            EmojiTag::Custom { name, .. } => format!(":{}:", name),
+            EmojiTag::Unicode(emoji) => emoji.clone(),
+        }
+    }
+
+    pub fn reaction(&self) -> ReactionType {
+        match self {
+            EmojiTag::Custom { id, name, animated } => ReactionType::Custom {
+                animated: *animated,
+                id: *id,
+                name: Some(name.clone()),
+            },
+            EmojiTag::Unicode(emoji) => ReactionType::Unicode(emoji.clone()),
+        }
+    }
+}
+
+/// Whether `s` is a single unicode emoji, including ZWJ sequences, skin tones and flags
+fn is_unicode_emoji(s: &str) -> bool {
+    let mut pictographs = 0;
+    for c in s.chars() {
+        match c as u32 {
+            0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x2300..=0x23FF => {
+                pictographs += 1
+            }
+            // Zero width joiner, variation selectors, keycap and tag characters glue sequences together
+            0x200D | 0xFE0E | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F => {}
+            _ => return false,
+        }
+    }
+    // Long sequences are several emoji, not one (the longest single ones are family ZWJ sequences)
+    (1..=8).contains(&pictographs) && s.chars().count() <= 16
+}
+
+/// Render tags for a channel status: emoji as-is, text tags in backticks
+/// Tags that would push the status past Discord's limit are left off
+pub fn format_status(tags: &[String]) -> String {
+    let mut status = String::new();
+    for tag in tags {
+        let piece = if EmojiTag::parse(tag).is_some() {
+            tag.clone()
+        } else {
+            format!("`{}`", tag)
+        };
+        let separator = if status.is_empty() { 0 } else { 1 };
+        if status.chars().count() + separator + piece.chars().count() > MAX_STATUS_LENGTH {
+            continue;
+        }
+        if separator == 1 {
+            status.push(' ');
+        }
+        status.push_str(&piece);
+    }
+    status
+}
+
+/// Casing applied to tags when they are normalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagCase {
+    /// "Gaming", "Deep Talk"
+    Title,
+    /// "gaming", "deep talk"
+    Lower,
+}
+
+impl TagCase {
+    /// Parse from a config value ("title" or "lower")
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "title" => Some(TagCase::Title),
+            "lower" => Some(TagCase::Lower),
+            _ => None,
+        }
+    }
+}
+
+/// Normalize a single tag: NFKC, trim, collapse whitespace, strip disallowed characters and apply casing
+/// Allowed characters are letters, digits, spaces, '-' and '&'
+/// NFKC runs first so decomposed accents and full-width letters compare equal to their plain forms
+/// Emoji tags are kept as they are, in their canonical form
+pub fn normalize_tag(raw: &str, case: TagCase) -> Result<String, String> {
+    if let Some(emoji) = EmojiTag::parse(raw) {
+        return Ok(emoji.as_tag());
+    }
+
+    let cleaned: String = raw
+        .nfkc()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '-' || *c == '&')
+        .collect();
+
+    let words: Vec<String> = cleaned
+        .split_whitespace()
+        .map(|word| match case {
+            TagCase::Lower => word.to_lowercase(),
+            TagCase::Title => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                    None => String::new(),
+                }
+            }
+        })
+        .collect();
+
+    let tag = words.join(" ");
+
+    if tag.is_empty() {
+        return Err(format!("'{}' is not a valid tag.", raw.trim()));
+    }
+
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(format!(
+            "Tags must be {} characters or less ('{}' is too long).",
+            MAX_TAG_LENGTH, tag
+        ));
+    }
+
+    Ok(tag)
+}
+
+/// Whether an already-normalized tag is one of `available`, compared in normalized form
+pub fn is_available_tag(tag: &str, available: &[&str], case: TagCase) -> bool {
+    available
+        .iter()
+        .any(|t| normalize_tag(t, case).is_ok_and(|t| t == tag))
+}
+
+/// Normalize a list of tags, dropping duplicates and enforcing `MAX_TAGS`
+pub fn normalize_tags<S: AsRef<str>>(raw: &[S], case: TagCase) -> Result<Vec<String>, String> {
+    let mut tags: Vec<String> = Vec::new();
+
+    for tag in raw {
+        let tag = normalize_tag(tag.as_ref(), case)?;
+        if !tags.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+            tags.push(tag);
+        }
+    }
+
+    if tags.len() > MAX_TAGS {
+        return Err(format!("You can only select up to {} tags.", MAX_TAGS));
+    }
+
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag_casing() {
+        assert_eq!(normalize_tag("  gaming ", TagCase::Title), Ok("Gaming".to_string()));
+        assert_eq!(normalize_tag("GAMING", TagCase::Lower), Ok("gaming".to_string()));
+        assert_eq!(normalize_tag("deep   TALK", TagCase::Title), Ok("Deep Talk".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_tag_strips_disallowed() {
+        assert_eq!(normalize_tag("`Music`!", TagCase::Title), Ok("Music".to_string()));
+        assert_eq!(normalize_tag("r&b", TagCase::Title), Ok("R&b".to_string()));
+        assert_eq!(normalize_tag("lo-fi", TagCase::Lower), Ok("lo-fi".to_string()));
+        assert!(normalize_tag("***", TagCase::Title).is_err());
+        assert!(normalize_tag("", TagCase::Title).is_err());
+    }
+
+    #[test]
+    fn test_normalize_tag_length() {
+        let max = "a".repeat(MAX_TAG_LENGTH);
+        assert!(normalize_tag(&max, TagCase::Lower).is_ok());
+        assert!(normalize_tag(&format!("{}a", max), TagCase::Lower).is_err());
+    }
+
+    #[test]
+    fn test_normalize_tags_dedupes_and_limits() {
+        assert_eq!(
+            normalize_tags(&["gaming", "Gaming", "MUSIC"], TagCase::Title),
+            Ok(vec!["Gaming".to_string(), "Music".to_string()])
+        );
+        assert!(normalize_tags(&["a", "b", "c", "d", "e"], TagCase::Title).is_err());
+        assert!(normalize_tags(&["a", "b", "c", "d", "A"], TagCase::Title).is_ok());
+    }
+
+    #[test]
+    fn test_builtin_tags_are_normalized() {
+        for tag in CASUAL_TAGS.iter().chain(DEBATE_TAGS) {
+            assert_eq!(normalize_tag(tag, TagCase::Title).as_deref(), Ok(*tag));
+        }
+    }
+
+    #[test]
+    fn test_available_tags_match_case_insensitively() {
+        for raw in ["gaming", "GAMING", "  Gaming "] {
+            let tag = normalize_tag(raw, TagCase::Title).unwrap();
+            assert!(is_available_tag(&tag, CASUAL_TAGS, TagCase::Title), "{}", raw);
+        }
+        let tag = normalize_tag("Gaming", TagCase::Lower).unwrap();
+        assert!(is_available_tag(&tag, CASUAL_TAGS, TagCase::Lower));
+        assert!(!is_available_tag("Gamer", CASUAL_TAGS, TagCase::Title));
+    }
+
+    #[test]
+    fn test_emoji_tags() {
+        assert_eq!(normalize_tag(" 🎮 ", TagCase::Lower), Ok("🎮".to_string()));
+        assert_eq!(
+            normalize_tag("<:pog:123456789012345678>", TagCase::Title),
+            Ok("<:pog:123456789012345678>".to_string())
+        );
+        // Skin tones and ZWJ sequences are one emoji
+        assert!(EmojiTag::parse("👍🏽").is_some());
+        assert!(EmojiTag::parse("👩\u{200D}💻").is_some());
+        assert!(EmojiTag::parse("🎮 Gaming").is_none());
+        assert!(EmojiTag::parse("Gaming").is_none());
+
+        let emoji = vec!["🎮".to_string()];
+        assert!(is_available_tag("🎮", &available_tags(true, &emoji), TagCase::Title));
+        assert!(!is_available_tag("🎮", CASUAL_TAGS, TagCase::Title));
+    }
+
+    #[test]
+    fn test_format_status() {
+        let tags = vec!["Gaming".to_string(), "🎮".to_string(), "<a:wave:42>".to_string()];
+        assert_eq!(format_status(&tags), "`Gaming` 🎮 <a:wave:42>");
+        assert_eq!(format_status(&[]), "");
+
+        let long: Vec<String> = (0..20).map(|_| "a".repeat(MAX_TAG_LENGTH)).collect();
+        assert!(format_status(&long).chars().count() <= MAX_STATUS_LENGTH);
+    }
+
+    #[test]
+    fn test_unicode_forms_normalize_equal() {
+        // Composed "é" vs "e" + combining acute accent
+        let composed = normalize_tag("Caf\u{e9}", TagCase::Title).unwrap();
+        let decomposed = normalize_tag("Cafe\u{301}", TagCase::Title).unwrap();
+        assert_eq!(composed, decomposed);
+        // Full-width letters from some mobile keyboards
+        assert_eq!(normalize_tag("\u{ff27}\u{ff41}\u{ff4d}\u{ff49}\u{ff4e}\u{ff47}", TagCase::Title), Ok("Gaming".to_string()));
+    }
+}
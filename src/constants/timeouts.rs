@@ -4,6 +4,13 @@ use std::time::Duration;
 pub const DEFAULT_SPAM_PROMPT_THRESHOLD: u32 = 5;   // Join/leave count to prompt owner
 pub const DEFAULT_SPAM_TIMEOUT_THRESHOLD: u32 = 10; // Join/leave count for auto-timeout
 pub const DEFAULT_SPAM_WINDOW_SECONDS: u64 = 60;    // Rolling window for spam detection (1 minute)
+/// Bounds on what a guild can set its spam thresholds to
+pub const MIN_SPAM_PROMPT_THRESHOLD: u32 = 2;
+pub const MIN_SPAM_WINDOW_SECONDS: u64 = 10;
+pub const MAX_SPAM_WINDOW_SECONDS: u64 = 10 * 60;
+
+/// Channels younger than this are never swept as empty (owner may not have been moved in yet)
+pub const DEFAULT_EMPTY_SWEEP_GRACE_SECONDS: u64 = 30;
 
 /// VC naming deadline
 pub const VC_NAMING_DEADLINE_SECONDS: u64 = 60;
@@ -22,6 +29,8 @@ pub const TIMEOUT_DURATIONS: &[Duration] = &[
 
 /// Days of good behavior before timeout level resets
 pub const TIMEOUT_RESET_DAYS: i64 = 30;
+/// How often stale timeout levels are reset
+pub const TIMEOUT_DECAY_INTERVAL_SECONDS: u64 = 60 * 60;
 
 /// JTC flow timeout (how long user has to complete modal/tag selection)
 pub const JTC_FLOW_TIMEOUT_SECONDS: u64 = 120;
@@ -29,6 +38,84 @@ pub const JTC_FLOW_TIMEOUT_SECONDS: u64 = 120;
 /// Rate limit for rename and retag commands (30 minutes)
 pub const RENAME_RETAG_RATE_LIMIT_SECONDS: u64 = 30 * 60;
 
+/// Ban appeals: max appeals a user can submit per window
+pub const APPEAL_RATE_MAX: i64 = 3;
+pub const APPEAL_RATE_WINDOW_SECONDS: i64 = 24 * 60 * 60;
+
+/// Periodic sweep for DB rows of channels deleted outside the bot
+pub const ORPHAN_REAP_INTERVAL_SECONDS: u64 = 15 * 60;
+/// Max channels checked per sweep tick
+pub const ORPHAN_REAP_BATCH_SIZE: usize = 100;
+
+/// Delay before unmuting a user who left a channel they were muted in (overridable via UNMUTE_GRACE_SECONDS)
+/// Rejoining a muted channel within this window keeps the mute without any flicker
+pub const DEFAULT_UNMUTE_GRACE_SECONDS: u64 = 3;
+
+/// Initial Discord connection attempts before giving up (overridable via STARTUP_MAX_ATTEMPTS)
+pub const DEFAULT_STARTUP_MAX_ATTEMPTS: u32 = 5;
+/// Backoff between startup attempts: doubles from the base up to the max
+pub const STARTUP_BACKOFF_BASE_SECONDS: u64 = 5;
+pub const STARTUP_BACKOFF_MAX_SECONDS: u64 = 2 * 60;
+
+/// Upper bound for a guild's empty-channel grace period
+pub const MAX_EMPTY_CHANNEL_GRACE_SECONDS: u32 = 10 * 60;
+/// Upper bound for a guild's wait between a user's room being deleted and their next one
+pub const MAX_RECREATE_COOLDOWN_SECONDS: u32 = 30 * 60;
+
+/// Window in which a guild's `raid_lock_joins` joins to one room trigger an auto-lock
+pub const RAID_LOCK_WINDOW_SECONDS: u64 = 20;
+
+/// How long /diagnostics waits for the database before reporting it unreachable
+pub const DIAGNOSTICS_DB_TIMEOUT_SECONDS: u64 = 5;
+
+/// How long an in-room moderation notice stays up before the bot deletes it
+pub const ROOM_NOTICE_SECONDS: u64 = 8;
+
+/// How long a room is held for an owner who hopped to another managed channel
+pub const OWNER_HOP_HOLD_SECONDS: u64 = 2 * 60;
+/// How long to wait before looking at a room again after Discord refused to delete it
+/// Doubles with each attempt
+pub const DELETION_RETRY_SECONDS: u64 = 60;
+/// Refused deletions retried before the bot gives up and tells the mod log
+pub const MAX_DELETION_RETRIES: u32 = 5;
+
+/// JTC queue depth at which a backlog warning is logged
+pub const JTC_QUEUE_DEPTH_WARN: usize = 20;
+/// How often the JTC queue health summary is logged
+pub const JTC_QUEUE_HEALTH_LOG_SECONDS: u64 = 5 * 60;
+/// Estimated queue wait at which a user is DMed that their channel is on the way
+pub const JTC_QUEUE_NOTICE_SECONDS: u64 = 5;
+
+/// Window for the user-limit change rate limit (a few changes per owner and room)
+pub const LIMIT_RATE_WINDOW_SECONDS: u64 = 60 * 60;
+/// Window for the per-room owner transfer rate limit
+pub const TRANSFER_RATE_WINDOW_SECONDS: u64 = 10 * 60;
+/// Owner transfers allowed per room inside the window
+pub const TRANSFER_RATE_MAX_USES: usize = 3;
+/// How long a join into a room is remembered without a matching leave, to catch gaps in the
+/// voice state cache of very large guilds
+pub const VOICE_JOIN_MEMORY_SECONDS: u64 = 10 * 60;
+/// How long shutdown spends cleaning up empty rooms; `docker stop` kills the process after 10s
+pub const SHUTDOWN_CLEANUP_SECONDS: u64 = 8;
+/// How long a cached guild config is used before it's read again; /setup changes drop it sooner
+pub const GUILD_CONFIG_CACHE_SECONDS: u64 = 5 * 60;
+/// How long the spam detector waits before prompting an owner about the same user again
+pub const SPAM_PROMPT_COOLDOWN_SECONDS: u64 = 5 * 60;
+/// How often expired entries are pruned from the in-memory rate-limit and cooldown maps
+pub const STATE_PRUNE_INTERVAL_SECONDS: u64 = 10 * 60;
+/// How often the channel owner cache is resynced with the database
+pub const OWNER_CACHE_RECONCILE_INTERVAL_SECONDS: u64 = 30 * 60;
+/// How often timed mutes are checked for expiry
+pub const MUTE_EXPIRY_CHECK_INTERVAL_SECONDS: u64 = 15;
+/// How often expired interactive messages are cleaned up
+pub const COMPONENT_SWEEP_INTERVAL_SECONDS: u64 = 30;
+/// Rolling window for counting Discord rate limit hits
+pub const RATE_LIMIT_WINDOW_SECONDS: u64 = 30;
+/// Route rate limit hits inside the window before background work is throttled
+pub const RATE_LIMIT_HITS_TO_THROTTLE: usize = 5;
+/// Minimum time background work stays throttled after rate limiting is detected
+pub const RATE_LIMIT_THROTTLE_SECONDS: u64 = 30;
+
 /// Get timeout duration for a given level
 pub fn get_timeout_duration(level: u32) -> Duration {
     let level = level.min(TIMEOUT_DURATIONS.len() as u32 - 1) as usize;
@@ -52,3 +139,9 @@ pub fn format_duration(duration: Duration) -> String {
         format!("{} day{}", days, if days == 1 { "" } else { "s" })
     }
 }
+
+/// Hours between table backups to object storage (overridable via BACKUP_INTERVAL_HOURS)
+pub const DEFAULT_BACKUP_INTERVAL_HOURS: u64 = 24;
+
+/// How long spam alert buttons stay usable if nobody acts on them (overridable via COMPONENT_LIFETIME_SECONDS)
+pub const DEFAULT_COMPONENT_LIFETIME_SECONDS: u64 = 60 * 60;
@@ -0,0 +1,69 @@
+//! In-memory copies of guild configs for the voice join path
+//! Every JTC join needs the guild's config, so it's read from here instead of the database.
+//! Entries are dropped when /setup changes the guild, and expire on their own in case the
+//! config was changed from outside the bot (`--setup-guild`).
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use sqlx::PgPool;
+
+use crate::constants::timeouts::GUILD_CONFIG_CACHE_SECONDS;
+use crate::db::models::GuildConfig;
+use crate::db::queries::guild_config;
+
+const GUILD_CONFIG_CACHE_TTL: Duration = Duration::from_secs(GUILD_CONFIG_CACHE_SECONDS);
+
+/// Guild configs by guild_id, with when each was loaded
+#[derive(Debug, Default)]
+pub struct GuildConfigCache {
+    configs: DashMap<u64, (Instant, GuildConfig)>,
+}
+
+impl GuildConfigCache {
+    /// The guild's config, loaded from the database on a miss
+    /// Guilds without a config aren't cached, so setting one up takes effect right away.
+    pub async fn get(
+        &self,
+        pool: &PgPool,
+        guild_id: u64,
+    ) -> Result<Option<GuildConfig>, sqlx::Error> {
+        if let Some(config) = self.cached_at(Instant::now(), guild_id) {
+            return Ok(Some(config));
+        }
+
+        let config = guild_config::get(pool, guild_id as i64).await?;
+        if let Some(ref config) = config {
+            self.configs.insert(guild_id, (Instant::now(), config.clone()));
+        }
+
+        Ok(config)
+    }
+
+    fn cached_at(&self, now: Instant, guild_id: u64) -> Option<GuildConfig> {
+        let entry = self.configs.get(&guild_id)?;
+        let (loaded_at, ref config) = *entry;
+        is_fresh(loaded_at, now).then(|| config.clone())
+    }
+
+    /// Forget a guild's config after it changed
+    pub fn invalidate(&self, guild_id: u64) {
+        self.configs.remove(&guild_id);
+    }
+}
+
+fn is_fresh(loaded_at: Instant, now: Instant) -> bool {
+    now.duration_since(loaded_at) < GUILD_CONFIG_CACHE_TTL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire() {
+        let now = Instant::now();
+        assert!(is_fresh(now, now));
+        assert!(!is_fresh(now, now + GUILD_CONFIG_CACHE_TTL));
+    }
+}
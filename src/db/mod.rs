@@ -1,3 +1,5 @@
+pub mod config_cache;
 pub mod models;
 pub mod pool;
 pub mod queries;
+pub mod repository;
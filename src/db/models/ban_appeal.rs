@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Appeal status for rate limiting and review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppealStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+impl AppealStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppealStatus::Pending => "pending",
+            AppealStatus::Approved => "approved",
+            AppealStatus::Denied => "denied",
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BanAppeal {
+    pub id: Uuid,
+    /// None once the ban itself is gone (lifted or purged with its channel)
+    pub ban_id: Option<Uuid>,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub user_id: i64,
+    pub reason: String,
+    pub status: String,
+    pub resolved_by_user_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
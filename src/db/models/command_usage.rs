@@ -0,0 +1,7 @@
+/// Aggregated usage of one command over a time window
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CommandUsageCount {
+    pub command_name: String,
+    pub uses: i64,
+    pub guilds: i64,
+}
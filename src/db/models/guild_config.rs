@@ -1,5 +1,131 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 
+use crate::constants::messages::{
+    DEFAULT_DEADLINE_DM, DEFAULT_NAMING_PROMPT, DEFAULT_SPAM_PROMPT, DEFAULT_WELCOME_DM,
+};
+use crate::constants::timeouts::{
+    MAX_SPAM_WINDOW_SECONDS, MIN_SPAM_PROMPT_THRESHOLD, MIN_SPAM_WINDOW_SECONDS,
+};
+use crate::utils::profanity::{MAX_CHANNEL_NAME_LENGTH, MIN_CHANNEL_NAME_LENGTH};
+
+/// User-facing messages a guild can customize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageTemplate {
+    SpamPrompt,
+    DeadlineDm,
+    NamingPrompt,
+    WelcomeDm,
+}
+
+impl MessageTemplate {
+    /// Built-in wording used when the guild has no override
+    pub fn default_text(&self) -> &'static str {
+        match self {
+            MessageTemplate::SpamPrompt => DEFAULT_SPAM_PROMPT,
+            MessageTemplate::DeadlineDm => DEFAULT_DEADLINE_DM,
+            MessageTemplate::NamingPrompt => DEFAULT_NAMING_PROMPT,
+            MessageTemplate::WelcomeDm => DEFAULT_WELCOME_DM,
+        }
+    }
+}
+
+/// What happens to a room when its owner hops to another managed channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerHopPolicy {
+    /// Transfer or delete the room right away (same as leaving voice)
+    Release,
+    /// Keep the room for a grace period in case the owner comes back
+    Hold,
+    /// Hold the room and DM the owner a button to release it early
+    Prompt,
+}
+
+impl OwnerHopPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OwnerHopPolicy::Release => "release",
+            OwnerHopPolicy::Hold => "hold",
+            OwnerHopPolicy::Prompt => "prompt",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "release" => Some(OwnerHopPolicy::Release),
+            "hold" => Some(OwnerHopPolicy::Hold),
+            "prompt" => Some(OwnerHopPolicy::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// Join/leave counts inside a rolling window that make the spam detector act
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpamThresholds {
+    /// Activity that prompts the owner to ban or ignore the user
+    pub prompt: u32,
+    /// Activity that times the user out
+    pub timeout: u32,
+    pub window_seconds: u64,
+}
+
+impl SpamThresholds {
+    /// Check the thresholds are within bounds and the timeout comes after the prompt
+    pub fn validate(&self) -> Result<(), String> {
+        if self.prompt < MIN_SPAM_PROMPT_THRESHOLD {
+            return Err(format!(
+                "The prompt threshold must be at least {}.",
+                MIN_SPAM_PROMPT_THRESHOLD
+            ));
+        }
+        if self.timeout <= self.prompt {
+            return Err(format!(
+                "The timeout threshold ({}) must be above the prompt threshold ({}).",
+                self.timeout, self.prompt
+            ));
+        }
+        if !(MIN_SPAM_WINDOW_SECONDS..=MAX_SPAM_WINDOW_SECONDS).contains(&self.window_seconds) {
+            return Err(format!(
+                "The window must be between {} and {} seconds.",
+                MIN_SPAM_WINDOW_SECONDS, MAX_SPAM_WINDOW_SECONDS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Where the spam detector's ban/ignore prompt is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpamPromptTarget {
+    /// The room's text chat, visible to everyone in it
+    Channel,
+    /// A DM to the room owner
+    OwnerDm,
+    /// The guild's mod log channel
+    ModChannel,
+}
+
+impl SpamPromptTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpamPromptTarget::Channel => "channel",
+            SpamPromptTarget::OwnerDm => "owner_dm",
+            SpamPromptTarget::ModChannel => "mod_channel",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "channel" => Some(SpamPromptTarget::Channel),
+            "owner_dm" => Some(SpamPromptTarget::OwnerDm),
+            "mod_channel" => Some(SpamPromptTarget::ModChannel),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct GuildConfig {
     pub guild_id: i64,
@@ -9,6 +135,36 @@ pub struct GuildConfig {
     pub category_debate_id: Option<i64>,
     pub rules_casual_channel_id: Option<i64>,
     pub rules_debate_channel_id: Option<i64>,
+    pub spam_prompt_template: Option<String>,
+    pub deadline_dm_template: Option<String>,
+    pub naming_prompt_template: Option<String>,
+    pub appeal_channel_id: Option<i64>,
+    pub mod_log_channel_id: Option<i64>,
+    pub announce_global_mute_joins: bool,
+    pub create_discussion_channels: bool,
+    pub owner_hop_policy: String,
+    pub empty_channel_grace_seconds: i32,
+    pub announce_room_mod_actions: bool,
+    pub raid_lock_joins: Option<i32>,
+    pub overflow_casual_category_ids: Vec<i64>,
+    pub overflow_debate_category_ids: Vec<i64>,
+    pub max_name_length: i32,
+    pub owner_role_id: Option<i64>,
+    pub keep_mutes_on_channel_delete: bool,
+    pub default_casual_tags: Vec<String>,
+    pub default_debate_tags: Vec<String>,
+    pub send_welcome_dm: bool,
+    pub welcome_dm_template: Option<String>,
+    pub spam_prompt_target: String,
+    pub emoji_casual_tags: Vec<String>,
+    pub emoji_debate_tags: Vec<String>,
+    pub keep_rooms_while_streaming: bool,
+    pub channel_created_notify_channel_id: Option<i64>,
+    pub spam_prompt_threshold: Option<i32>,
+    pub spam_timeout_threshold: Option<i32>,
+    pub spam_window_seconds: Option<i32>,
+    pub require_tags: bool,
+    pub recreate_cooldown_seconds: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -23,6 +179,11 @@ impl GuildConfig {
         }
     }
 
+    /// Check if at least one channel type has a category set up
+    pub fn has_any_category(&self) -> bool {
+        self.category_casual_id.is_some() || self.category_debate_id.is_some()
+    }
+
     /// Get the JTC channel ID for a given type
     pub fn jtc_channel_id(&self, casual: bool) -> Option<i64> {
         if casual {
@@ -32,6 +193,17 @@ impl GuildConfig {
         }
     }
 
+    /// Whether `channel_id` is this guild's casual (Some(true)) or debate (Some(false)) JTC channel
+    pub fn jtc_type(&self, channel_id: i64) -> Option<bool> {
+        if self.jtc_casual_channel_id == Some(channel_id) {
+            Some(true)
+        } else if self.jtc_debate_channel_id == Some(channel_id) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
     /// Get the category ID for a given type
     pub fn category_id(&self, casual: bool) -> Option<i64> {
         if casual {
@@ -41,6 +213,38 @@ impl GuildConfig {
         }
     }
 
+    /// Get the overflow categories for a given type, tried in order once the main one is full
+    pub fn overflow_category_ids(&self, casual: bool) -> &[i64] {
+        if casual {
+            &self.overflow_casual_category_ids
+        } else {
+            &self.overflow_debate_category_ids
+        }
+    }
+
+    /// Get the tags given to new rooms of a type when the owner has no saved preference
+    pub fn default_tags(&self, casual: bool) -> &[String] {
+        if casual {
+            &self.default_casual_tags
+        } else {
+            &self.default_debate_tags
+        }
+    }
+
+    /// How long a user waits after their room is deleted before they can make another
+    pub fn recreate_cooldown(&self) -> Duration {
+        Duration::from_secs(self.recreate_cooldown_seconds.max(0) as u64)
+    }
+
+    /// Get the emoji tags this guild offers for a type, on top of the built-in tags
+    pub fn emoji_tags(&self, casual: bool) -> &[String] {
+        if casual {
+            &self.emoji_casual_tags
+        } else {
+            &self.emoji_debate_tags
+        }
+    }
+
     /// Get the rules channel ID for a given type
     pub fn rules_channel_id(&self, casual: bool) -> Option<i64> {
         if casual {
@@ -49,4 +253,42 @@ impl GuildConfig {
             self.rules_debate_channel_id
         }
     }
+
+    /// Get the owner hop policy, treating unknown values as Release
+    pub fn owner_hop_policy(&self) -> OwnerHopPolicy {
+        OwnerHopPolicy::parse(&self.owner_hop_policy).unwrap_or(OwnerHopPolicy::Release)
+    }
+
+    /// Get where spam prompts go, treating unknown values as the room's chat
+    pub fn spam_prompt_target(&self) -> SpamPromptTarget {
+        SpamPromptTarget::parse(&self.spam_prompt_target).unwrap_or(SpamPromptTarget::Channel)
+    }
+
+    /// The spam thresholds this guild uses, with `defaults` filling in anything it hasn't set
+    pub fn spam_thresholds(&self, defaults: SpamThresholds) -> SpamThresholds {
+        SpamThresholds {
+            prompt: self.spam_prompt_threshold.map_or(defaults.prompt, |n| n.max(0) as u32),
+            timeout: self.spam_timeout_threshold.map_or(defaults.timeout, |n| n.max(0) as u32),
+            window_seconds: self
+                .spam_window_seconds
+                .map_or(defaults.window_seconds, |n| n.max(0) as u64),
+        }
+    }
+
+    /// Longest room name this guild allows, never above Discord's limit
+    pub fn max_name_length(&self) -> usize {
+        (self.max_name_length.max(0) as usize).clamp(MIN_CHANNEL_NAME_LENGTH, MAX_CHANNEL_NAME_LENGTH)
+    }
+
+    /// Get the template for a message, falling back to the built-in default
+    pub fn message_template(&self, kind: MessageTemplate) -> &str {
+        let custom = match kind {
+            MessageTemplate::SpamPrompt => self.spam_prompt_template.as_deref(),
+            MessageTemplate::DeadlineDm => self.deadline_dm_template.as_deref(),
+            MessageTemplate::NamingPrompt => self.naming_prompt_template.as_deref(),
+            MessageTemplate::WelcomeDm => self.welcome_dm_template.as_deref(),
+        };
+
+        custom.unwrap_or_else(|| kind.default_text())
+    }
 }
@@ -1,15 +1,23 @@
+mod ban_appeal;
 mod ban_record;
+mod command_usage;
 mod global_mute;
 mod guild_config;
 mod mute_record;
+mod room_permission;
 mod spam_record;
 mod user_vc_preference;
 mod voice_channel;
 
+pub use ban_appeal::{AppealStatus, BanAppeal};
 pub use ban_record::BanRecord;
+pub use command_usage::CommandUsageCount;
 pub use global_mute::GlobalMute;
-pub use guild_config::GuildConfig;
+pub use guild_config::{
+    GuildConfig, MessageTemplate, OwnerHopPolicy, SpamPromptTarget, SpamThresholds,
+};
 pub use mute_record::MuteRecord;
+pub use room_permission::RoomPermission;
 pub use spam_record::SpamRecord;
 pub use user_vc_preference::{PendingVcDeadline, UserVcPreference};
 pub use voice_channel::{ChannelType, VoiceChannel};
@@ -9,8 +9,11 @@ pub struct MuteRecord {
     pub muted_user_id: i64,
     pub muted_by_user_id: i64,
     pub is_admin_mute: bool,
+    pub is_deafened: bool,
     pub muted_at: DateTime<Utc>,
     pub unmuted_at: Option<DateTime<Utc>>,
+    /// When a timed mute is lifted automatically; None for mutes that last until unmuted
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl MuteRecord {
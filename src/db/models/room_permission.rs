@@ -0,0 +1,10 @@
+/// A role overwrite applied to every room created in a guild
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoomPermission {
+    pub guild_id: i64,
+    pub role_id: i64,
+    /// Discord permission bitfield granted to the role
+    pub allow: i64,
+    /// Discord permission bitfield denied to the role
+    pub deny: i64,
+}
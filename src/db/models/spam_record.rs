@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
 
+use crate::constants::timeouts::TIMEOUT_RESET_DAYS;
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct SpamRecord {
     pub guild_id: i64,
@@ -12,11 +14,11 @@ pub struct SpamRecord {
 }
 
 impl SpamRecord {
-    /// Check if the timeout level should be reset (30 days of good behavior)
+    /// Check if the timeout level should be reset (`TIMEOUT_RESET_DAYS` of good behavior)
     pub fn should_reset(&self) -> bool {
         if let Some(last) = self.last_infraction_at {
             let days_since = (Utc::now() - last).num_days();
-            days_since >= 30
+            days_since >= TIMEOUT_RESET_DAYS
         } else {
             true
         }
@@ -35,6 +35,12 @@ pub struct VoiceChannel {
     pub topic: Option<String>,
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
+    /// Paired discussion text channel, if the guild creates them
+    pub text_channel_id: Option<i64>,
+    /// Locked by the owner so only people already inside can stay
+    pub locked: bool,
+    /// Auto-locked after a join flood, until the owner or a moderator unlocks it
+    pub raid_locked: bool,
 }
 
 impl VoiceChannel {
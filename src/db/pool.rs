@@ -1,3 +1,4 @@
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use tracing::info;
 
@@ -14,42 +15,86 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
     Ok(pool)
 }
 
-pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+/// Run a trivial query to check the database is reachable
+pub async fn ping(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1").execute(pool).await?;
+    Ok(())
+}
+
+/// Every file under migrations/, embedded at build time
+/// sqlx applies each once, in its own transaction, and records it in `_sqlx_migrations`.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
     info!("Running database migrations...");
 
-    // Read and execute migrations in order
-    let migrations = [
-        include_str!("../../migrations/001_initial_schema.sql"),
-        include_str!("../../migrations/002_guild_configs.sql"),
-        include_str!("../../migrations/003_channels.sql"),
-        include_str!("../../migrations/004_mute_history.sql"),
-        include_str!("../../migrations/005_ban_history.sql"),
-        include_str!("../../migrations/006_spam_tracking.sql"),
-        include_str!("../../migrations/007_user_vc_preferences.sql"),
-        include_str!("../../migrations/008_rate_limits.sql"),
-        include_str!("../../migrations/009_global_mutes.sql"),
-    ];
-
-    for (i, migration) in migrations.iter().enumerate() {
-        info!("Running migration {}", i + 1);
-        // Split migration by semicolons and execute each statement
-        for statement in migration.split(';') {
-            let statement = statement.trim();
-            if !statement.is_empty() {
-                // Skip if the object already exists (idempotent migrations)
-                if let Err(e) = sqlx::query(statement).execute(pool).await {
-                    // Ignore "already exists" errors
-                    let err_str = e.to_string();
-                    if !err_str.contains("already exists")
-                        && !err_str.contains("duplicate key")
-                    {
-                        return Err(e);
-                    }
-                }
-            }
+    adopt_schema_migrations(pool).await?;
+    MIGRATOR.run(pool).await?;
+
+    info!("Migrations completed successfully");
+    Ok(())
+}
+
+/// Carry over what the old runner recorded in `schema_migrations`, so those files aren't re-run
+/// Databases from before any tracking have neither table; their files are all idempotent and
+/// simply run again once.
+async fn adopt_schema_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    let (legacy, adopted): (bool, bool) = sqlx::query_as(
+        "SELECT to_regclass('schema_migrations') IS NOT NULL, \
+                to_regclass('_sqlx_migrations') IS NOT NULL",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !legacy || adopted {
+        return Ok(());
+    }
+
+    let applied: Vec<String> = sqlx::query_scalar("SELECT name FROM schema_migrations")
+        .fetch_all(pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    tx.ensure_migrations_table().await?;
+    for migration in MIGRATOR.iter() {
+        if !applied.iter().any(|name| legacy_version(name) == Some(migration.version)) {
+            continue;
         }
+
+        sqlx::query(
+            r#"
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES ($1, $2, TRUE, $3, 0)
+            "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *tx)
+        .await?;
     }
+    tx.commit().await?;
 
-    info!("Migrations completed successfully");
+    info!("Adopted {} migrations from schema_migrations", applied.len());
     Ok(())
 }
+
+/// The version sqlx gives a migration file, from the old runner's name for it ("027_jtc_channels")
+fn legacy_version(name: &str) -> Option<i64> {
+    name.split_once('_')?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_names_map_to_versions() {
+        assert_eq!(legacy_version("001_initial_schema"), Some(1));
+        assert_eq!(legacy_version("038_recreate_cooldown"), Some(38));
+        assert_eq!(legacy_version("initial_schema"), None);
+
+        let versions: Vec<i64> = MIGRATOR.iter().map(|m| m.version).collect();
+        assert_eq!(versions.first(), Some(&1));
+        assert!(versions.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+}
@@ -0,0 +1,96 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::models::{AppealStatus, BanAppeal};
+
+/// Create an appeal for a ban. Returns None if the ban was already appealed
+pub async fn create(
+    pool: &PgPool,
+    ban_id: Uuid,
+    guild_id: i64,
+    channel_id: i64,
+    user_id: i64,
+    reason: &str,
+) -> Result<Option<BanAppeal>, sqlx::Error> {
+    sqlx::query_as::<_, BanAppeal>(
+        r#"
+        INSERT INTO ban_appeals (ban_id, guild_id, channel_id, user_id, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (ban_id) DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(ban_id)
+    .bind(guild_id)
+    .bind(channel_id)
+    .bind(user_id)
+    .bind(reason)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete an appeal that never reached the moderators, so the user can submit it again
+pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM ban_appeals WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get(pool: &PgPool, id: Uuid) -> Result<Option<BanAppeal>, sqlx::Error> {
+    sqlx::query_as::<_, BanAppeal>("SELECT * FROM ban_appeals WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_by_ban(pool: &PgPool, ban_id: Uuid) -> Result<Option<BanAppeal>, sqlx::Error> {
+    sqlx::query_as::<_, BanAppeal>("SELECT * FROM ban_appeals WHERE ban_id = $1")
+        .bind(ban_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Resolve a pending appeal. Returns None if it was already resolved
+pub async fn resolve(
+    pool: &PgPool,
+    id: Uuid,
+    status: AppealStatus,
+    resolved_by_user_id: i64,
+) -> Result<Option<BanAppeal>, sqlx::Error> {
+    sqlx::query_as::<_, BanAppeal>(
+        r#"
+        UPDATE ban_appeals
+        SET status = $2, resolved_by_user_id = $3, resolved_at = NOW()
+        WHERE id = $1 AND status = 'pending'
+        RETURNING *
+        "#
+    )
+    .bind(id)
+    .bind(status.as_str())
+    .bind(resolved_by_user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Count appeals a user has submitted within the last `window_seconds`
+pub async fn count_recent_by_user(
+    pool: &PgPool,
+    user_id: i64,
+    window_seconds: i64,
+) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM ban_appeals
+        WHERE user_id = $1 AND created_at > NOW() - make_interval(secs => $2)
+        "#
+    )
+    .bind(user_id)
+    .bind(window_seconds as f64)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0)
+}
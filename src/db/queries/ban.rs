@@ -1,7 +1,9 @@
 use sqlx::PgPool;
+use uuid::Uuid;
 
 use crate::db::models::BanRecord;
 
+/// Record a ban. Returns None if the user is already banned from the channel
 pub async fn create(
     pool: &PgPool,
     guild_id: i64,
@@ -9,11 +11,12 @@ pub async fn create(
     banned_user_id: i64,
     banned_by_user_id: i64,
     reason: Option<&str>,
-) -> Result<BanRecord, sqlx::Error> {
+) -> Result<Option<BanRecord>, sqlx::Error> {
     sqlx::query_as::<_, BanRecord>(
         r#"
         INSERT INTO vc_ban_history (guild_id, channel_id, banned_user_id, banned_by_user_id, reason)
         VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (channel_id, banned_user_id) DO NOTHING
         RETURNING *
         "#
     )
@@ -22,10 +25,17 @@ pub async fn create(
     .bind(banned_user_id)
     .bind(banned_by_user_id)
     .bind(reason)
-    .fetch_one(pool)
+    .fetch_optional(pool)
     .await
 }
 
+pub async fn get_by_id(pool: &PgPool, id: Uuid) -> Result<Option<BanRecord>, sqlx::Error> {
+    sqlx::query_as::<_, BanRecord>("SELECT * FROM vc_ban_history WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
 pub async fn is_banned(
     pool: &PgPool,
     channel_id: i64,
@@ -74,6 +84,25 @@ pub async fn get_bans_for_channel(
     .await
 }
 
+/// All bans on a user across a guild's channels
+pub async fn list_for_user_in_guild(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+) -> Result<Vec<BanRecord>, sqlx::Error> {
+    sqlx::query_as::<_, BanRecord>(
+        r#"
+        SELECT * FROM vc_ban_history
+        WHERE guild_id = $1 AND banned_user_id = $2
+        ORDER BY banned_at DESC
+        "#
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn get_user_ban_count(
     pool: &PgPool,
     guild_id: i64,
@@ -0,0 +1,53 @@
+use sqlx::PgPool;
+
+use crate::db::queries::guild_config;
+
+/// Words a guild has added to the room name filter
+pub async fn list(pool: &PgPool, guild_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    let rows: Vec<(String,)> =
+        sqlx::query_as("SELECT word FROM guild_banned_words WHERE guild_id = $1 ORDER BY word")
+            .bind(guild_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.into_iter().map(|(word,)| word).collect())
+}
+
+/// Add a word to a guild's filter
+/// Returns false if it was already there
+pub async fn add(
+    pool: &PgPool,
+    guild_id: i64,
+    word: &str,
+    added_by: i64,
+) -> Result<bool, sqlx::Error> {
+    // Ensure config exists
+    guild_config::get_or_create(pool, guild_id).await?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO guild_banned_words (guild_id, word, added_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id, word) DO NOTHING
+        "#
+    )
+    .bind(guild_id)
+    .bind(word)
+    .bind(added_by)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Take a word out of a guild's filter
+/// Returns false if it wasn't there
+pub async fn remove(pool: &PgPool, guild_id: i64, word: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM guild_banned_words WHERE guild_id = $1 AND word = $2")
+        .bind(guild_id)
+        .bind(word)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::db::models::CommandUsageCount;
+
+/// Record one command invocation
+pub async fn record(
+    pool: &PgPool,
+    command_name: &str,
+    guild_id: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO command_usage (command_name, guild_id) VALUES ($1, $2)")
+        .bind(command_name)
+        .bind(guild_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Most used commands since `since`, with how many guilds used each
+pub async fn top_commands(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<CommandUsageCount>, sqlx::Error> {
+    sqlx::query_as::<_, CommandUsageCount>(
+        r#"
+        SELECT command_name, COUNT(*) AS uses, COUNT(DISTINCT guild_id) AS guilds
+        FROM command_usage
+        WHERE used_at >= $1
+        GROUP BY command_name
+        ORDER BY uses DESC, command_name
+        LIMIT $2
+        "#,
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Total invocations since `since`
+pub async fn total_since(pool: &PgPool, since: DateTime<Utc>) -> Result<i64, sqlx::Error> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM command_usage WHERE used_at >= $1")
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
@@ -0,0 +1,31 @@
+use sqlx::PgPool;
+
+/// Tables included in backups, in restore order (configs before the rows that reference guilds)
+pub const BACKUP_TABLES: &[&str] = &[
+    "guild_configs",
+    "room_permission_templates",
+    "guild_banned_words",
+    "active_voice_channels",
+    "user_vc_preferences",
+    "mute_history",
+    "vc_ban_history",
+    "global_mutes",
+    "ban_appeals",
+    "spam_user_status",
+    "seen_welcome",
+];
+
+/// Serialize a whole table to a JSON array of row objects
+/// `table` must come from `BACKUP_TABLES`; it is interpolated into the query
+pub async fn table_json(pool: &PgPool, table: &str) -> Result<String, sqlx::Error> {
+    debug_assert!(BACKUP_TABLES.contains(&table));
+
+    let (json,): (String,) = sqlx::query_as(&format!(
+        "SELECT COALESCE(json_agg(t), '[]'::json)::text FROM {} t",
+        table
+    ))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(json)
+}
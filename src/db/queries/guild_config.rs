@@ -1,6 +1,6 @@
 use sqlx::PgPool;
 
-use crate::db::models::GuildConfig;
+use crate::db::models::{GuildConfig, MessageTemplate, OwnerHopPolicy, SpamPromptTarget};
 
 pub async fn get_or_create(pool: &PgPool, guild_id: i64) -> Result<GuildConfig, sqlx::Error> {
     // Try to get existing config
@@ -62,11 +62,37 @@ pub async fn set_jtc_channel(
         "#
     };
 
-    sqlx::query_as::<_, GuildConfig>(query)
+    let mut tx = pool.begin().await?;
+
+    let config = sqlx::query_as::<_, GuildConfig>(query)
         .bind(guild_id)
         .bind(channel_id)
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+
+    // Keep jtc_channels mirroring the single channel per type
+    let channel_type = if casual { "casual" } else { "debate" };
+    sqlx::query("DELETE FROM jtc_channels WHERE guild_id = $1 AND channel_type = $2::channel_type")
+        .bind(guild_id)
+        .bind(channel_type)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO jtc_channels (channel_id, guild_id, channel_type)
+        VALUES ($1, $2, $3::channel_type)
+        ON CONFLICT (channel_id) DO UPDATE SET guild_id = $2, channel_type = $3::channel_type
+        "#,
+    )
+    .bind(channel_id)
+    .bind(guild_id)
+    .bind(channel_type)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(config)
 }
 
 pub async fn set_category(
@@ -133,23 +159,576 @@ pub async fn set_rules_channel(
         .await
 }
 
-/// Find which guild and type a JTC channel belongs to
-pub async fn find_by_jtc_channel(
+/// Set or clear (None) the ban appeal review channel
+pub async fn set_appeal_channel(
     pool: &PgPool,
-    channel_id: i64,
-) -> Result<Option<(GuildConfig, bool)>, sqlx::Error> {
-    let config = sqlx::query_as::<_, GuildConfig>(
+    guild_id: i64,
+    channel_id: Option<i64>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
         r#"
-        SELECT * FROM guild_configs
-        WHERE jtc_casual_channel_id = $1 OR jtc_debate_channel_id = $1
+        UPDATE guild_configs
+        SET appeal_channel_id = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
         "#
     )
+    .bind(guild_id)
     .bind(channel_id)
-    .fetch_optional(pool)
-    .await?;
+    .fetch_one(pool)
+    .await
+}
+
+/// Set or clear (None) the channel new rooms are announced in
+pub async fn set_channel_created_notify_channel(
+    pool: &PgPool,
+    guild_id: i64,
+    channel_id: Option<i64>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET channel_created_notify_channel_id = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set the spam thresholds; None clears a value back to the bot-wide default
+pub async fn set_spam_thresholds(
+    pool: &PgPool,
+    guild_id: i64,
+    prompt_threshold: Option<i32>,
+    timeout_threshold: Option<i32>,
+    window_seconds: Option<i32>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET spam_prompt_threshold = $2, spam_timeout_threshold = $3, spam_window_seconds = $4,
+            updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(prompt_threshold)
+    .bind(timeout_threshold)
+    .bind(window_seconds)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set whether owner mutes outlive the room they were given in
+pub async fn set_keep_mutes_on_channel_delete(
+    pool: &PgPool,
+    guild_id: i64,
+    keep: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET keep_mutes_on_channel_delete = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(keep)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set whether rooms are kept while their owner is streaming or on camera
+pub async fn set_keep_rooms_while_streaming(
+    pool: &PgPool,
+    guild_id: i64,
+    keep: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET keep_rooms_while_streaming = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(keep)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set whether new rooms need a tag, not just a name, before they count as configured
+pub async fn set_require_tags(
+    pool: &PgPool,
+    guild_id: i64,
+    required: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET require_tags = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(required)
+    .fetch_one(pool)
+    .await
+}
+
+/// Enable or disable the one-time welcome DM for first-time room owners
+pub async fn set_send_welcome_dm(
+    pool: &PgPool,
+    guild_id: i64,
+    enabled: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET send_welcome_dm = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set or clear (None) the role given to room owners
+pub async fn set_owner_role(
+    pool: &PgPool,
+    guild_id: i64,
+    role_id: Option<i64>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET owner_role_id = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(role_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set or clear (None) the mod log channel
+pub async fn set_mod_log_channel(
+    pool: &PgPool,
+    guild_id: i64,
+    channel_id: Option<i64>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET mod_log_channel_id = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Toggle mod log notifications when a globally-muted user joins voice
+pub async fn set_announce_global_mute_joins(
+    pool: &PgPool,
+    guild_id: i64,
+    enabled: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET announce_global_mute_joins = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Toggle creating a paired discussion text channel with each voice room
+pub async fn set_create_discussion_channels(
+    pool: &PgPool,
+    guild_id: i64,
+    enabled: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET create_discussion_channels = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Enable or disable short-lived in-room notices for owner mutes and bans
+pub async fn set_announce_room_mod_actions(
+    pool: &PgPool,
+    guild_id: i64,
+    enabled: bool,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET announce_room_mod_actions = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace the overflow categories for a channel type (empty to clear)
+pub async fn set_overflow_categories(
+    pool: &PgPool,
+    guild_id: i64,
+    casual: bool,
+    category_ids: &[i64],
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    let query = if casual {
+        r#"
+        UPDATE guild_configs
+        SET overflow_casual_category_ids = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    } else {
+        r#"
+        UPDATE guild_configs
+        SET overflow_debate_category_ids = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    };
+
+    sqlx::query_as::<_, GuildConfig>(query)
+        .bind(guild_id)
+        .bind(category_ids)
+        .fetch_one(pool)
+        .await
+}
+
+/// Set the default tags for new rooms of a type (empty to clear)
+pub async fn set_default_tags(
+    pool: &PgPool,
+    guild_id: i64,
+    casual: bool,
+    tags: &[String],
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    let query = if casual {
+        r#"
+        UPDATE guild_configs
+        SET default_casual_tags = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    } else {
+        r#"
+        UPDATE guild_configs
+        SET default_debate_tags = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    };
+
+    sqlx::query_as::<_, GuildConfig>(query)
+        .bind(guild_id)
+        .bind(tags)
+        .fetch_one(pool)
+        .await
+}
+
+/// Set the emoji tags offered for a type (empty to clear)
+pub async fn set_emoji_tags(
+    pool: &PgPool,
+    guild_id: i64,
+    casual: bool,
+    tags: &[String],
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    let query = if casual {
+        r#"
+        UPDATE guild_configs
+        SET emoji_casual_tags = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    } else {
+        r#"
+        UPDATE guild_configs
+        SET emoji_debate_tags = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    };
+
+    sqlx::query_as::<_, GuildConfig>(query)
+        .bind(guild_id)
+        .bind(tags)
+        .fetch_one(pool)
+        .await
+}
+
+/// Set or clear (None) how many joins within the raid window auto-lock a room
+pub async fn set_raid_lock_joins(
+    pool: &PgPool,
+    guild_id: i64,
+    joins: Option<i32>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET raid_lock_joins = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(joins)
+    .fetch_one(pool)
+    .await
+}
 
-    Ok(config.map(|c| {
-        let is_casual = c.jtc_casual_channel_id == Some(channel_id);
-        (c, is_casual)
-    }))
+/// Set what happens to a room when its owner hops to another managed channel
+pub async fn set_owner_hop_policy(
+    pool: &PgPool,
+    guild_id: i64,
+    policy: OwnerHopPolicy,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET owner_hop_policy = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(policy.as_str())
+    .fetch_one(pool)
+    .await
+}
+
+/// Set where spam prompts are sent
+pub async fn set_spam_prompt_target(
+    pool: &PgPool,
+    guild_id: i64,
+    target: SpamPromptTarget,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET spam_prompt_target = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(target.as_str())
+    .fetch_one(pool)
+    .await
+}
+
+/// Set how long an emptied room is kept before deletion (0 = delete right away)
+pub async fn set_empty_channel_grace_seconds(
+    pool: &PgPool,
+    guild_id: i64,
+    seconds: i32,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET empty_channel_grace_seconds = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(seconds)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set how long a user waits after their room is deleted before they can make another
+pub async fn set_recreate_cooldown_seconds(
+    pool: &PgPool,
+    guild_id: i64,
+    seconds: i32,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET recreate_cooldown_seconds = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(seconds)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set the longest room name owners may choose
+pub async fn set_max_name_length(
+    pool: &PgPool,
+    guild_id: i64,
+    length: i32,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, GuildConfig>(
+        r#"
+        UPDATE guild_configs
+        SET max_name_length = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(length)
+    .fetch_one(pool)
+    .await
+}
+
+/// Set or clear (None) a custom message template
+pub async fn set_message_template(
+    pool: &PgPool,
+    guild_id: i64,
+    kind: MessageTemplate,
+    template: Option<&str>,
+) -> Result<GuildConfig, sqlx::Error> {
+    // Ensure config exists
+    get_or_create(pool, guild_id).await?;
+
+    let query = match kind {
+        MessageTemplate::SpamPrompt => r#"
+        UPDATE guild_configs
+        SET spam_prompt_template = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#,
+        MessageTemplate::DeadlineDm => r#"
+        UPDATE guild_configs
+        SET deadline_dm_template = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#,
+        MessageTemplate::NamingPrompt => r#"
+        UPDATE guild_configs
+        SET naming_prompt_template = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#,
+        MessageTemplate::WelcomeDm => r#"
+        UPDATE guild_configs
+        SET welcome_dm_template = $2, updated_at = NOW()
+        WHERE guild_id = $1
+        RETURNING *
+        "#,
+    };
+
+    sqlx::query_as::<_, GuildConfig>(query)
+        .bind(guild_id)
+        .bind(template)
+        .fetch_one(pool)
+        .await
+}
+
+/// Get the effective template text for a guild (custom or default)
+pub async fn get_message_template(
+    pool: &PgPool,
+    guild_id: i64,
+    kind: MessageTemplate,
+) -> Result<String, sqlx::Error> {
+    Ok(get(pool, guild_id)
+        .await?
+        .map(|c| c.message_template(kind).to_string())
+        .unwrap_or_else(|| kind.default_text().to_string()))
 }
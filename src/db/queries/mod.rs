@@ -1,8 +1,14 @@
+pub mod appeal;
 pub mod ban;
+pub mod banned_word;
+pub mod command_usage;
+pub mod export;
 pub mod global_mute;
 pub mod guild_config;
 pub mod mute;
 pub mod rate_limit;
+pub mod room_permission;
 pub mod spam;
 pub mod user_vc_preference;
 pub mod voice_channel;
+pub mod welcome;
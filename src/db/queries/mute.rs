@@ -1,8 +1,10 @@
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::db::models::MuteRecord;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create(
     pool: &PgPool,
     guild_id: i64,
@@ -10,11 +12,13 @@ pub async fn create(
     muted_user_id: i64,
     muted_by_user_id: i64,
     is_admin_mute: bool,
+    is_deafened: bool,
+    expires_at: Option<DateTime<Utc>>,
 ) -> Result<MuteRecord, sqlx::Error> {
     sqlx::query_as::<_, MuteRecord>(
         r#"
-        INSERT INTO mute_history (guild_id, channel_id, muted_user_id, muted_by_user_id, is_admin_mute)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO mute_history (guild_id, channel_id, muted_user_id, muted_by_user_id, is_admin_mute, is_deafened, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING *
         "#
     )
@@ -23,10 +27,33 @@ pub async fn create(
     .bind(muted_user_id)
     .bind(muted_by_user_id)
     .bind(is_admin_mute)
+    .bind(is_deafened)
+    .bind(expires_at)
     .fetch_one(pool)
     .await
 }
 
+/// Mark a user's active mute in a channel as also deafened
+/// Returns None if there is no active mute
+pub async fn set_deafened(
+    pool: &PgPool,
+    channel_id: i64,
+    user_id: i64,
+) -> Result<Option<MuteRecord>, sqlx::Error> {
+    sqlx::query_as::<_, MuteRecord>(
+        r#"
+        UPDATE mute_history
+        SET is_deafened = TRUE
+        WHERE channel_id = $1 AND muted_user_id = $2 AND unmuted_at IS NULL
+        RETURNING *
+        "#
+    )
+    .bind(channel_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
 pub async fn get_active_mute(
     pool: &PgPool,
     channel_id: i64,
@@ -46,12 +73,27 @@ pub async fn get_active_mute(
     .await
 }
 
+/// Active timed mutes whose expiry has passed
+pub async fn get_expired_mutes(pool: &PgPool) -> Result<Vec<MuteRecord>, sqlx::Error> {
+    sqlx::query_as::<_, MuteRecord>(
+        r#"
+        SELECT * FROM mute_history
+        WHERE unmuted_at IS NULL AND expires_at <= NOW()
+        ORDER BY expires_at
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Close a mute if it's still active
+/// Returns None if it was already closed, so callers never act on a finished mute twice
 pub async fn unmute(pool: &PgPool, id: Uuid) -> Result<Option<MuteRecord>, sqlx::Error> {
     sqlx::query_as::<_, MuteRecord>(
         r#"
         UPDATE mute_history
         SET unmuted_at = NOW()
-        WHERE id = $1
+        WHERE id = $1 AND unmuted_at IS NULL
         RETURNING *
         "#
     )
@@ -128,8 +170,40 @@ pub async fn list_active_mutes_for_channel(
     .await
 }
 
+/// List a user's active room-owner mutes in a guild
+pub async fn list_active_local_mutes_for_user(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+) -> Result<Vec<MuteRecord>, sqlx::Error> {
+    sqlx::query_as::<_, MuteRecord>(
+        r#"
+        SELECT * FROM mute_history
+        WHERE guild_id = $1 AND muted_user_id = $2 AND unmuted_at IS NULL AND is_admin_mute = FALSE
+        ORDER BY muted_at DESC
+        "#
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// List every active mute record (for reconciliation on startup)
+pub async fn list_all_active(pool: &PgPool) -> Result<Vec<MuteRecord>, sqlx::Error> {
+    sqlx::query_as::<_, MuteRecord>(
+        r#"
+        SELECT * FROM mute_history
+        WHERE unmuted_at IS NULL
+        ORDER BY muted_at ASC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
 /// Clear all active mute records for a user in a guild
-/// Used when a server-wide unmute is detected
+/// Used when a server-wide unmute is detected and by `/admin forgive`
 pub async fn unmute_all_for_user_in_guild(
     pool: &PgPool,
     guild_id: i64,
@@ -150,6 +224,61 @@ pub async fn unmute_all_for_user_in_guild(
     Ok(result.rows_affected())
 }
 
+/// Close a user's active room-owner mutes across a guild, leaving admin mutes in place
+/// Used when the user leaves the guild
+pub async fn unmute_local_for_user_in_guild(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE mute_history
+        SET unmuted_at = NOW()
+        WHERE guild_id = $1 AND muted_user_id = $2 AND unmuted_at IS NULL AND is_admin_mute = FALSE
+        "#
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Clear all active mute records for a channel
+/// Used when the channel no longer exists
+pub async fn unmute_all_for_channel(pool: &PgPool, channel_id: i64) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE mute_history
+        SET unmuted_at = NOW()
+        WHERE channel_id = $1 AND unmuted_at IS NULL
+        "#
+    )
+    .bind(channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Reopen mutes closed for a room teardown, for a room that turned out not to be deleted
+pub async fn reopen(pool: &PgPool, ids: &[Uuid]) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE mute_history
+        SET unmuted_at = NULL
+        WHERE id = ANY($1)
+        "#
+    )
+    .bind(ids)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Check if user has an active mute in any channel in the guild, except the specified one
 /// Used to determine if a user hopping between channels should stay muted
 pub async fn has_active_mute_in_guild_except(
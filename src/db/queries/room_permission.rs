@@ -0,0 +1,56 @@
+use sqlx::PgPool;
+
+use crate::db::models::RoomPermission;
+use crate::db::queries::guild_config;
+
+/// Role overwrites new rooms in a guild start with
+pub async fn list(pool: &PgPool, guild_id: i64) -> Result<Vec<RoomPermission>, sqlx::Error> {
+    sqlx::query_as::<_, RoomPermission>(
+        "SELECT * FROM room_permission_templates WHERE guild_id = $1 ORDER BY role_id"
+    )
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Set (or replace) the overwrite new rooms give a role
+pub async fn set(
+    pool: &PgPool,
+    guild_id: i64,
+    role_id: i64,
+    allow: i64,
+    deny: i64,
+) -> Result<RoomPermission, sqlx::Error> {
+    // Ensure config exists
+    guild_config::get_or_create(pool, guild_id).await?;
+
+    sqlx::query_as::<_, RoomPermission>(
+        r#"
+        INSERT INTO room_permission_templates (guild_id, role_id, allow, deny)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (guild_id, role_id) DO UPDATE
+        SET allow = EXCLUDED.allow, deny = EXCLUDED.deny
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(role_id)
+    .bind(allow)
+    .bind(deny)
+    .fetch_one(pool)
+    .await
+}
+
+/// Stop giving new rooms an overwrite for a role
+/// Returns false if the role had none
+pub async fn remove(pool: &PgPool, guild_id: i64, role_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM room_permission_templates WHERE guild_id = $1 AND role_id = $2"
+    )
+    .bind(guild_id)
+    .bind(role_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
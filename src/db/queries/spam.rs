@@ -81,6 +81,53 @@ pub async fn reset_timeout_level(
     .await
 }
 
+/// Reset the timeout level of everyone without an infraction in the last `days` days
+/// Returns how many users were reset
+pub async fn reset_stale_timeout_levels(pool: &PgPool, days: i64) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE spam_user_status
+        SET
+            current_timeout_level = 0,
+            updated_at = NOW()
+        WHERE current_timeout_level > 0
+          AND (last_infraction_at IS NULL OR last_infraction_at < NOW() - make_interval(days => $1))
+        "#
+    )
+    .bind(days as i32)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Set a user's timeout level directly, e.g. when an admin corrects it
+pub async fn set_timeout_level(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+    level: i32,
+) -> Result<SpamRecord, sqlx::Error> {
+    // Ensure record exists
+    get_or_create(pool, guild_id, user_id).await?;
+
+    sqlx::query_as::<_, SpamRecord>(
+        r#"
+        UPDATE spam_user_status
+        SET
+            current_timeout_level = $3,
+            updated_at = NOW()
+        WHERE guild_id = $1 AND user_id = $2
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(level)
+    .fetch_one(pool)
+    .await
+}
+
 pub async fn get_user_stats(
     pool: &PgPool,
     guild_id: i64,
@@ -50,6 +50,44 @@ pub async fn upsert(
     .await
 }
 
+/// Save a user's preferred name, keeping whatever tags they already saved
+pub async fn save_name(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+    channel_type: &str,
+    preferred_name: &str,
+) -> Result<UserVcPreference, sqlx::Error> {
+    sqlx::query_as::<_, UserVcPreference>(
+        r#"
+        INSERT INTO user_vc_preferences (guild_id, user_id, channel_type, preferred_name, preferred_tags)
+        VALUES ($1, $2, $3, $4, '{}')
+        ON CONFLICT (guild_id, user_id, channel_type)
+        DO UPDATE SET
+            preferred_name = $4,
+            updated_at = NOW()
+        RETURNING *
+        "#
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(channel_type)
+    .bind(preferred_name)
+    .fetch_one(pool)
+    .await
+}
+
+/// Delete all of a user's saved VC preferences in a guild
+pub async fn delete_for_user(pool: &PgPool, guild_id: i64, user_id: i64) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM user_vc_preferences WHERE guild_id = $1 AND user_id = $2")
+        .bind(guild_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Create a pending deadline for a VC
 pub async fn create_deadline(
     pool: &PgPool,
@@ -84,11 +122,15 @@ pub async fn remove_deadline(pool: &PgPool, channel_id: i64) -> Result<bool, sql
     Ok(result.rows_affected() > 0)
 }
 
-/// Get all deadlines that have passed
-pub async fn get_expired_deadlines(pool: &PgPool) -> Result<Vec<PendingVcDeadline>, sqlx::Error> {
+/// Get up to `limit` deadlines that have passed, oldest first
+pub async fn get_expired_deadlines(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<PendingVcDeadline>, sqlx::Error> {
     sqlx::query_as::<_, PendingVcDeadline>(
-        "SELECT * FROM pending_vc_deadlines WHERE deadline_at <= NOW()"
+        "SELECT * FROM pending_vc_deadlines WHERE deadline_at <= NOW() ORDER BY deadline_at LIMIT $1"
     )
+    .bind(limit)
     .fetch_all(pool)
     .await
 }
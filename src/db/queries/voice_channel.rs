@@ -51,6 +51,21 @@ pub async fn get_by_owner(
     .await
 }
 
+/// List every managed channel a user owns in a guild
+pub async fn list_by_owner(
+    pool: &PgPool,
+    guild_id: i64,
+    owner_id: i64,
+) -> Result<Vec<VoiceChannel>, sqlx::Error> {
+    sqlx::query_as::<_, VoiceChannel>(
+        "SELECT * FROM active_voice_channels WHERE guild_id = $1 AND owner_id = $2 ORDER BY created_at DESC"
+    )
+    .bind(guild_id)
+    .bind(owner_id)
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn update_owner(
     pool: &PgPool,
     channel_id: i64,
@@ -89,6 +104,63 @@ pub async fn update_topic(
     .await
 }
 
+/// Record (or clear) the discussion text channel paired with a voice room
+pub async fn set_text_channel(
+    pool: &PgPool,
+    channel_id: i64,
+    text_channel_id: Option<i64>,
+) -> Result<Option<VoiceChannel>, sqlx::Error> {
+    sqlx::query_as::<_, VoiceChannel>(
+        r#"
+        UPDATE active_voice_channels
+        SET text_channel_id = $2
+        WHERE channel_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(channel_id)
+    .bind(text_channel_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record whether the owner has locked the room
+pub async fn set_locked(
+    pool: &PgPool,
+    channel_id: i64,
+    locked: bool,
+) -> Result<Option<VoiceChannel>, sqlx::Error> {
+    sqlx::query_as::<_, VoiceChannel>(
+        r#"
+        UPDATE active_voice_channels
+        SET locked = $2
+        WHERE channel_id = $1
+        RETURNING *
+        "#
+    )
+    .bind(channel_id)
+    .bind(locked)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record whether the room is auto-locked after a join flood
+pub async fn set_raid_locked(
+    pool: &PgPool,
+    channel_id: i64,
+    raid_locked: bool,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE active_voice_channels SET raid_locked = $2 WHERE channel_id = $1",
+    )
+    .bind(channel_id)
+    .bind(raid_locked)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn update_tags(
     pool: &PgPool,
     channel_id: i64,
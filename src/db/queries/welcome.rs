@@ -0,0 +1,19 @@
+use sqlx::PgPool;
+
+/// Record that a user got the welcome DM in a guild
+/// Returns true only the first time, so concurrent room creations can't both send it
+pub async fn mark_seen(pool: &PgPool, guild_id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO seen_welcome (guild_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
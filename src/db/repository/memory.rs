@@ -0,0 +1,221 @@
+//! In-memory repository used by unit tests in place of Postgres
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::future::{ready, Future};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::models::{ChannelType, MuteRecord, VoiceChannel};
+use crate::db::repository::{MuteRepo, VoiceChannelRepo};
+
+#[derive(Default)]
+pub struct InMemoryRepo {
+    channels: Mutex<HashMap<i64, VoiceChannel>>,
+    mutes: Mutex<Vec<MuteRecord>>,
+}
+
+impl InMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl VoiceChannelRepo for InMemoryRepo {
+    fn create_channel(
+        &self,
+        channel_id: i64,
+        guild_id: i64,
+        owner_id: i64,
+        channel_type: ChannelType,
+        topic: Option<&str>,
+        tags: &[String],
+    ) -> impl Future<Output = Result<VoiceChannel, sqlx::Error>> + Send {
+        let vc = VoiceChannel {
+            channel_id,
+            guild_id,
+            owner_id,
+            channel_type,
+            topic: topic.map(str::to_string),
+            tags: tags.to_vec(),
+            created_at: Utc::now(),
+            text_channel_id: None,
+            locked: false,
+            raid_locked: false,
+        };
+        let result = match self.channels.lock().unwrap().entry(channel_id) {
+            Entry::Occupied(_) => Err(sqlx::Error::Protocol("duplicate channel_id".to_string())),
+            Entry::Vacant(slot) => Ok(slot.insert(vc).clone()),
+        };
+        ready(result)
+    }
+
+    fn get_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        ready(Ok(self.channels.lock().unwrap().get(&channel_id).cloned()))
+    }
+
+    fn get_channel_by_owner(
+        &self,
+        guild_id: i64,
+        owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        let found = self
+            .channels
+            .lock()
+            .unwrap()
+            .values()
+            .find(|vc| vc.guild_id == guild_id && vc.owner_id == owner_id)
+            .cloned();
+        ready(Ok(found))
+    }
+
+    fn update_channel_owner(
+        &self,
+        channel_id: i64,
+        new_owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        let updated = self.channels.lock().unwrap().get_mut(&channel_id).map(|vc| {
+            vc.owner_id = new_owner_id;
+            vc.clone()
+        });
+        ready(Ok(updated))
+    }
+
+    fn delete_channel(&self, channel_id: i64) -> impl Future<Output = Result<bool, sqlx::Error>> + Send {
+        ready(Ok(self.channels.lock().unwrap().remove(&channel_id).is_some()))
+    }
+
+    fn list_channels(&self) -> impl Future<Output = Result<Vec<VoiceChannel>, sqlx::Error>> + Send {
+        let mut channels: Vec<VoiceChannel> =
+            self.channels.lock().unwrap().values().cloned().collect();
+        channels.sort_by_key(|vc| std::cmp::Reverse(vc.created_at));
+        ready(Ok(channels))
+    }
+}
+
+impl MuteRepo for InMemoryRepo {
+    fn create_mute(
+        &self,
+        guild_id: i64,
+        channel_id: i64,
+        muted_user_id: i64,
+        muted_by_user_id: i64,
+        is_admin_mute: bool,
+        is_deafened: bool,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<MuteRecord, sqlx::Error>> + Send {
+        let record = MuteRecord {
+            id: Uuid::new_v4(),
+            guild_id,
+            channel_id,
+            muted_user_id,
+            muted_by_user_id,
+            is_admin_mute,
+            is_deafened,
+            muted_at: Utc::now(),
+            unmuted_at: None,
+            expires_at,
+        };
+        self.mutes.lock().unwrap().push(record.clone());
+        ready(Ok(record))
+    }
+
+    fn get_active_mute(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send {
+        let found = self
+            .mutes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|m| m.channel_id == channel_id && m.muted_user_id == user_id && m.is_active())
+            .cloned();
+        ready(Ok(found))
+    }
+
+    fn unmute_by_channel_user(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send {
+        let mut cleared = false;
+        for m in self.mutes.lock().unwrap().iter_mut() {
+            if m.channel_id == channel_id && m.muted_user_id == user_id && m.is_active() {
+                m.unmuted_at = Some(Utc::now());
+                cleared = true;
+            }
+        }
+        ready(Ok(cleared))
+    }
+
+    fn list_active_mutes_for_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        let mutes = self
+            .mutes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|m| m.channel_id == channel_id && m.is_active())
+            .cloned()
+            .collect();
+        ready(Ok(mutes))
+    }
+
+    fn list_active_local_mutes_for_user(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        let mutes = self
+            .mutes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|m| {
+                m.guild_id == guild_id && m.muted_user_id == user_id && !m.is_admin_mute && m.is_active()
+            })
+            .cloned()
+            .collect();
+        ready(Ok(mutes))
+    }
+
+    fn get_expired_mutes(&self) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        let now = Utc::now();
+        let mut expired: Vec<MuteRecord> = self
+            .mutes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.is_active() && m.expires_at.is_some_and(|at| at <= now))
+            .cloned()
+            .collect();
+        expired.sort_by_key(|m| m.expires_at);
+        ready(Ok(expired))
+    }
+
+    fn close_mute(&self, id: Uuid) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send {
+        let closed = self
+            .mutes
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|m| m.id == id && m.is_active())
+            .map(|m| {
+                m.unmuted_at = Some(Utc::now());
+                m.clone()
+            });
+        ready(Ok(closed))
+    }
+}
@@ -0,0 +1,93 @@
+//! Repository traits over the query modules
+//! Services can be written against these traits instead of a concrete `PgPool`,
+//! which lets the mute logic be unit-tested without a live database.
+
+#[cfg(test)]
+pub mod memory;
+mod pg;
+
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::models::{ChannelType, MuteRecord, VoiceChannel};
+
+/// Storage for managed voice channels (`active_voice_channels`)
+pub trait VoiceChannelRepo: Send + Sync {
+    fn create_channel(
+        &self,
+        channel_id: i64,
+        guild_id: i64,
+        owner_id: i64,
+        channel_type: ChannelType,
+        topic: Option<&str>,
+        tags: &[String],
+    ) -> impl Future<Output = Result<VoiceChannel, sqlx::Error>> + Send;
+
+    fn get_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send;
+
+    fn get_channel_by_owner(
+        &self,
+        guild_id: i64,
+        owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send;
+
+    fn update_channel_owner(
+        &self,
+        channel_id: i64,
+        new_owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send;
+
+    fn delete_channel(&self, channel_id: i64) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    fn list_channels(&self) -> impl Future<Output = Result<Vec<VoiceChannel>, sqlx::Error>> + Send;
+}
+
+/// Storage for per-channel mutes (`mute_history`)
+pub trait MuteRepo: Send + Sync {
+    #[allow(clippy::too_many_arguments)]
+    fn create_mute(
+        &self,
+        guild_id: i64,
+        channel_id: i64,
+        muted_user_id: i64,
+        muted_by_user_id: i64,
+        is_admin_mute: bool,
+        is_deafened: bool,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<MuteRecord, sqlx::Error>> + Send;
+
+    fn get_active_mute(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send;
+
+    fn unmute_by_channel_user(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    fn list_active_mutes_for_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send;
+
+    /// A user's active room-owner mutes in a guild
+    fn list_active_local_mutes_for_user(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send;
+
+    /// Active mutes whose expiry has passed
+    fn get_expired_mutes(&self) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send;
+
+    /// Close a mute if it's still active, returning None if it was already closed
+    fn close_mute(&self, id: Uuid) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send;
+}
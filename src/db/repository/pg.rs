@@ -0,0 +1,117 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::models::{ChannelType, MuteRecord, VoiceChannel};
+use crate::db::queries::{mute, voice_channel};
+use crate::db::repository::{MuteRepo, VoiceChannelRepo};
+
+impl VoiceChannelRepo for PgPool {
+    fn create_channel(
+        &self,
+        channel_id: i64,
+        guild_id: i64,
+        owner_id: i64,
+        channel_type: ChannelType,
+        topic: Option<&str>,
+        tags: &[String],
+    ) -> impl Future<Output = Result<VoiceChannel, sqlx::Error>> + Send {
+        voice_channel::create(self, channel_id, guild_id, owner_id, channel_type, topic, tags)
+    }
+
+    fn get_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        voice_channel::get(self, channel_id)
+    }
+
+    fn get_channel_by_owner(
+        &self,
+        guild_id: i64,
+        owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        voice_channel::get_by_owner(self, guild_id, owner_id)
+    }
+
+    fn update_channel_owner(
+        &self,
+        channel_id: i64,
+        new_owner_id: i64,
+    ) -> impl Future<Output = Result<Option<VoiceChannel>, sqlx::Error>> + Send {
+        voice_channel::update_owner(self, channel_id, new_owner_id)
+    }
+
+    fn delete_channel(&self, channel_id: i64) -> impl Future<Output = Result<bool, sqlx::Error>> + Send {
+        voice_channel::delete(self, channel_id)
+    }
+
+    fn list_channels(&self) -> impl Future<Output = Result<Vec<VoiceChannel>, sqlx::Error>> + Send {
+        voice_channel::list_all(self)
+    }
+}
+
+impl MuteRepo for PgPool {
+    fn create_mute(
+        &self,
+        guild_id: i64,
+        channel_id: i64,
+        muted_user_id: i64,
+        muted_by_user_id: i64,
+        is_admin_mute: bool,
+        is_deafened: bool,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<MuteRecord, sqlx::Error>> + Send {
+        mute::create(
+            self,
+            guild_id,
+            channel_id,
+            muted_user_id,
+            muted_by_user_id,
+            is_admin_mute,
+            is_deafened,
+            expires_at,
+        )
+    }
+
+    fn get_active_mute(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send {
+        mute::get_active_mute(self, channel_id, user_id)
+    }
+
+    fn unmute_by_channel_user(
+        &self,
+        channel_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send {
+        mute::unmute_by_channel_user(self, channel_id, user_id)
+    }
+
+    fn list_active_mutes_for_channel(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        mute::list_active_mutes_for_channel(self, channel_id)
+    }
+
+    fn list_active_local_mutes_for_user(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        mute::list_active_local_mutes_for_user(self, guild_id, user_id)
+    }
+
+    fn get_expired_mutes(&self) -> impl Future<Output = Result<Vec<MuteRecord>, sqlx::Error>> + Send {
+        mute::get_expired_mutes(self)
+    }
+
+    fn close_mute(&self, id: Uuid) -> impl Future<Output = Result<Option<MuteRecord>, sqlx::Error>> + Send {
+        mute::unmute(self, id)
+    }
+}
@@ -5,7 +5,8 @@ use tracing::{debug, error, info};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::handlers::{interaction, voice_state};
+use crate::handlers::{guild_removal, interaction, member_removal, voice_state};
+use crate::services::jtc::channel_deleter;
 
 pub async fn event_handler(
     ctx: &serenity::Context,
@@ -16,6 +17,13 @@ pub async fn event_handler(
     match event {
         FullEvent::Ready { data_about_bot, .. } => {
             info!("Master the bot is ready as {}", data_about_bot.user.name);
+            // A fresh session may follow a long disconnect - pending markers are stale
+            data.clear_transient_state();
+        }
+
+        FullEvent::Resume { .. } => {
+            info!("Gateway session resumed, clearing transient state");
+            data.clear_transient_state();
         }
 
         FullEvent::VoiceStateUpdate { old, new } => {
@@ -42,14 +50,35 @@ pub async fn event_handler(
         }
 
         FullEvent::ChannelDelete { channel, .. } => {
-            // Clean up cache when a channel is deleted
-            data.remove_channel(channel.id.get());
-            debug!("Channel {} deleted, removed from cache", channel.id);
+            // Clean up cache and every record tied to the channel (it may have been deleted externally)
+            match channel_deleter::purge_channel_records(ctx, data, channel.id).await {
+                Ok(true) => info!("Channel {} deleted externally, removed its records", channel.id),
+                Ok(false) => debug!("Channel {} deleted, removed from cache", channel.id),
+                Err(e) => error!("Failed to clean up records for deleted channel {}: {:?}", channel.id, e),
+            }
+        }
+
+        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+            if let Err(e) = member_removal::handle_member_removal(ctx, data, *guild_id, user.id).await {
+                error!("Failed to clean up after departed member {}: {:?}", user.id, e);
+            }
+        }
+
+        FullEvent::Ratelimit { data: info } => {
+            debug!(
+                "Rate limited on {:?} {} for {:?} (global: {})",
+                info.method, info.path, info.timeout, info.global
+            );
+            data.rate_limits.record_hit(info.global, info.timeout);
         }
 
         FullEvent::GuildDelete { incomplete, .. } => {
-            // Could clean up guild data here if needed
-            debug!("Guild {} removed", incomplete.id);
+            // Outages send this too, flagged unavailable; the guild and its rooms come back after
+            if incomplete.unavailable {
+                info!("Guild {} became unavailable", incomplete.id);
+            } else if let Err(e) = guild_removal::handle_guild_removal(data, incomplete.id).await {
+                error!("Failed to clean up after removal from guild {}: {:?}", incomplete.id, e);
+            }
         }
 
         _ => {}
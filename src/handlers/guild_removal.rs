@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use serenity::all::GuildId;
+use tracing::info;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::db::queries::{mute, user_vc_preference, voice_channel};
+
+/// Drop a guild's rooms once the bot has been removed from it
+/// Only room state goes, since the bot can no longer act on those channels. Configuration and
+/// moderation history are kept so a re-invite picks up where the guild left off.
+/// Must not run for an outage (an unavailable guild), only for a real removal.
+pub async fn handle_guild_removal(data: &Arc<Data>, guild_id: GuildId) -> Result<(), Error> {
+    let rooms = voice_channel::list_by_guild(&data.pool, guild_id.get() as i64).await?;
+    let mut mutes_closed = 0;
+
+    // Database and memory only; the guild's channels are out of the bot's reach now
+    for vc in &rooms {
+        let channel_id = vc.channel_id;
+        voice_channel::delete(&data.pool, channel_id).await?;
+        user_vc_preference::remove_deadline(&data.pool, channel_id).await?;
+        mutes_closed += mute::unmute_all_for_channel(&data.pool, channel_id).await?;
+        data.remove_channel(channel_id as u64);
+        data.activity_tracker.cleanup_channel(channel_id as u64);
+    }
+    data.word_filters.invalidate(guild_id.get());
+
+    info!(
+        "Removed from guild {}: dropped {} rooms, closed {} active mutes",
+        guild_id,
+        rooms.len(),
+        mutes_closed
+    );
+
+    Ok(())
+}
@@ -1,14 +1,16 @@
+use std::future::Future;
 use std::sync::Arc;
 
 use serenity::all::{
-    ComponentInteraction, Context, CreateInteractionResponse, CreateInteractionResponseMessage,
+    ComponentInteraction, Context, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, EditInteractionResponse,
     Interaction, ModalInteraction,
 };
 use tracing::{debug, error};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::components::{ban_selector, limit_selector, naming_prompt, owner_actions, spam_prompt, tag_selector, topic_modal};
+use crate::components::{ban_appeal, ban_list, ban_selector, channel_admin, confirm, limit_selector, naming_prompt, owner_actions, raid_lock, room_hold, room_lock, spam_prompt, tag_selector, topic_modal, unmute_all};
 use crate::constants::embeds;
 
 pub async fn handle_interaction(
@@ -47,6 +49,8 @@ async fn handle_component(
     // Route based on custom_id prefix
     let result = if custom_id.starts_with("tags_") {
         tag_selector::handle_selection(ctx, data, component).await
+    } else if custom_id.starts_with("banlist_") {
+        ban_list::handle_selection(ctx, data, component).await
     } else if custom_id.starts_with("ban_") {
         ban_selector::handle_selection(ctx, data, component).await
     } else if custom_id.starts_with("spam_") {
@@ -59,6 +63,20 @@ async fn handle_component(
         limit_selector::handle_button(ctx, data, component).await
     } else if custom_id.starts_with("vc_") {
         owner_actions::handle_selection(ctx, data, component).await
+    } else if custom_id.starts_with("chanadmin_") {
+        channel_admin::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("confirm_") {
+        confirm::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("appeal_") {
+        ban_appeal::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("roomhold_") {
+        room_hold::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("roomlock_") {
+        room_lock::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("raidlock_") {
+        raid_lock::handle_button(ctx, data, component).await
+    } else if custom_id.starts_with("unmuteall_") {
+        unmute_all::handle_button(ctx, data, component).await
     } else {
         // Unknown component - acknowledge but do nothing
         debug!("Unknown component interaction: {}", custom_id);
@@ -69,7 +87,11 @@ async fn handle_component(
     if let Err(e) = result {
         error!("Component interaction error for {}: {:?}", custom_id, e);
         // Try to send error response, but don't fail if it doesn't work
-        let _ = send_component_error(ctx, component, &format!("An error occurred: {}", e)).await;
+        let message = format!("An error occurred: {}", e);
+        if send_component_error(ctx, component, &message).await.is_err() {
+            // The interaction may already have been acknowledged (deferred) - use a followup
+            let _ = send_component_error_followup(ctx, component, &message).await;
+        }
     }
 
     Ok(())
@@ -89,6 +111,10 @@ async fn handle_modal(
         naming_prompt::handle_naming_modal(ctx, data, modal).await?;
     } else if custom_id.starts_with("limit_modal_") {
         limit_selector::handle_modal(ctx, data, modal).await?;
+    } else if custom_id.starts_with("ban_modal_") {
+        ban_selector::handle_reason_modal(ctx, data, modal).await?;
+    } else if custom_id.starts_with("appeal_modal_") {
+        ban_appeal::handle_modal(ctx, data, modal).await?;
     }
 
     Ok(())
@@ -118,6 +144,79 @@ pub async fn send_component_error(
     Ok(())
 }
 
+/// Send an ephemeral error as a followup to an already-acknowledged component interaction
+async fn send_component_error_followup(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    message: &str,
+) -> Result<(), Error> {
+    let embed = embeds::error_embed()
+        .title("Error")
+        .description(message);
+
+    component
+        .create_followup(
+            ctx,
+            CreateInteractionResponseFollowup::new()
+                .embed(embed)
+                .ephemeral(true),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// How a deferred component response is shown once the work completes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeferMode {
+    /// Reply with a new ephemeral message
+    Ephemeral,
+    /// Replace the message the component is attached to (and clear its components)
+    UpdateMessage,
+}
+
+/// Acknowledge a component interaction right away, run the slow work, then edit in the result
+/// Keeps handlers that do DB + several API calls from hitting Discord's 3-second limit
+/// Only the token of the interaction being handled is used (valid for 15 minutes) - never
+/// store tokens for later; edit long-lived panels through their message ID instead
+pub async fn defer_then_edit(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    mode: DeferMode,
+    work: impl Future<Output = CreateEmbed>,
+) -> Result<(), Error> {
+    defer_then_edit_response(ctx, component, mode, async {
+        let edit = EditInteractionResponse::new().embed(work.await);
+        if mode == DeferMode::UpdateMessage {
+            edit.components(vec![])
+        } else {
+            edit
+        }
+    })
+    .await
+}
+
+/// Like `defer_then_edit`, but the work builds the whole edit (e.g. to attach follow-up buttons)
+pub async fn defer_then_edit_response(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    mode: DeferMode,
+    work: impl Future<Output = EditInteractionResponse>,
+) -> Result<(), Error> {
+    let ack = match mode {
+        DeferMode::Ephemeral => CreateInteractionResponse::Defer(
+            CreateInteractionResponseMessage::new().ephemeral(true),
+        ),
+        DeferMode::UpdateMessage => CreateInteractionResponse::Acknowledge,
+    };
+    component.create_response(ctx, ack).await?;
+
+    let edit = work.await;
+    component.edit_response(ctx, edit).await?;
+
+    Ok(())
+}
+
 /// Send an ephemeral error message for a modal interaction
 pub async fn send_modal_error(
     ctx: &Context,
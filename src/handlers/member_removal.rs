@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use serenity::all::{Context, GuildId, UserId};
+use tracing::info;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::db::queries::{mute, user_vc_preference};
+use crate::services::jtc::channel_deleter;
+
+/// Reconcile a member's active state when they leave a guild
+/// Only transient/active state is touched; mute and ban history, stats and admin mutes are kept
+pub async fn handle_member_removal(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let guild = guild_id.get() as i64;
+    let user = user_id.get() as i64;
+
+    data.forget_member(guild_id.get(), user_id.get());
+
+    // A departed owner's rooms would otherwise linger until the next voice event
+    let (transferred, deleted) =
+        channel_deleter::handle_owner_departure(ctx, data, guild_id, user_id).await?;
+
+    // Room-owner mutes only apply while they're around; Discord resets the voice mute on rejoin anyway
+    let mutes_cleared = mute::unmute_local_for_user_in_guild(&data.pool, guild, user).await?;
+
+    let preferences_pruned = if data.settings.prune_preferences_on_leave {
+        user_vc_preference::delete_for_user(&data.pool, guild, user).await?
+    } else {
+        0
+    };
+
+    if transferred + deleted > 0 || mutes_cleared > 0 || preferences_pruned > 0 {
+        info!(
+            "Member {} left guild {}: transferred {} channels, deleted {}, cleared {} mutes, pruned {} preferences",
+            user_id, guild_id, transferred, deleted, mutes_cleared, preferences_pruned
+        );
+    }
+
+    Ok(())
+}
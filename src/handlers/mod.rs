@@ -1,3 +1,5 @@
 pub mod event_handler;
+pub mod guild_removal;
 pub mod interaction;
+pub mod member_removal;
 pub mod voice_state;
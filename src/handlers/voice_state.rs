@@ -6,13 +6,12 @@ use tracing::{debug, error, info};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::db::queries::{global_mute, guild_config, mute, voice_channel};
-use crate::services::jtc::{channel_creator, channel_deleter};
-use crate::services::moderation::mute_service;
-use crate::services::spam::detector;
-
-/// Delay before unmuting a user after they leave a channel (in seconds)
-const UNMUTE_DELAY_SECONDS: u64 = 3;
+use crate::constants::embeds;
+use crate::db::models::{MuteRecord, OwnerHopPolicy};
+use crate::db::queries::{global_mute, mute, voice_channel};
+use crate::services::jtc::{channel_creator, channel_deleter, queue};
+use crate::services::moderation::{ban_service, mod_log, mute_service};
+use crate::services::spam::{detector, raid_guard};
 
 pub async fn handle_voice_state_update(
     ctx: &Context,
@@ -64,11 +63,21 @@ pub async fn handle_voice_state_update(
         }
     }
 
+    // A muted user moved straight to another channel (usually dragged there by a mod) is settled
+    // right away from the channel they landed in, instead of by the join re-mute and the delayed
+    // leave unmute, which are meant for users who disconnect and come back
+    let was_muted = old.is_some_and(|o| o.mute);
+    let muted_move = is_muted_move(old_channel, new_channel, was_muted, new.mute);
+    if let (true, Some(from), Some(to)) = (muted_move, old_channel, new_channel) {
+        handle_muted_move(ctx, data, guild_id, user_id, from, to, new.deaf).await?;
+    }
+
     // User joined a channel
     if let Some(channel_id) = new_channel {
         // Only handle join if channel actually changed
         if old_channel != Some(channel_id) {
-            handle_channel_join(ctx, data, guild_id, user_id, channel_id).await?;
+            handle_channel_join(ctx, data, guild_id, user_id, channel_id, muted_move, new.mute)
+                .await?;
         }
     }
 
@@ -76,24 +85,122 @@ pub async fn handle_voice_state_update(
     if let Some(channel_id) = old_channel {
         // Only process if they actually left (moved to different channel or disconnected)
         if new_channel != Some(channel_id) {
-            handle_channel_leave(ctx, data, guild_id, user_id, channel_id).await?;
+            handle_channel_leave(ctx, data, guild_id, user_id, channel_id, new_channel, muted_move)
+                .await?;
         }
     }
 
     Ok(())
 }
 
+/// Whether the user went straight from one channel to another while server-muted throughout
+fn is_muted_move(
+    old_channel: Option<ChannelId>,
+    new_channel: Option<ChannelId>,
+    was_muted: bool,
+    is_muted: bool,
+) -> bool {
+    match (old_channel, new_channel) {
+        (Some(from), Some(to)) => from != to && was_muted && is_muted,
+        _ => false,
+    }
+}
+
+/// What to do with the server mute of a user moved straight between channels
+#[derive(Debug, PartialEq, Eq)]
+enum MovedMute {
+    /// Not a mute the bot lifts (global, admin, or not ours at all)
+    Untouched,
+    /// Muted in the new channel too; the deafen follows the new channel's mute
+    Keep { deafen: Option<bool> },
+    /// Nothing holds the mute in the new channel
+    Lift { undeafen: bool },
+}
+
+fn moved_mute(from: Option<&MuteRecord>, to: Option<&MuteRecord>, globally_muted: bool) -> MovedMute {
+    if globally_muted {
+        return MovedMute::Untouched;
+    }
+
+    match (from, to) {
+        (_, Some(to)) => MovedMute::Keep {
+            deafen: if to.is_deafened {
+                Some(true)
+            } else if from.is_some_and(|f| f.is_deafened) {
+                Some(false)
+            } else {
+                None
+            },
+        },
+        (Some(from), None) if !from.is_admin_mute => MovedMute::Lift {
+            undeafen: from.is_deafened,
+        },
+        _ => MovedMute::Untouched,
+    }
+}
+
+/// Carry or lift the mute of a user moved while muted, based on the channel they landed in
+/// Mute records stay as they are; only the voice state changes
+async fn handle_muted_move(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    user_id: UserId,
+    from: ChannelId,
+    to: ChannelId,
+    is_deafened: bool,
+) -> Result<(), Error> {
+    let from_mute = mute::get_active_mute(&data.pool, from.get() as i64, user_id.get() as i64).await?;
+    let to_mute = mute::get_active_mute(&data.pool, to.get() as i64, user_id.get() as i64).await?;
+    let globally_muted =
+        global_mute::is_globally_muted(&data.pool, guild_id.get() as i64, user_id.get() as i64)
+            .await?;
+
+    match moved_mute(from_mute.as_ref(), to_mute.as_ref(), globally_muted) {
+        // An unmute pending from an earlier leave still applies; it checks where they are when it fires
+        MovedMute::Untouched => {}
+        MovedMute::Keep { deafen } => {
+            data.pending_leave_unmutes.cancel(guild_id.get(), user_id.get());
+            if let Some(deafen) = deafen.filter(|&d| d != is_deafened) {
+                mute_service::apply_voice_state(ctx, guild_id, user_id, true, Some(deafen)).await?;
+            }
+            debug!(
+                "Kept mute on user {} moved from {} to {}, where they're also muted",
+                user_id, from, to
+            );
+        }
+        MovedMute::Lift { undeafen } => {
+            data.pending_leave_unmutes.cancel(guild_id.get(), user_id.get());
+            data.mark_pending_unmute(guild_id.get(), user_id.get());
+            mute_service::apply_voice_state(ctx, guild_id, user_id, false, undeafen.then_some(false))
+                .await?;
+            info!(
+                "Lifted mute on user {} moved from {} to {}, where they aren't muted",
+                user_id, from, to
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// `muted_move`: the user was moved here while muted and `handle_muted_move` already settled it
+/// `server_muted`: the user joined server-muted
 async fn handle_channel_join(
     ctx: &Context,
     data: &Arc<Data>,
     guild_id: GuildId,
     user_id: UserId,
     channel_id: ChannelId,
+    muted_move: bool,
+    server_muted: bool,
 ) -> Result<(), Error> {
     // Check if this is a JTC channel
-    if let Some((_config, is_casual)) =
-        guild_config::find_by_jtc_channel(&data.pool, channel_id.get() as i64).await?
-    {
+    let jtc = data.guild_config(guild_id.get()).await?.and_then(|config| {
+        let is_casual = config.jtc_type(channel_id.get() as i64)?;
+        Some((config, is_casual))
+    });
+    if let Some((config, is_casual)) = jtc {
         info!(
             "User {} joined JTC channel {} ({})",
             user_id,
@@ -101,12 +208,36 @@ async fn handle_channel_join(
             if is_casual { "casual" } else { "debate" }
         );
 
-        // Start the JTC flow
-        channel_creator::start_jtc_flow(ctx, data, guild_id, user_id, channel_id, is_casual)
-            .await?;
+        // Someone whose room was just deleted waits out the guild's cooldown before making another
+        if let Some(remaining) = data.recreate_cooldown_remaining(
+            guild_id.get(),
+            user_id.get(),
+            config.recreate_cooldown(),
+        ) {
+            info!("User {} is on the recreate cooldown for {:?}", user_id, remaining);
+            channel_creator::refuse_recreate(ctx, guild_id, user_id, remaining).await;
+            return Ok(());
+        }
+
+        // Rooms are only ever created by the queue processor, so bursts stay ordered and spaced
+        queue::queue_join(ctx, data, guild_id, user_id, channel_id, is_casual);
         return Ok(());
     }
 
+    // Globally-muted users stay muted wherever they go, and mods can get a heads-up
+    if global_mute::is_globally_muted(&data.pool, guild_id.get() as i64, user_id.get() as i64)
+        .await?
+    {
+        handle_globally_muted_join(ctx, data, guild_id, user_id, channel_id).await;
+    }
+
+    // A room deleted while they were offline couldn't lift its mute then
+    if server_muted && !muted_move {
+        if let Err(e) = mute_service::lift_orphaned_mutes(ctx, data, guild_id, user_id, channel_id).await {
+            error!("Failed to lift leftover mutes for user {}: {:?}", user_id, e);
+        }
+    }
+
     // Check if this is a managed voice channel
     if let Some(vc) = voice_channel::get(&data.pool, channel_id.get() as i64).await? {
         debug!(
@@ -114,21 +245,70 @@ async fn handle_channel_join(
             user_id, channel_id, vc.owner_id
         );
 
+        // Banned users can slip in if their deny was lost; they don't count as joining
+        if ban_service::enforce_ban_on_join(ctx, data, guild_id, channel_id, user_id).await? {
+            return Ok(());
+        }
+
+        if vc.owner_id as u64 == user_id.get() && channel_deleter::cancel_hold(data, channel_id) {
+            info!("Owner {} returned to held channel {}", user_id, channel_id);
+        }
+
+        // Someone came back to a room waiting out its empty grace; whoever it is keeps it
+        let is_bot = ctx.cache.user(user_id).is_some_and(|u| u.bot);
+        if !is_bot && channel_deleter::cancel_empty_deletion(data, channel_id) {
+            info!("User {} rejoined emptied channel {}, keeping it", user_id, channel_id);
+            if vc.owner_id as u64 != user_id.get() {
+                channel_deleter::transfer_ownership(ctx, data, channel_id, user_id.get()).await?;
+            }
+        }
+
         // Track activity for spam detection
         data.activity_tracker
-            .record_join(channel_id.get(), user_id.get(), data.settings.spam_window_seconds);
+            .record_join(channel_id.get(), user_id.get(), data.spam_activity_window());
+        // Remembered in case the voice state cache turns out to be missing them
+        data.recent_joins.record_join(channel_id.get(), user_id.get());
 
         // Check for spam and potentially prompt owner
         detector::check_spam(ctx, data, guild_id, channel_id, UserId::new(vc.owner_id as u64))
             .await?;
 
+        // Lock the room if it's being flooded with joins (opt-in per guild)
+        raid_guard::check_join_flood(ctx, data, guild_id, channel_id, UserId::new(vc.owner_id as u64))
+            .await?;
+
         // Check if user has an active mute for this channel
-        if let Some(_mute_record) =
-            mute::get_active_mute(&data.pool, channel_id.get() as i64, user_id.get() as i64).await?
-        {
-            // Re-apply mute
-            mute_service::apply_server_mute(ctx, guild_id, user_id, true).await?;
-            debug!("Re-applied mute to user {} in channel {}", user_id, channel_id);
+        let remute = if muted_move {
+            None
+        } else {
+            mute_service::should_remute(&data.pool, channel_id, user_id).await?
+        };
+        if let Some(record) = remute {
+            match data.pending_leave_unmutes.cancel(guild_id.get(), user_id.get()) {
+                // Still server-muted from the channel they just left; only the deafen may differ
+                Some(was_deafened) => {
+                    if was_deafened != record.is_deafened {
+                        mute_service::apply_voice_state(
+                            ctx,
+                            guild_id,
+                            user_id,
+                            true,
+                            Some(record.is_deafened),
+                        )
+                        .await?;
+                    }
+                    debug!(
+                        "Cancelled pending unmute for user {} rejoining muted channel {}",
+                        user_id, channel_id
+                    );
+                }
+                None => {
+                    // Re-apply mute (and deafen, if the mute included it)
+                    let deafen = record.is_deafened.then_some(true);
+                    mute_service::apply_voice_state(ctx, guild_id, user_id, true, deafen).await?;
+                    debug!("Re-applied mute to user {} in channel {}", user_id, channel_id);
+                }
+            }
         }
     }
 
@@ -140,6 +320,55 @@ async fn handle_channel_join(
     Ok(())
 }
 
+/// Re-apply a global mute if it was lifted and notify the mod log (if enabled)
+/// Failures are logged so they never block the rest of the join handling
+async fn handle_globally_muted_join(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    user_id: UserId,
+    channel_id: ChannelId,
+) {
+    let is_muted = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.voice_states.get(&user_id).map(|vs| vs.mute))
+        .unwrap_or(false);
+
+    if !is_muted {
+        match mute_service::apply_server_mute(ctx, guild_id, user_id, true).await {
+            Ok(()) => info!(
+                "Re-applied global mute to user {} in channel {}",
+                user_id, channel_id
+            ),
+            Err(e) => error!("Failed to re-apply global mute to user {}: {:?}", user_id, e),
+        }
+    }
+
+    let announce = match data.guild_config(guild_id.get()).await {
+        Ok(config) => config.is_some_and(|c| c.announce_global_mute_joins),
+        Err(e) => {
+            error!("Failed to load guild config for global mute alert: {:?}", e);
+            false
+        }
+    };
+
+    if !announce {
+        return;
+    }
+
+    let embed = embeds::warning_embed()
+        .title("Globally Muted User Joined")
+        .description(format!(
+            "<@{}> (`{}`) joined <#{}> and is still globally muted.",
+            user_id, user_id, channel_id
+        ));
+
+    if let Err(e) = mod_log::post(ctx, data, guild_id, embed).await {
+        error!("Failed to post global mute alert to mod log: {:?}", e);
+    }
+}
+
 /// Handle when a mute is detected on a user
 /// Determines if it's a VC-owner mute (tracked per-channel) or a global mute (never unmuted by bot)
 async fn handle_mute_detected(
@@ -154,6 +383,13 @@ async fn handle_mute_detected(
     match vc {
         Some(vc) => {
             // User is in a managed VC - this is a VC owner mute
+            // Unless it's the bot re-applying a global mute, which is already tracked
+            if global_mute::is_globally_muted(&data.pool, guild_id.get() as i64, user_id.get() as i64)
+                .await?
+            {
+                return Ok(());
+            }
+
             // Check if already tracked
             if mute::get_active_mute(&data.pool, channel_id.get() as i64, user_id.get() as i64)
                 .await?
@@ -175,6 +411,8 @@ async fn handle_mute_detected(
                 user_id.get() as i64,
                 owner_id as i64,
                 false, // not an admin mute
+                false, // manual mutes don't deafen
+                None,  // manual mutes don't expire
             )
             .await?;
         }
@@ -213,6 +451,42 @@ async fn handle_unmute_detected(
         return Ok(());
     }
 
+    // Clear any global mute record (if someone with server perms unmuted them)
+    let was_globally_muted =
+        global_mute::record_global_unmute(&data.pool, guild_id.get() as i64, user_id.get() as i64)
+            .await?;
+    if was_globally_muted {
+        info!(
+            "Cleared GLOBAL mute for user {} in guild {}",
+            user_id, guild_id
+        );
+    }
+
+    // Mirrors handle_mute_detected: an unmute outside a managed VC, or one that lifted a
+    // global mute, is a server-wide unmute and clears every channel's record so the user
+    // isn't re-muted on their next join
+    let in_managed_vc = voice_channel::get(&data.pool, channel_id.get() as i64)
+        .await?
+        .is_some();
+
+    if was_globally_muted || !in_managed_vc {
+        let cleared = mute::unmute_all_for_user_in_guild(
+            &data.pool,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .await?;
+
+        if cleared > 0 {
+            info!(
+                "Cleared {} mute record(s) for user {} in guild {} (server-wide unmute)",
+                cleared, user_id, guild_id
+            );
+        }
+
+        return Ok(());
+    }
+
     // This is a manual unmute by the channel owner - only clear THIS channel's mute record
     let cleared = mute::unmute_by_channel_user(
         &data.pool,
@@ -228,17 +502,27 @@ async fn handle_unmute_detected(
         );
     }
 
-    // Also clear any global mute record (if someone with server perms unmuted them)
-    if global_mute::record_global_unmute(&data.pool, guild_id.get() as i64, user_id.get() as i64)
-        .await?
-    {
-        info!(
-            "Cleared GLOBAL mute for user {} in guild {}",
-            user_id, guild_id
-        );
+    Ok(())
+}
+
+/// The guild's owner hop policy, if the owner moved into another managed channel
+async fn owner_hop_policy(
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    moved_to: Option<ChannelId>,
+) -> Result<Option<OwnerHopPolicy>, Error> {
+    let Some(moved_to) = moved_to else {
+        return Ok(None);
+    };
+
+    if voice_channel::get(&data.pool, moved_to.get() as i64).await?.is_none() {
+        return Ok(None);
     }
 
-    Ok(())
+    Ok(data
+        .guild_config(guild_id.get())
+        .await?
+        .map(|config| config.owner_hop_policy()))
 }
 
 async fn handle_channel_leave(
@@ -247,6 +531,8 @@ async fn handle_channel_leave(
     guild_id: GuildId,
     user_id: UserId,
     channel_id: ChannelId,
+    moved_to: Option<ChannelId>,
+    muted_move: bool,
 ) -> Result<(), Error> {
     // Check if this is a managed voice channel
     let vc = match voice_channel::get(&data.pool, channel_id.get() as i64).await? {
@@ -256,16 +542,33 @@ async fn handle_channel_leave(
 
     // Track activity for spam detection
     data.activity_tracker
-        .record_leave(channel_id.get(), user_id.get(), data.settings.spam_window_seconds);
+        .record_leave(channel_id.get(), user_id.get(), data.spam_activity_window());
+    data.recent_joins.record_leave(channel_id.get(), user_id.get());
 
     // Check if the owner left
     let owner_id = vc.owner_id as u64;
     if user_id.get() == owner_id {
         debug!("Owner {} left channel {}", user_id, channel_id);
 
+        // Owners hopping to another managed room may get theirs held, per guild policy
+        if let Some(policy) = owner_hop_policy(data, guild_id, moved_to).await? {
+            if policy != OwnerHopPolicy::Release {
+                channel_deleter::hold_for_owner(
+                    ctx,
+                    data,
+                    guild_id,
+                    channel_id,
+                    user_id,
+                    policy == OwnerHopPolicy::Prompt,
+                )
+                .await;
+                return Ok(());
+            }
+        }
+
         // Check if channel should be deleted
         channel_deleter::handle_owner_leave(ctx, data, guild_id, channel_id).await?;
-    } else {
+    } else if !muted_move {
         // Non-owner left - check if they should be unmuted
         if let Some(mute_record) =
             mute::get_active_mute(&data.pool, channel_id.get() as i64, user_id.get() as i64).await?
@@ -273,15 +576,15 @@ async fn handle_channel_leave(
             // Only unmute if it wasn't an admin mute
             if !mute_record.is_admin_mute {
                 // Delay unmute to allow for channel hopping
-                // If user jumps to another channel where they're also muted, they stay muted
+                // If user jumps to another channel where they're also muted, they stay muted,
+                // and a rejoin into a muted channel within the grace cancels this outright
                 let http = ctx.http.clone();
                 let cache = ctx.cache.clone();
                 let pool = data.pool.clone();
-                let data = data.clone();
-
-                tokio::spawn(async move {
-                    tokio::time::sleep(Duration::from_secs(UNMUTE_DELAY_SECONDS)).await;
+                let task_data = data.clone();
+                let grace_seconds = data.settings.unmute_grace_seconds;
 
+                let unmute = async move {
                     // First check: Is user globally muted? Never unmute them.
                     let is_globally_muted = match global_mute::is_globally_muted(
                         &pool,
@@ -313,26 +616,35 @@ async fn handle_channel_leave(
 
                     if let Some(current_channel_id) = current_channel {
                         // User is in a channel - check if they have a mute for THIS specific channel
-                        let is_muted_in_current = match mute::get_active_mute(
+                        let current_mute = match mute::get_active_mute(
                             &pool,
                             current_channel_id.get() as i64,
                             user_id.get() as i64,
                         )
                         .await
                         {
-                            Ok(Some(_)) => true,
-                            Ok(None) => false,
+                            Ok(record) => record,
                             Err(e) => {
                                 error!("Failed to check mute in current channel: {:?}", e);
-                                false // Unmute on error to be safe
+                                None // Unmute on error to be safe
                             }
                         };
 
-                        if is_muted_in_current {
+                        if let Some(current_mute) = current_mute {
                             debug!(
                                 "User {} is in channel {} where they're muted, keeping server mute",
                                 user_id, current_channel_id
                             );
+                            // The deafen only carries over if this channel's mute includes it too
+                            if mute_record.is_deafened && !current_mute.is_deafened {
+                                if let Err(e) = mute_service::apply_voice_state(
+                                    &http, guild_id, user_id, true, Some(false),
+                                )
+                                .await
+                                {
+                                    error!("Failed to undeafen user {} after hop: {:?}", user_id, e);
+                                }
+                            }
                             // Don't remove the server mute - they're in a channel where they're muted
                             // Don't clear any records - records are only cleared by owner unmute
                             return;
@@ -342,23 +654,93 @@ async fn handle_channel_leave(
                     // User is either not in any channel, or in a channel where they're NOT muted
                     // Remove the Discord server mute so they can talk elsewhere
                     // BUT DO NOT clear the mute record - that persists until owner unmutes them
-                    data.mark_pending_unmute(guild_id.get(), user_id.get());
+                    task_data.mark_pending_unmute(guild_id.get(), user_id.get());
 
-                    // Remove the Discord server mute (but keep the database record!)
+                    // Remove the Discord server mute and our deafen (but keep the database record!)
+                    let undeafen = mute_record.is_deafened.then_some(false);
                     if let Err(e) =
-                        mute_service::apply_server_mute(&http, guild_id, user_id, false).await
+                        mute_service::apply_voice_state(&http, guild_id, user_id, false, undeafen).await
                     {
                         error!("Failed to unmute user {} after delay: {:?}", user_id, e);
                     } else {
                         debug!(
                             "Removed server mute from user {} after {} second delay",
-                            user_id, UNMUTE_DELAY_SECONDS
+                            user_id, grace_seconds
                         );
                     }
-                });
+                };
+
+                data.pending_leave_unmutes.schedule(
+                    guild_id.get(),
+                    user_id.get(),
+                    mute_record.is_deafened,
+                    Duration::from_secs(grace_seconds),
+                    unmute,
+                );
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(is_admin_mute: bool, is_deafened: bool) -> MuteRecord {
+        MuteRecord {
+            id: uuid::Uuid::nil(),
+            guild_id: 1,
+            channel_id: 2,
+            muted_user_id: 3,
+            muted_by_user_id: 4,
+            is_admin_mute,
+            is_deafened,
+            muted_at: chrono::Utc::now(),
+            unmuted_at: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn only_muted_channel_switches_are_moves() {
+        let (a, b) = (Some(ChannelId::new(1)), Some(ChannelId::new(2)));
+        assert!(is_muted_move(a, b, true, true));
+
+        assert!(!is_muted_move(a, a, true, true));
+        assert!(!is_muted_move(None, b, true, true));
+        assert!(!is_muted_move(a, None, true, true));
+        // Muted or unmuted on the way is a mute change, handled by the join and leave paths
+        assert!(!is_muted_move(a, b, false, true));
+        assert!(!is_muted_move(a, b, true, false));
+    }
+
+    #[test]
+    fn move_keeps_mute_only_where_the_new_channel_has_one() {
+        let plain = record(false, false);
+        let deafened = record(false, true);
+
+        assert_eq!(moved_mute(Some(&plain), Some(&plain), false), MovedMute::Keep { deafen: None });
+        assert_eq!(
+            moved_mute(Some(&deafened), Some(&plain), false),
+            MovedMute::Keep { deafen: Some(false) }
+        );
+        assert_eq!(
+            moved_mute(None, Some(&deafened), false),
+            MovedMute::Keep { deafen: Some(true) }
+        );
+
+        assert_eq!(moved_mute(Some(&plain), None, false), MovedMute::Lift { undeafen: false });
+        assert_eq!(moved_mute(Some(&deafened), None, false), MovedMute::Lift { undeafen: true });
+    }
+
+    #[test]
+    fn move_leaves_mutes_the_bot_does_not_lift() {
+        let plain = record(false, false);
+        assert_eq!(moved_mute(Some(&plain), None, true), MovedMute::Untouched);
+        assert_eq!(moved_mute(Some(&plain), Some(&plain), true), MovedMute::Untouched);
+        assert_eq!(moved_mute(Some(&record(true, false)), None, false), MovedMute::Untouched);
+        assert_eq!(moved_mute(None, None, false), MovedMute::Untouched);
+    }
+}
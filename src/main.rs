@@ -1,20 +1,39 @@
-use jarvis::{bot, config::Settings, db};
+use jarvis::{bot, bot::logging::LogControl, config::provision, config::Settings, db};
 use tracing::{error, info};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
 
 #[tokio::main]
 async fn main() {
     // Load .env file if present
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
+    // Initialize tracing with a reloadable filter so levels can be changed at runtime
+    let (filter_layer, filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
     tracing_subscriber::registry()
-        .with(EnvFilter::from_default_env())
+        .with(filter_layer)
         .with(tracing_subscriber::fmt::layer())
         .init();
+    let log_control = LogControl::new(filter_handle, std::env::var("RUST_LOG").unwrap_or_default());
+
+    // Offline provisioning: apply a guild spec to the database and exit without starting the bot
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--setup-guild") {
+        let Some(path) = args.get(pos + 1) else {
+            error!("--setup-guild requires a path to a JSON guild spec");
+            std::process::exit(2);
+        };
+        if let Err(e) = setup_guilds(path).await {
+            error!("Guild setup failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     info!("Starting Jarvis Discord Bot");
 
+    // Compile the profanity word list once, before any names are checked
+    jarvis::utils::profanity::init();
+
     // Load settings
     let settings = match Settings::from_env() {
         Ok(s) => s,
@@ -42,8 +61,38 @@ async fn main() {
     info!("Database initialized successfully");
 
     // Start the bot
-    if let Err(e) = bot::framework::run(settings, pool).await {
+    if let Err(e) = bot::framework::run(settings, pool, log_control).await {
         error!("Bot error: {}", e);
         std::process::exit(1);
     }
 }
+
+/// Apply the guild spec at `path` to the database
+async fn setup_guilds(path: &str) -> Result<(), String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let specs = provision::parse_specs(&json)?;
+
+    let database_url = Settings::database_url_from_env()?;
+    let pool = db::pool::create_pool(&database_url)
+        .await
+        .map_err(|e| format!("Failed to create database pool: {}", e))?;
+    db::pool::run_migrations(&pool)
+        .await
+        .map_err(|e| format!("Failed to run migrations: {}", e))?;
+
+    for spec in &specs {
+        let config = provision::apply(&pool, spec)
+            .await
+            .map_err(|e| format!("Failed to configure guild {}: {}", spec.guild_id, e))?;
+        info!(
+            "Configured guild {} (casual JTC ready: {}, debate JTC ready: {})",
+            spec.guild_id,
+            config.is_jtc_configured(true),
+            config.is_jtc_configured(false)
+        );
+    }
+
+    info!("Configured {} guild(s) from {}", specs.len(), path);
+    Ok(())
+}
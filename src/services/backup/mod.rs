@@ -0,0 +1,69 @@
+//! Scheduled export of critical tables to S3-compatible storage
+//! Disabled unless `BACKUP_S3_*` settings are provided; failures are logged and retried next run.
+
+pub mod s3;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::db::queries::export;
+use s3::S3Uploader;
+
+/// Start the backup loop if backups are configured
+pub fn spawn_backup_task(data: Arc<Data>) -> Option<AbortHandle> {
+    let Some(settings) = data.settings.backup.clone() else {
+        debug!("Backups not configured, skipping backup task");
+        return None;
+    };
+
+    let interval = Duration::from_secs(settings.interval_hours.max(1) * 60 * 60);
+    info!(
+        "Started backup task: every {}h to {}/{}",
+        settings.interval_hours, settings.endpoint, settings.bucket
+    );
+
+    let task = tokio::spawn(async move {
+        let uploader = S3Uploader::new(settings);
+        // First tick fires immediately, so a misconfiguration shows up at startup
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match run_backup(&data.pool, &uploader).await {
+                Ok(prefix) => info!("Backup completed to {}", prefix),
+                Err(e) => error!("Backup failed: {:?}", e),
+            }
+        }
+    });
+
+    Some(task.abort_handle())
+}
+
+/// Export every table in `BACKUP_TABLES` under a timestamped prefix
+/// Returns the prefix the run was written to
+pub async fn run_backup(pool: &PgPool, uploader: &S3Uploader) -> Result<String, Error> {
+    let prefix = format!(
+        "{}/{}",
+        uploader.prefix(),
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+
+    for table in export::BACKUP_TABLES {
+        let json = export::table_json(pool, table).await?;
+        let key = format!("{}/{}.json", prefix, table);
+        uploader
+            .put_object(&key, json.into_bytes(), "application/json")
+            .await?;
+        debug!("Uploaded backup of {} to {}", table, key);
+    }
+
+    Ok(prefix)
+}
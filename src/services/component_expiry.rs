@@ -0,0 +1,183 @@
+//! Auto-expiry for interactive messages nobody acted on
+//! Sites that send buttons or menus register the message with a lifetime, and a sweep task cleans
+//! it up once that passes so nobody clicks a long-dead component. Tracking is in memory only, so
+//! messages sent before a restart are left as they are.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::{ChannelId, EditMessage, Http, Message, MessageId};
+use tokio::task::AbortHandle;
+use tracing::debug;
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::timeouts::COMPONENT_SWEEP_INTERVAL_SECONDS;
+
+/// What happens to an interactive message once it expires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// Remove the message entirely (it means nothing without its components)
+    Delete,
+    /// Keep the message as a record but strip its buttons and menus
+    Disable,
+}
+
+#[derive(Debug)]
+struct TrackedMessage {
+    channel_id: ChannelId,
+    expires_at: Instant,
+    expiry: Expiry,
+}
+
+/// Interactive messages waiting to expire
+#[derive(Debug, Default)]
+pub struct ExpiringComponents {
+    messages: DashMap<MessageId, TrackedMessage>,
+}
+
+impl ExpiringComponents {
+    /// Clean up `message` after `lifetime` unless it's handled first
+    pub fn track(&self, message: &Message, lifetime: Duration, expiry: Expiry) {
+        self.track_at(
+            message.channel_id,
+            message.id,
+            Instant::now() + lifetime,
+            expiry,
+        );
+    }
+
+    fn track_at(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        expires_at: Instant,
+        expiry: Expiry,
+    ) {
+        self.messages.insert(
+            message_id,
+            TrackedMessage {
+                channel_id,
+                expires_at,
+                expiry,
+            },
+        );
+    }
+
+    /// Stop tracking a message whose components were used and cleaned up already
+    pub fn forget(&self, message_id: MessageId) {
+        self.messages.remove(&message_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Remove and return every message that has expired by `now`
+    fn take_expired(&self, now: Instant) -> Vec<(ChannelId, MessageId, Expiry)> {
+        let expired: Vec<MessageId> = self
+            .messages
+            .iter()
+            .filter(|entry| entry.expires_at <= now)
+            .map(|entry| *entry.key())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|id| self.messages.remove(&id))
+            .map(|(id, tracked)| (tracked.channel_id, id, tracked.expiry))
+            .collect()
+    }
+}
+
+/// Periodically clean up expired interactive messages
+pub fn spawn_component_sweeper(http: Arc<Http>, data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(COMPONENT_SWEEP_INTERVAL_SECONDS));
+
+        loop {
+            ticker.tick().await;
+
+            // Stale buttons can wait; expired entries stay tracked until the next tick
+            if data.rate_limits.is_throttled() {
+                debug!("Rate limited, deferring component sweep");
+                continue;
+            }
+
+            for (channel_id, message_id, expiry) in
+                data.expiring_components.take_expired(Instant::now())
+            {
+                if let Err(e) = expire(&http, channel_id, message_id, expiry).await {
+                    data.rate_limits.record_error(&e);
+                    // Usually the message or channel is already gone
+                    debug!(
+                        "Could not expire message {} in {}: {:?}",
+                        message_id, channel_id, e
+                    );
+                }
+            }
+        }
+    })
+    .abort_handle()
+}
+
+async fn expire(
+    http: &Http,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    expiry: Expiry,
+) -> Result<(), Error> {
+    match expiry {
+        Expiry::Delete => channel_id.delete_message(http, message_id).await?,
+        Expiry::Disable => {
+            channel_id
+                .edit_message(http, message_id, EditMessage::new().components(vec![]))
+                .await?;
+        }
+    }
+    debug!(
+        "Expired interactive message {} in {}",
+        message_id, channel_id
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_expired_messages_are_taken() {
+        let components = ExpiringComponents::default();
+        let now = Instant::now();
+        let channel = ChannelId::new(1);
+
+        components.track_at(
+            channel,
+            MessageId::new(10),
+            now - Duration::from_secs(1),
+            Expiry::Delete,
+        );
+        components.track_at(
+            channel,
+            MessageId::new(11),
+            now + Duration::from_secs(60),
+            Expiry::Disable,
+        );
+        components.track_at(channel, MessageId::new(12), now, Expiry::Disable);
+        components.forget(MessageId::new(12));
+
+        assert_eq!(
+            components.take_expired(now),
+            vec![(channel, MessageId::new(10), Expiry::Delete)]
+        );
+        assert_eq!(components.len(), 1);
+        assert!(components.take_expired(now).is_empty());
+    }
+}
@@ -1,20 +1,51 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::Utc;
 use serenity::all::{
-    ChannelId, ChannelType as SerenityChannelType, Context, CreateChannel,
+    ChannelId, ChannelType as SerenityChannelType, Context, CreateChannel, CreateMessage,
     EditChannel, EditMember, GuildId, PermissionOverwrite, PermissionOverwriteType, Permissions,
-    UserId, VideoQualityMode,
+    RoleId, UserId, VideoQualityMode,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::components::naming_prompt;
+use crate::constants::embeds;
+use crate::constants::tags::{format_status, EmojiTag};
 use crate::constants::timeouts::VC_NAMING_DEADLINE_SECONDS;
-use crate::db::models::ChannelType;
-use crate::db::queries::{guild_config, user_vc_preference, voice_channel};
-use crate::services::jtc::welcome_embed;
+use crate::db::models::{ChannelType, VoiceChannel};
+use crate::db::queries::{room_permission, user_vc_preference, voice_channel};
+use crate::services::jtc::{owner_role, room_announcement, welcome_dm, welcome_embed};
+use crate::utils::duration::format_short;
+
+/// Discord's limit on channels in one category
+const CATEGORY_CHANNEL_LIMIT: usize = 50;
+/// Most overflow categories a guild can configure per channel type
+pub const MAX_OVERFLOW_CATEGORIES: usize = 3;
+/// Most roles a guild can give new rooms overwrites for
+pub const MAX_ROOM_PERMISSION_ROLES: usize = 10;
+/// What a room owner is allowed to do in their own voice channel
+pub const OWNER_PERMISSIONS: Permissions = Permissions::MUTE_MEMBERS;
+
+/// Name a new room gets when its owner has no saved name
+pub fn default_channel_name(is_casual: bool) -> &'static str {
+    if is_casual {
+        "Casual VC"
+    } else {
+        "Debate VC"
+    }
+}
+
+/// The voice channel overwrite that marks `user_id` as the room owner
+pub fn owner_overwrite(user_id: UserId) -> PermissionOverwrite {
+    PermissionOverwrite {
+        allow: OWNER_PERMISSIONS,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(user_id),
+    }
+}
 
 /// Start the JTC flow - check preferences or prompt for naming
 pub async fn start_jtc_flow(
@@ -46,19 +77,42 @@ pub async fn start_jtc_flow(
             user_id, prefs.preferred_name
         );
 
-        create_channel(
+        let untagged = prefs.preferred_tags.is_empty();
+        let channel_id = create_channel(
             ctx,
             data,
             guild_id,
             user_id,
             is_casual,
             prefs.preferred_name,
-            prefs.preferred_tags,
+            Some(prefs.preferred_tags),
         )
         .await?;
+
+        // A saved name alone doesn't configure the room in guilds that require tags
+        if untagged {
+            if let Some(config) = data
+                .guild_config(guild_id.get())
+                .await?
+                .filter(|config| config.require_tags)
+            {
+                let deadline_at =
+                    Utc::now() + chrono::Duration::seconds(VC_NAMING_DEADLINE_SECONDS as i64);
+                user_vc_preference::create_deadline(
+                    &data.pool,
+                    channel_id.get() as i64,
+                    guild_id.get() as i64,
+                    user_id.get() as i64,
+                    deadline_at,
+                )
+                .await?;
+
+                naming_prompt::send_tag_selector(ctx, data, &config, channel_id, is_casual).await;
+            }
+        }
     } else {
         // No preferences - create with default name and show naming prompt
-        let channel_id = create_channel(ctx, data, guild_id, user_id, is_casual, None, vec![]).await?;
+        let channel_id = create_channel(ctx, data, guild_id, user_id, is_casual, None, None).await?;
 
         // Create deadline for configuration
         let deadline_at = Utc::now() + chrono::Duration::seconds(VC_NAMING_DEADLINE_SECONDS as i64);
@@ -72,13 +126,14 @@ pub async fn start_jtc_flow(
         .await?;
 
         // Send naming prompt
-        naming_prompt::send_prompt(ctx, channel_id, user_id).await?;
+        naming_prompt::send_prompt(ctx, data, guild_id, channel_id, user_id).await?;
     }
 
     Ok(())
 }
 
 /// Create a new voice channel for the user
+/// `tags: None` means the user has no saved preference, so the guild's default tags apply
 pub async fn create_channel(
     ctx: &Context,
     data: &Arc<Data>,
@@ -86,13 +141,34 @@ pub async fn create_channel(
     user_id: UserId,
     is_casual: bool,
     topic: Option<String>,
-    tags: Vec<String>,
+    tags: Option<Vec<String>>,
 ) -> Result<ChannelId, Error> {
-    let config = guild_config::get(&data.pool, guild_id.get() as i64)
+    let config = data
+        .guild_config(guild_id.get())
         .await?
         .ok_or(Error::JtcNotConfigured)?;
 
-    let category_id = config.category_id(is_casual).ok_or(Error::JtcNotConfigured)?;
+    let tags = tags.unwrap_or_else(|| config.default_tags(is_casual).to_vec());
+
+    let main_category = config.category_id(is_casual).ok_or(Error::JtcNotConfigured)?;
+    // A discussion channel needs a slot in the same category as its room
+    let slots_needed = if config.create_discussion_channels { 2 } else { 1 };
+    let category_id = match pick_category(
+        ctx,
+        guild_id,
+        std::iter::once(main_category).chain(config.overflow_category_ids(is_casual).iter().copied()),
+        slots_needed,
+    ) {
+        Some(id) => id,
+        None => {
+            notify_categories_full(ctx, user_id).await;
+            return Err(Error::InvalidOperation(format!(
+                "All {} categories in guild {} are full",
+                if is_casual { "casual" } else { "debate" },
+                guild_id
+            )));
+        }
+    };
 
     let channel_type = if is_casual {
         ChannelType::Casual
@@ -100,14 +176,13 @@ pub async fn create_channel(
         ChannelType::Debate
     };
 
+    // The guild's role template, plus the owner's overwrite
+    let role_overwrites = room_role_overwrites(ctx, data, guild_id).await?;
+
     // Generate channel name
-    let channel_name = if let Some(ref t) = topic {
-        t.clone()
-    } else if is_casual {
-        format!("Casual VC")
-    } else {
-        format!("Debate VC")
-    };
+    let channel_name = topic
+        .clone()
+        .unwrap_or_else(|| default_channel_name(is_casual).to_string());
 
     // Create the voice channel with highest quality settings
     // Bitrate: 96kbps (universal max - higher requires server boosts)
@@ -120,14 +195,9 @@ pub async fn create_channel(
                 .category(ChannelId::new(category_id as u64))
                 .bitrate(96_000) // 96kbps - max for all servers
                 .video_quality_mode(VideoQualityMode::Full) // 720p video
-                .permissions(vec![
-                    // Owner can only mute members
-                    PermissionOverwrite {
-                        allow: Permissions::MUTE_MEMBERS,
-                        deny: Permissions::empty(),
-                        kind: PermissionOverwriteType::Member(user_id),
-                    },
-                ]),
+                .permissions(
+                    role_overwrites.iter().cloned().chain([owner_overwrite(user_id)]),
+                ),
         )
         .await?;
 
@@ -136,20 +206,61 @@ pub async fn create_channel(
         channel_type, channel.id, user_id
     );
 
+    // Paired discussion text channel; don't leave a half-made room behind if it fails
+    let text_channel_id = if config.create_discussion_channels {
+        let created = create_discussion_channel(
+            ctx,
+            guild_id,
+            category_id,
+            &channel_name,
+            &role_overwrites,
+            user_id,
+        );
+        match created.await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                error!("Failed to create discussion channel for {}: {:?}", channel.id, e);
+                delete_created_channels(ctx, channel.id, None).await;
+                return Err(e);
+            }
+        }
+    } else {
+        None
+    };
+
     // Store in database
-    voice_channel::create(
-        &data.pool,
-        channel.id.get() as i64,
-        guild_id.get() as i64,
-        user_id.get() as i64,
-        channel_type,
-        topic.as_deref(),
-        &tags,
-    )
-    .await?;
+    let stored = async {
+        voice_channel::create(
+            &data.pool,
+            channel.id.get() as i64,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            channel_type,
+            topic.as_deref(),
+            &tags,
+        )
+        .await?;
+        if let Some(text_id) = text_channel_id {
+            voice_channel::set_text_channel(
+                &data.pool,
+                channel.id.get() as i64,
+                Some(text_id.get() as i64),
+            )
+            .await?;
+        }
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+    if let Err(e) = stored {
+        // Untracked channels would never be cleaned up
+        delete_created_channels(ctx, channel.id, text_channel_id).await;
+        let _ = voice_channel::delete(&data.pool, channel.id.get() as i64).await;
+        return Err(e.into());
+    }
 
     // Update cache
     data.set_channel_owner(channel.id.get(), user_id.get());
+    owner_role::grant(&ctx.http, &data.pool, guild_id, user_id).await;
 
     // Move user to the new channel
     if let Err(e) = guild_id
@@ -161,23 +272,225 @@ pub async fn create_channel(
 
     // Set channel status with tags if available
     if !tags.is_empty() {
-        let status_text = tags.iter().map(|t| format!("`{}`", t)).collect::<Vec<_>>().join(" ");
-        if let Err(e) = channel.id.edit(ctx, EditChannel::new().status(&status_text)).await {
-            debug!("Could not set channel status during creation (may not be available): {:?}", e);
-        } else {
-            info!("Set channel status for {}: {}", channel.id, status_text);
-        }
+        set_tag_status(ctx, channel.id, &tags).await;
     }
 
     // Send welcome embed in the text-in-voice channel
     welcome_embed::send(ctx, data, channel.id, user_id, is_casual).await?;
 
+    // Opt-in "come join" post, sent in the background so it never holds up the room
+    if config.channel_created_notify_channel_id.is_some() {
+        let http = ctx.http.clone();
+        let config = config.clone();
+        let channel_id = channel.id;
+        tokio::spawn(async move {
+            room_announcement::announce_created(
+                &http,
+                &config,
+                channel_id,
+                user_id,
+                is_casual,
+                &channel_name,
+            )
+            .await;
+        });
+    }
+
+    // First-time owners get a one-time DM, sent in the background so it never holds up the room
+    if config.send_welcome_dm {
+        let http = ctx.http.clone();
+        let pool = data.pool.clone();
+        let channel_id = channel.id;
+        tokio::spawn(async move {
+            welcome_dm::send_if_first(&http, &pool, &config, channel_id, user_id).await;
+        });
+    }
+
     // Clean up pending status
     data.jtc_pending.remove(&user_id.get());
 
     Ok(channel.id)
 }
 
+/// Pick the first category with `slots_needed` free channel slots
+/// Falls back to the first candidate when the guild isn't cached, so creation is never blocked on the cache
+fn pick_category(
+    ctx: &Context,
+    guild_id: GuildId,
+    mut candidates: impl Iterator<Item = i64>,
+    slots_needed: usize,
+) -> Option<i64> {
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return candidates.next();
+    };
+
+    candidates.find(|&category_id| {
+        let category = ChannelId::new(category_id as u64);
+        let children = guild
+            .channels
+            .values()
+            .filter(|c| c.parent_id == Some(category))
+            .count();
+        children + slots_needed <= CATEGORY_CHANNEL_LIMIT
+    })
+}
+
+/// Tell a user their room couldn't be made because every category is full
+async fn notify_categories_full(ctx: &Context, user_id: UserId) {
+    let embed = embeds::warning_embed()
+        .title("No Room Available")
+        .description(
+            "Every room category on this server is full right now, so a room couldn't be made for you. \
+            Try again once some rooms have closed, or let the server staff know.",
+        );
+
+    match user_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(ctx, CreateMessage::new().embed(embed)).await {
+                debug!("Could not DM user {} about full categories: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for user {}: {:?}", user_id, e);
+        }
+    }
+}
+
+/// Turn away a user still on the guild's recreate cooldown: move them out of the JTC channel and
+/// tell them how long to wait
+pub async fn refuse_recreate(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    remaining: Duration,
+) {
+    if let Err(e) = guild_id.disconnect_member(ctx, user_id).await {
+        warn!("Failed to disconnect {} from JTC on recreate cooldown: {:?}", user_id, e);
+    }
+
+    let embed = embeds::warning_embed()
+        .title("Please Wait")
+        .description(format!(
+            "Your last room was deleted moments ago. You can create a new one in {}.",
+            // Rounded up, so the last moments don't read as "0s"
+            format_short(Duration::from_secs(remaining.as_secs_f64().ceil() as u64))
+        ));
+
+    match user_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(ctx, CreateMessage::new().embed(embed)).await {
+                debug!("Could not DM user {} about the recreate cooldown: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for user {}: {:?}", user_id, e);
+        }
+    }
+}
+
+/// Create the text channel paired with a voice room, with message management for the owner
+async fn create_discussion_channel(
+    ctx: &Context,
+    guild_id: GuildId,
+    category_id: i64,
+    name: &str,
+    role_overwrites: &[PermissionOverwrite],
+    owner_id: UserId,
+) -> Result<ChannelId, Error> {
+    let channel = guild_id
+        .create_channel(
+            ctx,
+            CreateChannel::new(name)
+                .kind(SerenityChannelType::Text)
+                .category(ChannelId::new(category_id as u64))
+                .permissions(
+                    role_overwrites.iter().cloned().chain([discussion_owner_overwrite(owner_id)]),
+                ),
+        )
+        .await?;
+
+    info!("Created discussion channel {} for user {}", channel.id, owner_id);
+
+    Ok(channel.id)
+}
+
+/// Overwrites from the guild's room permission template
+/// Roles deleted since they were configured are skipped
+async fn room_role_overwrites(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+) -> Result<Vec<PermissionOverwrite>, Error> {
+    let templates = room_permission::list(&data.pool, guild_id.get() as i64).await?;
+    if templates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let roles: Option<Vec<RoleId>> =
+        ctx.cache.guild(guild_id).map(|guild| guild.roles.keys().copied().collect());
+
+    Ok(templates
+        .into_iter()
+        .filter_map(|template| {
+            let role_id = RoleId::new(template.role_id as u64);
+            // Without a cached guild, trust the template rather than drop it
+            if roles.as_ref().is_some_and(|roles| !roles.contains(&role_id)) {
+                warn!(
+                    "Skipping room permissions for deleted role {} in guild {}",
+                    role_id, guild_id
+                );
+                return None;
+            }
+            Some(PermissionOverwrite {
+                allow: Permissions::from_bits_truncate(template.allow as u64),
+                deny: Permissions::from_bits_truncate(template.deny as u64),
+                kind: PermissionOverwriteType::Role(role_id),
+            })
+        })
+        .collect())
+}
+
+/// Owner permissions on a discussion channel
+fn discussion_owner_overwrite(owner_id: UserId) -> PermissionOverwrite {
+    PermissionOverwrite {
+        allow: Permissions::MANAGE_MESSAGES,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(owner_id),
+    }
+}
+
+/// Roll back channels made by a failed `create_channel`
+async fn delete_created_channels(ctx: &Context, voice_id: ChannelId, text_id: Option<ChannelId>) {
+    for id in std::iter::once(voice_id).chain(text_id) {
+        if let Err(e) = id.delete(ctx).await {
+            error!("Failed to roll back channel {}: {:?}", id, e);
+        }
+    }
+}
+
+/// Move discussion channel owner permissions after an ownership transfer
+/// No-op for rooms without a discussion channel
+pub async fn transfer_discussion_channel(
+    ctx: &Context,
+    vc: &VoiceChannel,
+    old_owner: UserId,
+    new_owner: UserId,
+) -> Result<(), Error> {
+    let Some(text_id) = vc.text_channel_id else {
+        return Ok(());
+    };
+    let text_id = ChannelId::new(text_id as u64);
+
+    let _ = text_id
+        .delete_permission(ctx, PermissionOverwriteType::Member(old_owner))
+        .await;
+    text_id
+        .create_permission(ctx, discussion_owner_overwrite(new_owner))
+        .await?;
+
+    Ok(())
+}
+
 /// Update channel topic and name
 pub async fn update_channel_topic(
     ctx: &Context,
@@ -186,16 +499,69 @@ pub async fn update_channel_topic(
     topic: &str,
 ) -> Result<(), Error> {
     // Update database
-    voice_channel::update_topic(&data.pool, channel_id.get() as i64, Some(topic)).await?;
+    let vc = voice_channel::update_topic(&data.pool, channel_id.get() as i64, Some(topic)).await?;
 
     // Update Discord channel name
-    channel_id
+    let channel = channel_id
         .edit(ctx, EditChannel::new().name(topic))
         .await?;
 
+    // Edits have been seen to drop overwrites; make sure the owner can still mute
+    if let Some(owner_id) = data.get_channel_owner(channel_id.get()) {
+        let owner_id = UserId::new(owner_id);
+        let has_owner_overwrite = channel.permission_overwrites.iter().any(|overwrite| {
+            overwrite.kind == PermissionOverwriteType::Member(owner_id)
+                && overwrite.allow.contains(OWNER_PERMISSIONS)
+        });
+        if !has_owner_overwrite {
+            warn!(
+                "Owner overwrite for {} missing on {} after rename, reapplying",
+                owner_id, channel_id
+            );
+            channel_id.create_permission(ctx, owner_overwrite(owner_id)).await?;
+        }
+    }
+
+    // Keep the discussion channel's name in step
+    if let Some(text_id) = vc.and_then(|vc| vc.text_channel_id) {
+        if let Err(e) = ChannelId::new(text_id as u64)
+            .edit(ctx, EditChannel::new().name(topic))
+            .await
+        {
+            debug!("Could not rename discussion channel {}: {:?}", text_id, e);
+        }
+    }
+
     Ok(())
 }
 
+/// Show tags in the voice channel status
+/// Channel status may not be available everywhere, and Discord rejects custom emoji it can't resolve,
+/// so a failed update is retried with the text tags alone before giving up quietly
+async fn set_tag_status(ctx: &Context, channel_id: ChannelId, tags: &[String]) {
+    let status_text = format_status(tags);
+    let Err(e) = channel_id.edit(ctx, EditChannel::new().status(&status_text)).await else {
+        info!("Set channel status for {}: {}", channel_id, status_text);
+        return;
+    };
+
+    let text_tags: Vec<String> = tags
+        .iter()
+        .filter(|t| EmojiTag::parse(t).is_none())
+        .cloned()
+        .collect();
+    if text_tags.len() == tags.len() {
+        debug!("Could not set channel status for {} (may not be available): {:?}", channel_id, e);
+        return;
+    }
+
+    debug!("Could not set channel status with emoji for {}, retrying without: {:?}", channel_id, e);
+    let status_text = format_status(&text_tags);
+    if let Err(e) = channel_id.edit(ctx, EditChannel::new().status(&status_text)).await {
+        debug!("Could not set channel status for {} (may not be available): {:?}", channel_id, e);
+    }
+}
+
 /// Update channel tags
 pub async fn update_channel_tags(
     ctx: &Context,
@@ -208,16 +574,7 @@ pub async fn update_channel_tags(
 
     // Set tags in channel status if available
     if !tags.is_empty() {
-        let status_text = tags.iter().map(|t| format!("`{}`", t)).collect::<Vec<_>>().join(" ");
-        
-        // Try to set channel status (activity status)
-        // This may not be available on all Discord servers/plans
-        if let Err(e) = channel_id.edit(ctx, EditChannel::new().status(&status_text)).await {
-            // Status not available or failed - that's okay, just log it
-            tracing::debug!("Could not set channel status (may not be available): {:?}", e);
-        } else {
-            tracing::info!("Set channel status for {}: {}", channel_id, status_text);
-        }
+        set_tag_status(ctx, channel_id, &tags).await;
     } else {
         // Clear status if no tags
         if let Err(e) = channel_id.edit(ctx, EditChannel::new().status("")).await {
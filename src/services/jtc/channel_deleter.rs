@@ -1,12 +1,33 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use serenity::all::{ChannelId, Context, GuildId, Http};
+use chrono::Utc;
+use serenity::all::{
+    ChannelId, Context, GuildId, Http, HttpError, StatusCode, UserId, VoiceState,
+};
 use sqlx::PgPool;
+use tokio::task::{AbortHandle, JoinSet};
 use tracing::{debug, info, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::db::queries::voice_channel;
+use crate::components::room_hold;
+use crate::constants::embeds;
+use crate::constants::timeouts::{
+    DELETION_RETRY_SECONDS, MAX_DELETION_RETRIES, ORPHAN_REAP_BATCH_SIZE,
+    ORPHAN_REAP_INTERVAL_SECONDS, OWNER_HOP_HOLD_SECONDS,
+};
+use crate::db::models::VoiceChannel;
+use crate::db::queries::{guild_config, mute, user_vc_preference, voice_channel};
+use crate::services::jtc::{
+    channel_creator, channel_lock, deadline_tracker, owner_role, voice_presence,
+};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
+use crate::services::moderation::{ban_service, mute_service};
+
+/// Discord channel deletions allowed in flight at once; the rest wait their turn
+/// Keeps a mass exit (an event ending) from bursting into rate limits and failed deletes
+pub const MAX_CONCURRENT_DELETIONS: usize = 3;
 
 /// Handle when the channel owner leaves
 pub async fn handle_owner_leave(
@@ -16,7 +37,7 @@ pub async fn handle_owner_leave(
     channel_id: ChannelId,
 ) -> Result<(), Error> {
     // Get current member count in the channel
-    let member_count = get_channel_member_count(ctx, guild_id, channel_id).await;
+    let member_count = get_channel_member_count(ctx, data, guild_id, channel_id).await;
 
     debug!(
         "Owner left channel {}, {} members remaining",
@@ -24,11 +45,23 @@ pub async fn handle_owner_leave(
     );
 
     if member_count < 2 {
+        // A streaming owner's room is held instead, and looked at again when the hold ends
+        if let Some(owner_id) = streaming_owner(ctx, data, guild_id, channel_id).await? {
+            info!("Keeping channel {} while owner {} is streaming", channel_id, owner_id);
+            hold_for_owner(ctx, data, guild_id, channel_id, owner_id, false).await;
+            return Ok(());
+        }
+
+        // Fully emptied rooms may be kept for a while in case someone comes back
+        if member_count == 0 && schedule_empty_deletion(ctx, data, guild_id, channel_id).await? {
+            return Ok(());
+        }
+
         // Delete the channel
         delete_channel(ctx, data, channel_id).await?;
     } else {
         // Transfer ownership to another member
-        if let Some(new_owner) = get_next_owner(ctx, guild_id, channel_id).await {
+        if let Some(new_owner) = get_next_owner(ctx, data, guild_id, channel_id, None).await {
             transfer_ownership(ctx, data, channel_id, new_owner).await?;
         } else {
             // No valid owner found, delete the channel
@@ -39,41 +72,484 @@ pub async fn handle_owner_leave(
     Ok(())
 }
 
+/// Schedule another look at a room Discord refused to delete, backing off each time
+/// Only one retry per room is pending at once; after `MAX_DELETION_RETRIES` the bot gives up
+/// and tells the mod log, leaving the room managed.
+async fn schedule_retry(ctx: &Context, data: &Arc<Data>, guild_id: GuildId, channel_id: ChannelId) {
+    let attempt = {
+        let mut retry = data.deletion_retries.entry(channel_id.get()).or_insert((0, false));
+        let (attempts, scheduled) = &mut *retry;
+        if *scheduled {
+            return;
+        }
+        *attempts += 1;
+        *scheduled = true;
+        *attempts
+    };
+
+    if attempt > MAX_DELETION_RETRIES {
+        data.deletion_retries.remove(&channel_id.get());
+        warn!("Giving up on deleting channel {} after {} attempts", channel_id, MAX_DELETION_RETRIES);
+        alert_undeletable(
+            ctx,
+            data,
+            guild_id,
+            format!(
+                "Discord refused to delete <#{}> {} times in a row. It's still managed; delete it by hand if it should go.",
+                channel_id, MAX_DELETION_RETRIES
+            ),
+        )
+        .await;
+        return;
+    }
+
+    let delay = retry_delay(attempt);
+    info!("Retrying deletion of channel {} in {:?} (attempt {})", channel_id, delay, attempt);
+
+    let ctx = ctx.clone();
+    let data = Arc::clone(data);
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        // The room may have been deleted some other way in the meantime
+        match data.deletion_retries.get_mut(&channel_id.get()) {
+            Some(mut retry) => retry.1 = false,
+            None => return,
+        }
+
+        // The attempt count is kept until the room is gone, so a room that keeps coming back
+        // here (e.g. through an empty grace) still runs out of retries
+        if let Err(e) = retry_room(&ctx, &data, guild_id, channel_id).await {
+            warn!("Failed to retry deletion of channel {}: {:?}", channel_id, e);
+        }
+    });
+}
+
+/// Wait before retry `attempt` (1-based): `DELETION_RETRY_SECONDS`, doubling each time
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(DELETION_RETRY_SECONDS << attempt.saturating_sub(1).min(10))
+}
+
+/// Decide again what to do with a room whose deletion failed a while ago
+/// A room its owner left goes through the owner-leave handling once more; with the owner
+/// inside, it's only deleted if it's still unconfigured (its naming deadline ran out).
+/// Boxed because a failed deletion from here schedules the next retry.
+fn retry_room<'a>(
+    ctx: &'a Context,
+    data: &'a Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> poise::BoxFuture<'a, Result<(), Error>> {
+    Box::pin(async move {
+        let Some(vc) = voice_channel::get(&data.pool, channel_id.get() as i64).await? else {
+            return Ok(());
+        };
+
+        let owner_id = UserId::new(vc.owner_id as u64);
+        let owner_present = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.voice_states.get(&owner_id).and_then(|vs| vs.channel_id))
+            == Some(channel_id);
+        if !owner_present {
+            return handle_owner_leave(ctx, data, guild_id, channel_id).await;
+        }
+
+        let require_tags = data
+            .guild_config(guild_id.get())
+            .await?
+            .is_some_and(|config| config.require_tags);
+        if deadline_tracker::is_unconfigured(vc.topic.as_deref(), &vc.tags, require_tags) {
+            delete_channel(ctx, data, channel_id).await?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Whether the bot may delete a channel, going by the cache
+/// Returns None when the cache can't tell
+fn can_delete(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Option<bool> {
+    let guild = ctx.cache.guild(guild_id)?;
+    let channel = guild.channels.get(&channel_id)?;
+    let me = guild.members.get(&ctx.cache.current_user().id)?;
+    Some(guild.user_permissions_in(channel, me).manage_channels())
+}
+
+/// Whether Discord refused because the bot lacks access (Missing Access / Missing Permissions)
+/// Retrying these can't succeed until someone changes the bot's permissions
+fn is_missing_access(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+            response.status_code == StatusCode::FORBIDDEN
+                || matches!(response.error.code, 50001 | 50013)
+        }
+        _ => false,
+    }
+}
+
+/// Stop managing a room the bot isn't allowed to delete
+/// Its mutes are left as they are; the mod log is told so someone can clean up by hand.
+async fn abandon_room(ctx: &Context, data: &Arc<Data>, vc: &VoiceChannel) -> Result<(), Error> {
+    let channel_id = ChannelId::new(vc.channel_id as u64);
+    let guild_id = GuildId::new(vc.guild_id as u64);
+    warn!("Missing permission to delete channel {}, no longer managing it", channel_id);
+
+    data.remove_channel(channel_id.get());
+    data.activity_tracker.cleanup_channel(channel_id.get());
+    voice_channel::delete(&data.pool, vc.channel_id).await?;
+    user_vc_preference::remove_deadline(&data.pool, vc.channel_id).await?;
+    owner_role::revoke(&ctx.http, &data.pool, guild_id, UserId::new(vc.owner_id as u64)).await;
+
+    alert_undeletable(
+        ctx,
+        data,
+        guild_id,
+        format!(
+            "I don't have permission to delete <#{}> (owned by <@{}>), so I've stopped managing it. \
+             Give me Manage Channels on it, or delete it by hand.",
+            channel_id, vc.owner_id
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Tell the guild's mod log that a room couldn't be deleted
+async fn alert_undeletable(ctx: &Context, data: &Arc<Data>, guild_id: GuildId, description: String) {
+    let embed = embeds::warning_embed()
+        .title("Room Not Deleted")
+        .description(description);
+    if let Err(e) = mod_log::post(ctx, data, guild_id, embed).await {
+        warn!("Failed to post deletion alert to the mod log of guild {}: {:?}", guild_id, e);
+    }
+}
+
+/// The room's owner, if they're streaming or on camera somewhere and the guild keeps such rooms
+async fn streaming_owner(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<Option<UserId>, Error> {
+    let Some(owner_id) = data.get_channel_owner(channel_id.get()).map(UserId::new) else {
+        return Ok(None);
+    };
+
+    let streaming = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.voice_states.get(&owner_id).map(is_streaming))
+        .unwrap_or(false);
+    if !streaming {
+        return Ok(None);
+    }
+
+    let keep = guild_config::get(&data.pool, guild_id.get() as i64)
+        .await?
+        .is_some_and(|config| config.keep_rooms_while_streaming);
+    Ok(keep.then_some(owner_id))
+}
+
+/// Whether a voice state is connected with a stream or camera on
+fn is_streaming(state: &VoiceState) -> bool {
+    state.channel_id.is_some() && (state.self_stream.unwrap_or(false) || state.self_video)
+}
+
+/// Keep an emptied room for the guild's grace period, then delete it if it's still empty
+/// Returns false (and schedules nothing) when the guild has no grace configured
+async fn schedule_empty_deletion(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let grace_seconds = guild_config::get(&data.pool, guild_id.get() as i64)
+        .await?
+        .map(|config| config.empty_channel_grace_seconds.max(0) as u64)
+        .unwrap_or(0);
+    if grace_seconds == 0 {
+        return Ok(false);
+    }
+
+    debug!(
+        "Channel {} is empty, deleting in {}s unless someone joins",
+        channel_id, grace_seconds
+    );
+
+    let scheduled_at = Instant::now();
+    let task_ctx = ctx.clone();
+    let task_data = Arc::clone(data);
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(grace_seconds)).await;
+
+        // Claim the deletion; once claimed a join can no longer abort it halfway through
+        if task_data
+            .pending_deletions
+            .remove_if(&channel_id.get(), |_, (at, _)| *at == scheduled_at)
+            .is_none()
+        {
+            return;
+        }
+
+        if get_channel_member_count(&task_ctx, &task_data, guild_id, channel_id).await > 0 {
+            return;
+        }
+
+        if let Err(e) = delete_channel(&task_ctx, &task_data, channel_id).await {
+            warn!("Failed to delete channel {} after empty grace: {:?}", channel_id, e);
+        }
+    });
+
+    if let Some((_, task)) = data
+        .pending_deletions
+        .insert(channel_id.get(), (scheduled_at, task.abort_handle()))
+    {
+        task.abort();
+    }
+
+    Ok(true)
+}
+
+/// Cancel a pending empty-room deletion because someone joined
+pub fn cancel_empty_deletion(data: &Data, channel_id: ChannelId) -> bool {
+    match data.pending_deletions.remove(&channel_id.get()) {
+        Some((_, (_, task))) => {
+            task.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Hold a room for an owner who hopped to another managed channel
+/// After `OWNER_HOP_HOLD_SECONDS` the room is released as if they had left, unless they came back.
+/// With `notify`, the owner is DMed a button to release it early.
+pub async fn hold_for_owner(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+    notify: bool,
+) {
+    let held_since = Instant::now();
+    data.held_rooms.insert(channel_id.get(), held_since);
+
+    info!(
+        "Holding channel {} for owner {} for {}s",
+        channel_id, owner_id, OWNER_HOP_HOLD_SECONDS
+    );
+
+    if notify {
+        room_hold::send_hold_notice(ctx, data, guild_id, channel_id, owner_id).await;
+    }
+
+    let ctx = ctx.clone();
+    let data = Arc::clone(data);
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(OWNER_HOP_HOLD_SECONDS)).await;
+
+        // A newer hold, an early release or the owner returning all take this one over
+        if data
+            .held_rooms
+            .remove_if(&channel_id.get(), |_, since| *since == held_since)
+            .is_none()
+        {
+            return;
+        }
+
+        if let Err(e) = release_room(&ctx, &data, guild_id, channel_id, owner_id).await {
+            warn!("Failed to release held channel {}: {:?}", channel_id, e);
+        }
+    });
+}
+
+/// Release a held room now, if it's still held
+/// Returns false if there was nothing to release
+pub async fn release_held_room(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) -> Result<bool, Error> {
+    if data.held_rooms.remove(&channel_id.get()).is_none() {
+        return Ok(false);
+    }
+
+    release_room(ctx, data, guild_id, channel_id, owner_id).await?;
+
+    Ok(true)
+}
+
+/// Cancel the hold on a room because its owner is back
+pub fn cancel_hold(data: &Data, channel_id: ChannelId) -> bool {
+    data.held_rooms.remove(&channel_id.get()).is_some()
+}
+
+/// Run the normal owner-leave handling for a held room, unless things changed in the meantime
+/// Boxed because a room kept for a streaming owner is held again from here.
+fn release_room<'a>(
+    ctx: &'a Context,
+    data: &'a Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) -> poise::BoxFuture<'a, Result<(), Error>> {
+    Box::pin(async move {
+        // The room may have been deleted or handed over while it was held
+        if data.get_channel_owner(channel_id.get()) != Some(owner_id.get()) {
+            return Ok(());
+        }
+
+        let owner_present = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.voice_states.get(&owner_id).and_then(|vs| vs.channel_id))
+            == Some(channel_id);
+        if owner_present {
+            return Ok(());
+        }
+
+        info!("Releasing held channel {} (owner {} didn't return)", channel_id, owner_id);
+        handle_owner_leave(ctx, data, guild_id, channel_id).await
+    })
+}
+
+/// What became of an attempt to delete a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteOutcome {
+    Deleted,
+    /// Discord refused; the room stays managed and another attempt is scheduled
+    Failed,
+    /// The bot isn't allowed to delete the room, so it's no longer managed
+    Forbidden,
+}
+
 /// Delete a managed voice channel
+/// A refused deletion leaves the room managed with its mutes in place and is retried with
+/// backoff; one the bot has no permission for is given up on right away.
 pub async fn delete_channel(
     ctx: &Context,
     data: &Arc<Data>,
     channel_id: ChannelId,
-) -> Result<(), Error> {
-    // Delete from database first
-    voice_channel::delete(&data.pool, channel_id.get() as i64).await?;
+) -> Result<DeleteOutcome, Error> {
+    // Look up the paired discussion channel and owner before the row goes away
+    let vc = voice_channel::get(&data.pool, channel_id.get() as i64).await?;
 
-    // Remove from cache
-    data.remove_channel(channel_id.get());
+    // Checked before anything is touched, so a room that can't go keeps its mutes
+    if let Some(vc) = &vc {
+        if can_delete(ctx, GuildId::new(vc.guild_id as u64), channel_id) == Some(false) {
+            abandon_room(ctx, data, vc).await?;
+            return Ok(DeleteOutcome::Forbidden);
+        }
+    }
+
+    // Drop the owner first, so nothing acts on the room while it's being deleted
+    // The rest of its state is only cleared once Discord confirms the deletion
+    data.channel_owners.remove(&channel_id.get());
+
+    let mut released_mutes = Vec::new();
+    if let Some(vc) = &vc {
+        // Anyone still inside gets disconnected by the deletion, so lift their mutes first
+        let guild_id = GuildId::new(vc.guild_id as u64);
+        match mute_service::release_channel_mutes(ctx, data, guild_id, channel_id).await {
+            Ok(records) => released_mutes = records,
+            Err(e) => warn!("Failed to release mutes for channel {}: {:?}", channel_id, e),
+        }
+    }
+
+    // Only the API calls wait for a slot
+    if data.channel_deletions.available_permits() == 0 {
+        debug!("Deletion of channel {} queued behind other deletions", channel_id);
+    }
+    let _permit = data
+        .channel_deletions
+        .acquire()
+        .await
+        .map_err(|e| Error::custom(e.to_string()))?;
 
     // Delete the Discord channel
     match channel_id.delete(ctx).await {
         Ok(_) => {
             info!("Deleted voice channel {}", channel_id);
         }
+        // Already deleted, so there's nothing left but the records
+        Err(serenity::Error::Http(e)) if e.status_code() == Some(StatusCode::NOT_FOUND) => {
+            debug!("Channel {} was already deleted", channel_id);
+        }
         Err(e) => {
-            // Channel might already be deleted
+            let Some(vc) = vc else {
+                warn!("Failed to delete untracked channel {}: {:?}", channel_id, e);
+                return Ok(DeleteOutcome::Failed);
+            };
+            let guild_id = GuildId::new(vc.guild_id as u64);
+            let missing_access = is_missing_access(&e);
+            let e = Error::from(e);
+            data.rate_limits.record_error(&e);
             warn!("Failed to delete channel {}: {:?}", channel_id, e);
+
+            let restored =
+                mute_service::restore_channel_mutes(ctx, data, guild_id, channel_id, &released_mutes)
+                    .await;
+            if let Err(e) = restored {
+                warn!("Failed to restore mutes for channel {}: {:?}", channel_id, e);
+            }
+
+            if missing_access {
+                abandon_room(ctx, data, &vc).await?;
+                return Ok(DeleteOutcome::Forbidden);
+            }
+
+            data.set_channel_owner(channel_id.get(), vc.owner_id as u64);
+            schedule_retry(ctx, data, guild_id, channel_id).await;
+            return Ok(DeleteOutcome::Failed);
         }
     }
 
-    Ok(())
+    data.remove_channel(channel_id.get());
+    data.activity_tracker.cleanup_channel(channel_id.get());
+    voice_channel::delete(&data.pool, channel_id.get() as i64).await?;
+
+    if let Some(vc) = vc {
+        let guild_id = GuildId::new(vc.guild_id as u64);
+        owner_role::revoke(&ctx.http, &data.pool, guild_id, UserId::new(vc.owner_id as u64)).await;
+        // Starts the guild's recreate cooldown for the owner
+        data.room_deletions
+            .insert((vc.guild_id as u64, vc.owner_id as u64), Instant::now());
+
+        delete_discussion_channel(ctx, vc.text_channel_id).await;
+    }
+
+    Ok(DeleteOutcome::Deleted)
+}
+
+/// Delete the discussion text channel paired with a voice room, if there is one
+async fn delete_discussion_channel(ctx: &Context, text_channel_id: Option<i64>) {
+    let Some(text_id) = text_channel_id else {
+        return;
+    };
+    let text_id = ChannelId::new(text_id as u64);
+
+    match text_id.delete(ctx).await {
+        Ok(_) => info!("Deleted discussion channel {}", text_id),
+        Err(e) => warn!("Failed to delete discussion channel {}: {:?}", text_id, e),
+    }
 }
 
 /// Transfer ownership to a new user
-async fn transfer_ownership(
-    _ctx: &Context,
+pub async fn transfer_ownership(
+    ctx: &Context,
     data: &Arc<Data>,
     channel_id: ChannelId,
     new_owner_id: u64,
 ) -> Result<(), Error> {
+    let old_owner_id = data.get_channel_owner(channel_id.get());
+
     // Update database
-    voice_channel::update_owner(&data.pool, channel_id.get() as i64, new_owner_id as i64).await?;
+    let vc = voice_channel::update_owner(&data.pool, channel_id.get() as i64, new_owner_id as i64).await?;
 
     // Update cache
     data.set_channel_owner(channel_id.get(), new_owner_id);
@@ -83,6 +559,47 @@ async fn transfer_ownership(
         channel_id, new_owner_id
     );
 
+    let guild_id = vc.as_ref().map(|vc| GuildId::new(vc.guild_id as u64));
+    if let Some(guild_id) = guild_id {
+        match old_owner_id {
+            Some(old_owner_id) => {
+                owner_role::hand_over(
+                    &ctx.http,
+                    &data.pool,
+                    guild_id,
+                    UserId::new(old_owner_id),
+                    UserId::new(new_owner_id),
+                )
+                .await
+            }
+            None => owner_role::grant(&ctx.http, &data.pool, guild_id, UserId::new(new_owner_id)).await,
+        }
+    }
+
+    if let (Some(vc), Some(old_owner_id)) = (vc, old_owner_id) {
+        if let Err(e) = channel_creator::transfer_discussion_channel(
+            ctx,
+            &vc,
+            UserId::new(old_owner_id),
+            UserId::new(new_owner_id),
+        )
+        .await
+        {
+            warn!("Failed to move discussion channel permissions for {}: {:?}", channel_id, e);
+        }
+    }
+
+    if let Some(guild_id) = guild_id {
+        let entry = LogEntry {
+            action: LogAction::Transfer,
+            actor: None,
+            target: UserId::new(new_owner_id),
+            channel_id: Some(channel_id),
+            reason: old_owner_id.map(|id| format!("Taken over from <@{}>", id)),
+        };
+        mod_log::record(ctx, data, guild_id, entry).await;
+    }
+
     // Could notify the new owner via the text channel
     // For now, just update the permissions
     // This would require editing channel permissions to give new owner manage rights
@@ -91,75 +608,228 @@ async fn transfer_ownership(
 }
 
 /// Get the number of members in a voice channel
-async fn get_channel_member_count(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> usize {
-    // Get guild from cache
-    if let Some(guild) = ctx.cache.guild(guild_id) {
-        return guild
-            .voice_states
-            .values()
-            .filter(|vs| vs.channel_id == Some(channel_id))
-            .count();
-    }
-
-    0
+/// An empty count from the cache is double-checked when the bot saw someone join
+pub async fn get_channel_member_count(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> usize {
+    voice_presence::channel_members(ctx, data, guild_id, channel_id).await.len()
 }
 
 /// Get the next suitable owner from the channel members
-async fn get_next_owner(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Option<u64> {
+/// Only human members are eligible; members we can't verify as human are skipped
+/// `exclude` skips a user who is still cached in the channel but can't take it (e.g. left the guild)
+async fn get_next_owner(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    exclude: Option<UserId>,
+) -> Option<u64> {
+    let mut cached_any = false;
     if let Some(guild) = ctx.cache.guild(guild_id) {
         // Find a member in the channel (not a bot)
         for vs in guild.voice_states.values() {
-            if vs.channel_id == Some(channel_id) {
-                // Check if user is not a bot
-                if let Some(member) = guild.members.get(&vs.user_id) {
-                    if !member.user.bot {
-                        return Some(vs.user_id.get());
-                    }
-                }
+            if vs.channel_id != Some(channel_id) {
+                continue;
+            }
+            cached_any = true;
+            if Some(vs.user_id) == exclude {
+                continue;
+            }
+
+            // Prefer the guild member cache, fall back to the member attached to the voice state
+            let is_bot = guild
+                .members
+                .get(&vs.user_id)
+                .or(vs.member.as_ref())
+                .map(|m| m.user.bot);
+
+            if is_bot == Some(false) {
+                return Some(vs.user_id.get());
             }
         }
     }
 
+    if cached_any {
+        return None;
+    }
+
+    // Members missing from the cache of a very large guild can still take over, once checked
+    for user_id in voice_presence::channel_members(ctx, data, guild_id, channel_id).await {
+        if Some(user_id) != exclude && is_valid_owner(ctx, guild_id, user_id).await == Some(true) {
+            return Some(user_id.get());
+        }
+    }
+
     None
 }
 
+/// Check whether a recorded owner is still a valid human member of the guild
+/// Returns None when this can't be determined (e.g. transient API failure)
+async fn is_valid_owner(ctx: &Context, guild_id: GuildId, owner_id: UserId) -> Option<bool> {
+    match guild_id.member(ctx, owner_id).await {
+        Ok(member) => Some(!member.user.bot),
+        Err(serenity::Error::Http(e)) if e.status_code() == Some(StatusCode::NOT_FOUND) => {
+            Some(false)
+        }
+        Err(e) => {
+            debug!("Could not verify owner {} in guild {}: {:?}", owner_id, guild_id, e);
+            None
+        }
+    }
+}
+
+/// Fix channels whose recorded owner is a bot or has left the guild
+/// Ownership moves to a human member still in the channel, otherwise the channel is deleted
+/// A channel that can't be fixed is logged and skipped so the rest are still checked
+/// Returns (transferred_count, deleted_count)
+pub async fn reconcile_invalid_owners(
+    ctx: &Context,
+    data: &Arc<Data>,
+) -> Result<(usize, usize), Error> {
+    let channels = voice_channel::list_all(&data.pool).await?;
+    let mut orphaned = Vec::new();
+
+    for vc in channels {
+        let guild_id = GuildId::new(vc.guild_id as u64);
+        let owner_id = UserId::new(vc.owner_id as u64);
+
+        if is_valid_owner(ctx, guild_id, owner_id).await == Some(false) {
+            warn!(
+                "Channel {} had invalid owner {} (bot or left guild)",
+                vc.channel_id, owner_id
+            );
+            orphaned.push(vc);
+        }
+    }
+
+    Ok(rehome_rooms(ctx, data, orphaned, None).await)
+}
+
+/// Hand over or delete every room owned by a member who left the guild
+/// Returns (transferred, deleted)
+pub async fn handle_owner_departure(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    owner_id: UserId,
+) -> Result<(usize, usize), Error> {
+    let channels =
+        voice_channel::list_by_owner(&data.pool, guild_id.get() as i64, owner_id.get() as i64)
+            .await?;
+    info!("Owner {} left guild {}, rehoming {} channel(s)", owner_id, guild_id, channels.len());
+
+    for vc in &channels {
+        // Nothing to hand over any more; a pending hold would only release into this same path
+        data.held_rooms.remove(&(vc.channel_id as u64));
+    }
+
+    Ok(rehome_rooms(ctx, data, channels, Some(owner_id)).await)
+}
+
+/// Transfer each room to a human member still inside, or delete it when nobody is left
+/// `exclude` skips the departing owner while they're still cached in the channel
+/// A room that fails is logged and skipped so the rest are still handled
+/// Returns (transferred, deleted)
+async fn rehome_rooms(
+    ctx: &Context,
+    data: &Arc<Data>,
+    rooms: Vec<VoiceChannel>,
+    exclude: Option<UserId>,
+) -> (usize, usize) {
+    let mut transferred = 0;
+    let mut deleted = 0;
+
+    for vc in rooms {
+        let channel_id = ChannelId::new(vc.channel_id as u64);
+        let guild_id = GuildId::new(vc.guild_id as u64);
+
+        match get_next_owner(ctx, data, guild_id, channel_id, exclude).await {
+            Some(new_owner) => {
+                info!("Transferring channel {} to {}", channel_id, new_owner);
+                match transfer_ownership(ctx, data, channel_id, new_owner).await {
+                    Ok(()) => transferred += 1,
+                    Err(e) => warn!("Failed to transfer channel {}: {:?}", channel_id, e),
+                }
+            }
+            None => {
+                info!("Deleting channel {} (no human members left)", channel_id);
+                match delete_channel(ctx, data, channel_id).await {
+                    Ok(DeleteOutcome::Deleted) => deleted += 1,
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to delete channel {}: {:?}", channel_id, e),
+                }
+            }
+        }
+    }
+
+    (transferred, deleted)
+}
+
 /// Check for empty channels and delete them (runs after cache is populated)
 pub async fn cleanup_empty_channels(
     ctx: &Context,
     data: &Arc<Data>,
 ) -> Result<usize, Error> {
     let channels = voice_channel::list_all(&data.pool).await?;
+    let grace = chrono::Duration::seconds(data.settings.empty_sweep_grace_seconds as i64);
     let mut deleted = 0;
 
     for vc in channels {
         let channel_id = ChannelId::new(vc.channel_id as u64);
         let guild_id = GuildId::new(vc.guild_id as u64);
 
+        // Skip brand-new channels - the owner may still be in the middle of being moved in
+        if Utc::now() - vc.created_at < grace {
+            debug!("Skipping empty check for channel {} (created within grace period)", channel_id);
+            continue;
+        }
+
         // Check if channel is empty using cache
-        let member_count = get_channel_member_count(ctx, guild_id, channel_id).await;
+        let member_count = get_channel_member_count(ctx, data, guild_id, channel_id).await;
 
         if member_count == 0 {
+            if let Some(owner_id) = streaming_owner(ctx, data, guild_id, channel_id).await? {
+                debug!("Skipping empty channel {}, owner {} is streaming", channel_id, owner_id);
+                continue;
+            }
+
+            // People dropped by a restart get the guild's grace to reconnect, like any other exit
+            if schedule_empty_deletion(ctx, data, guild_id, channel_id).await? {
+                continue;
+            }
+
             info!("Deleting empty channel {} on startup check", channel_id);
-            delete_channel(ctx, data, channel_id).await?;
-            deleted += 1;
+            if delete_channel(ctx, data, channel_id).await? == DeleteOutcome::Deleted {
+                deleted += 1;
+            }
         }
     }
 
     Ok(deleted)
 }
 
+/// Channels whose bans are re-checked at once during the startup restore
+const BAN_RESTORE_CONCURRENCY: usize = 4;
+
 /// Restore channels to cache on bot startup
 /// Only removes DB entries for channels that no longer exist in Discord
 /// Does NOT delete any Discord channels - let normal handlers manage that
+/// Restored channels get any lost ban and lock overwrites re-applied
 /// Returns (cleaned_count, restored_count)
 pub async fn cleanup_orphaned_channels(
-    http: &Http,
+    http: &Arc<Http>,
     pool: &PgPool,
     data: &Arc<Data>,
 ) -> Result<(usize, usize), Error> {
     let channels = voice_channel::list_all(pool).await?;
     let mut cleaned = 0;
     let mut restored = 0;
+    // Lost ban and lock overwrites are re-applied in the background, a few channels at a time
+    let mut ban_checks = JoinSet::new();
 
     info!("Restoring {} voice channels from database...", channels.len());
 
@@ -170,15 +840,34 @@ pub async fn cleanup_orphaned_channels(
         match http.get_channel(channel_id).await {
             Ok(channel) => {
                 // Channel exists in Discord
-                if channel.guild().is_some() {
+                if let Some(channel) = channel.guild() {
                     // Restore to cache - normal voice state handlers will manage cleanup
                     // Empty channels will be cleaned up when users join/leave triggers voice state updates
                     // Note: We can't easily check member count via HTTP API without cache on startup,
                     // so we restore all existing channels. The voice state handler will delete empty
                     // channels when the owner leaves or when the channel becomes empty.
                     data.set_channel_owner(vc.channel_id as u64, vc.owner_id as u64);
+                    if vc.raid_locked {
+                        data.raid_locked.insert(vc.channel_id as u64, Instant::now());
+                    }
                     restored += 1;
                     debug!("Restored channel {} to cache (owner: {})", channel_id, vc.owner_id);
+
+                    if ban_checks.len() >= BAN_RESTORE_CONCURRENCY {
+                        ban_checks.join_next().await;
+                    }
+                    let (http, pool) = (Arc::clone(http), pool.clone());
+                    ban_checks.spawn(async move {
+                        let result = ban_service::reapply_channel_bans(&http, &pool, &channel).await;
+                        if let Err(e) = result {
+                            warn!("Failed to check bans on channel {}: {:?}", channel.id, e);
+                        }
+                        if vc.locked || vc.raid_locked {
+                            if let Err(e) = channel_lock::reapply_lock(&http, &channel).await {
+                                warn!("Failed to re-lock channel {}: {:?}", channel.id, e);
+                            }
+                        }
+                    });
                 } else {
                     // Not a guild channel (shouldn't happen) - clean DB
                     voice_channel::delete(pool, vc.channel_id).await?;
@@ -188,11 +877,141 @@ pub async fn cleanup_orphaned_channels(
             Err(_) => {
                 // Channel doesn't exist in Discord anymore - clean DB entry
                 voice_channel::delete(pool, vc.channel_id).await?;
+                owner_role::revoke(
+                    http,
+                    pool,
+                    GuildId::new(vc.guild_id as u64),
+                    UserId::new(vc.owner_id as u64),
+                )
+                .await;
                 info!("Removed non-existent channel {} from database", channel_id);
                 cleaned += 1;
             }
         }
     }
 
+    while ban_checks.join_next().await.is_some() {}
+
     Ok((cleaned, restored))
 }
+
+/// Remove all records tied to a channel that no longer exists in Discord
+/// Returns true if the channel was being tracked
+pub async fn purge_channel_records(
+    ctx: &Context,
+    data: &Arc<Data>,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let id = channel_id.get() as i64;
+
+    // The voice room is gone, so its discussion channel goes too
+    let vc = voice_channel::get(&data.pool, id).await?;
+    let text_channel_id = vc.as_ref().and_then(|vc| vc.text_channel_id);
+
+    let removed = voice_channel::delete(&data.pool, id).await?;
+    user_vc_preference::remove_deadline(&data.pool, id).await?;
+    let mutes = mute::unmute_all_for_channel(&data.pool, id).await?;
+    data.remove_channel(channel_id.get());
+    if let Some(vc) = vc {
+        owner_role::revoke(
+            &ctx.http,
+            &data.pool,
+            GuildId::new(vc.guild_id as u64),
+            UserId::new(vc.owner_id as u64),
+        )
+        .await;
+    }
+    data.activity_tracker.cleanup_channel(channel_id.get());
+    delete_discussion_channel(ctx, text_channel_id).await;
+
+    if removed || mutes > 0 {
+        debug!(
+            "Purged records for vanished channel {} ({} active mutes closed)",
+            channel_id, mutes
+        );
+    }
+
+    Ok(removed)
+}
+
+/// Start the background task that removes DB rows for channels deleted outside the bot
+/// A running counterpart to `cleanup_orphaned_channels`, checking a bounded batch per tick
+pub fn spawn_orphan_reaper(ctx: Context, data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(ORPHAN_REAP_INTERVAL_SECONDS));
+        // The startup restore already covers the first pass
+        ticker.tick().await;
+        let mut cursor = 0;
+
+        loop {
+            ticker.tick().await;
+
+            if data.rate_limits.is_throttled() {
+                debug!("Rate limited, deferring vanished channel sweep");
+                continue;
+            }
+
+            match reap_vanished_channels(&ctx, &data, cursor, ORPHAN_REAP_BATCH_SIZE).await {
+                Ok((next_cursor, removed)) => {
+                    cursor = next_cursor;
+                    if removed > 0 {
+                        info!("Self-heal removed {} vanished channels from database", removed);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to reap vanished channels: {:?}", e);
+                }
+            }
+        }
+    })
+    .abort_handle()
+}
+
+/// Check up to `limit` tracked channels starting at `cursor` against the cache
+/// Channels in guilds that aren't cached (e.g. unavailable) are left alone
+/// Returns (next_cursor, removed_count)
+async fn reap_vanished_channels(
+    ctx: &Context,
+    data: &Arc<Data>,
+    cursor: usize,
+    limit: usize,
+) -> Result<(usize, usize), Error> {
+    let channels = voice_channel::list_all(&data.pool).await?;
+    let grace = chrono::Duration::seconds(data.settings.empty_sweep_grace_seconds as i64);
+    let start = if cursor >= channels.len() { 0 } else { cursor };
+    let mut removed = 0;
+
+    for vc in channels.iter().skip(start).take(limit) {
+        let channel_id = ChannelId::new(vc.channel_id as u64);
+        let guild_id = GuildId::new(vc.guild_id as u64);
+
+        // Brand-new channels may not have reached the cache yet
+        if Utc::now() - vc.created_at < grace {
+            continue;
+        }
+
+        let exists = match ctx.cache.guild(guild_id) {
+            Some(guild) => guild.channels.contains_key(&channel_id),
+            None => continue,
+        };
+
+        if exists {
+            continue;
+        }
+
+        // One bad row shouldn't stop the rest of the batch
+        match purge_channel_records(ctx, data, channel_id).await {
+            Ok(true) => {
+                info!("Removed vanished channel {} from database", channel_id);
+                removed += 1;
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Failed to remove vanished channel {}: {:?}", channel_id, e),
+        }
+    }
+
+    // Rows removed this tick shift later rows down, so step back by that many
+    let next_cursor = (start + limit).saturating_sub(removed);
+
+    Ok((next_cursor, removed))
+}
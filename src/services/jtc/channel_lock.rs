@@ -0,0 +1,171 @@
+//! Owner locks that keep new people out of a room
+//! A lock denies CONNECT to @everyone, so people already inside stay until they leave. The state
+//! is stored on the channel row, along with any automatic raid lock, and re-applied on startup in
+//! case the overwrite was lost.
+
+use std::sync::Arc;
+
+use serenity::all::{
+    ChannelId, Context, GuildChannel, GuildId, Http, PermissionOverwrite, PermissionOverwriteType,
+    Permissions, RoleId,
+};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::db::queries::voice_channel;
+
+/// Lock a room to new joins, returning false if it was already locked
+pub async fn lock_channel(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let id = channel_id.get() as i64;
+    if voice_channel::get(&data.pool, id).await?.is_some_and(|vc| vc.locked) {
+        return Ok(false);
+    }
+
+    set_connect_denied(ctx, guild_id, channel_id, true).await?;
+    voice_channel::set_locked(&data.pool, id, true).await?;
+
+    Ok(true)
+}
+
+/// Open a room to new joins again, returning false if it wasn't locked
+/// Also lifts an automatic raid lock, since the owner has decided the room should be open.
+pub async fn unlock_channel(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let id = channel_id.get() as i64;
+    let vc = voice_channel::get(&data.pool, id).await?;
+    let owner_locked = vc.as_ref().is_some_and(|vc| vc.locked);
+    // The raid lock is stored too, so it's still seen after a restart
+    let remembered = data.raid_locked.remove(&channel_id.get()).is_some();
+    let raid_locked = remembered || vc.is_some_and(|vc| vc.raid_locked);
+    if !owner_locked && !raid_locked {
+        return Ok(false);
+    }
+
+    set_connect_denied(ctx, guild_id, channel_id, false).await?;
+    if owner_locked {
+        voice_channel::set_locked(&data.pool, id, false).await?;
+    }
+    if raid_locked {
+        voice_channel::set_raid_locked(&data.pool, id, false).await?;
+        // Start counting afresh so the joins that caused the raid lock don't re-trigger it
+        data.activity_tracker.reset_channel_joins(channel_id.get());
+    }
+
+    Ok(true)
+}
+
+/// Deny or restore CONNECT for @everyone, keeping the rest of its overwrite intact
+pub async fn set_connect_denied(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    denied: bool,
+) -> Result<(), Error> {
+    let current = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| {
+            guild
+                .channels
+                .get(&channel_id)
+                .map(|channel| channel.permission_overwrites.clone())
+        })
+        .unwrap_or_default();
+
+    write_overwrite(&ctx.http, channel_id, everyone_overwrite(guild_id, &current, denied)).await
+}
+
+/// Re-apply the lock on a restored channel if its overwrite went missing
+/// Returns true if the channel needed fixing
+pub async fn reapply_lock(http: &Http, channel: &GuildChannel) -> Result<bool, Error> {
+    let overwrite = everyone_overwrite(channel.guild_id, &channel.permission_overwrites, true);
+    let already_locked = channel
+        .permission_overwrites
+        .iter()
+        .any(|o| o.kind == overwrite.kind && o.deny.contains(Permissions::CONNECT));
+    if already_locked {
+        return Ok(false);
+    }
+
+    write_overwrite(http, channel.id, overwrite).await?;
+
+    Ok(true)
+}
+
+async fn write_overwrite(
+    http: &Http,
+    channel_id: ChannelId,
+    overwrite: PermissionOverwrite,
+) -> Result<(), Error> {
+    if overwrite.allow.is_empty() && overwrite.deny.is_empty() {
+        channel_id.delete_permission(http, overwrite.kind).await?;
+    } else {
+        channel_id.create_permission(http, overwrite).await?;
+    }
+
+    Ok(())
+}
+
+/// The @everyone overwrite from `current` with CONNECT denied or no longer denied
+fn everyone_overwrite(
+    guild_id: GuildId,
+    current: &[PermissionOverwrite],
+    denied: bool,
+) -> PermissionOverwrite {
+    // @everyone's role ID is the guild ID
+    let everyone = PermissionOverwriteType::Role(RoleId::new(guild_id.get()));
+    let mut overwrite = current
+        .iter()
+        .find(|o| o.kind == everyone)
+        .cloned()
+        .unwrap_or(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::empty(),
+            kind: everyone,
+        });
+
+    if denied {
+        overwrite.allow.remove(Permissions::CONNECT);
+        overwrite.deny.insert(Permissions::CONNECT);
+    } else {
+        overwrite.deny.remove(Permissions::CONNECT);
+    }
+
+    overwrite
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_keeps_other_everyone_permissions() {
+        let guild_id = GuildId::new(1);
+        let everyone = PermissionOverwriteType::Role(RoleId::new(1));
+        let current = vec![PermissionOverwrite {
+            allow: Permissions::CONNECT | Permissions::SPEAK,
+            deny: Permissions::STREAM,
+            kind: everyone,
+        }];
+
+        let locked = everyone_overwrite(guild_id, &current, true);
+        assert_eq!(locked.allow, Permissions::SPEAK);
+        assert_eq!(locked.deny, Permissions::STREAM | Permissions::CONNECT);
+
+        let unlocked = everyone_overwrite(guild_id, &[locked], false);
+        assert_eq!(unlocked.allow, Permissions::SPEAK);
+        assert_eq!(unlocked.deny, Permissions::STREAM);
+
+        let fresh = everyone_overwrite(guild_id, &[], false);
+        assert!(fresh.allow.is_empty() && fresh.deny.is_empty());
+    }
+}
@@ -1,40 +1,67 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use serenity::all::{ChannelId, CreateMessage, Http, UserId};
-use tokio::time::interval;
-use tracing::{debug, error, info, warn};
+use serenity::all::{ChannelId, Context, CreateMessage, Http, UserId};
+use tokio::task::AbortHandle;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, error, info};
 
 use crate::bot::data::Data;
 use crate::constants::embeds;
-use crate::db::queries::{user_vc_preference, voice_channel};
+use crate::constants::timeouts::VC_NAMING_DEADLINE_SECONDS;
+use crate::db::models::MessageTemplate;
+use crate::db::queries::{guild_config, user_vc_preference, voice_channel};
+use crate::services::jtc::channel_deleter::{self, DeleteOutcome};
+use crate::utils::formatting::fill_template;
 
 /// Interval for checking expired deadlines (in seconds)
 const CHECK_INTERVAL_SECONDS: u64 = 10;
 
+/// Max channels deleted per tick - the rest stay queued for the next tick
+const MAX_DELETIONS_PER_TICK: i64 = 5;
+
+/// Spacing between deletions within a tick, plus up to DELETE_JITTER_MS of random jitter
+const DELETE_SPACING_MS: u64 = 500;
+const DELETE_JITTER_MS: u64 = 500;
+
 /// Start the deadline checker background task
-pub fn spawn_deadline_checker(http: Arc<Http>, data: Arc<Data>) {
+pub fn spawn_deadline_checker(ctx: Context, data: Arc<Data>) -> AbortHandle {
     tokio::spawn(async move {
         let mut ticker = interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        // A slow tick shouldn't be followed by a burst of catch-up ticks
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         loop {
             ticker.tick().await;
 
-            if let Err(e) = check_expired_deadlines(&http, &data).await {
+            // Expired rooms can wait a few extra seconds while Discord is rate limiting us
+            if data.rate_limits.is_throttled() {
+                debug!("Rate limited, deferring deadline check");
+                continue;
+            }
+
+            if let Err(e) = check_expired_deadlines(&ctx, &data).await {
                 error!("Error checking expired deadlines: {:?}", e);
             }
         }
-    });
+    })
+    .abort_handle()
 }
 
 /// Check for expired deadlines and delete unconfigured channels
 async fn check_expired_deadlines(
-    http: &Http,
+    ctx: &Context,
     data: &Arc<Data>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let expired = user_vc_preference::get_expired_deadlines(&data.pool).await?;
+    let expired =
+        user_vc_preference::get_expired_deadlines(&data.pool, MAX_DELETIONS_PER_TICK).await?;
+
+    for (i, deadline) in expired.into_iter().enumerate() {
+        // Spread deletions out so a burst of expiries doesn't hammer the API
+        if i > 0 {
+            tokio::time::sleep(deletion_delay()).await;
+        }
 
-    for deadline in expired {
         let channel_id = ChannelId::new(deadline.channel_id as u64);
 
         debug!(
@@ -45,29 +72,20 @@ async fn check_expired_deadlines(
         // Check if channel still exists and is still unconfigured
         // (user might have already configured it or left)
         if let Some(vc) = voice_channel::get(&data.pool, deadline.channel_id).await? {
-            // Check if it still has the default name (unconfigured)
-            if vc.topic.is_none() {
-                let owner_id = UserId::new(deadline.owner_id as u64);
-
-                // Delete the channel
-                match channel_id.delete(http).await {
-                    Ok(_) => {
-                        info!(
-                            "Deleted unconfigured channel {} (deadline expired)",
-                            channel_id
-                        );
-
-                        // Try to DM the user explaining why
-                        notify_user_channel_deleted(http, owner_id).await;
-                    }
-                    Err(e) => {
-                        warn!("Failed to delete channel {}: {:?}", channel_id, e);
-                    }
+            let require_tags = data
+                .guild_config(deadline.guild_id as u64)
+                .await?
+                .is_some_and(|config| config.require_tags);
+            if is_unconfigured(vc.topic.as_deref(), &vc.tags, require_tags) {
+                // A refused deletion is retried by the deleter, so the deadline is done either way
+                let outcome = channel_deleter::delete_channel(ctx, data, channel_id).await?;
+                if outcome == DeleteOutcome::Deleted {
+                    info!("Deleted unconfigured channel {} (deadline expired)", channel_id);
+
+                    // Try to DM the user explaining why
+                    let owner_id = UserId::new(deadline.owner_id as u64);
+                    notify_user_channel_deleted(&ctx.http, data, deadline.guild_id, owner_id).await;
                 }
-
-                // Clean up database
-                voice_channel::delete(&data.pool, deadline.channel_id).await?;
-                data.remove_channel(deadline.channel_id as u64);
             }
         }
 
@@ -78,16 +96,38 @@ async fn check_expired_deadlines(
     Ok(())
 }
 
+/// Whether a room still isn't set up when its deadline runs out: it has no name, or it has no
+/// tags in a guild that requires them
+pub fn is_unconfigured(topic: Option<&str>, tags: &[String], require_tags: bool) -> bool {
+    topic.is_none() || (require_tags && tags.is_empty())
+}
+
+/// Delay before the next deletion: fixed spacing plus jitter taken from the clock
+fn deletion_delay() -> Duration {
+    let nanos = chrono::Utc::now().timestamp_subsec_nanos() as u64;
+    Duration::from_millis(DELETE_SPACING_MS + jitter_ms(nanos))
+}
+
+/// Jitter in 0..=DELETE_JITTER_MS from a time-based seed
+fn jitter_ms(seed: u64) -> u64 {
+    seed % (DELETE_JITTER_MS + 1)
+}
+
 /// Notify user via DM that their channel was deleted due to timeout
-async fn notify_user_channel_deleted(http: &Http, user_id: UserId) {
+async fn notify_user_channel_deleted(http: &Http, data: &Arc<Data>, guild_id: i64, user_id: UserId) {
+    let template = guild_config::get_message_template(&data.pool, guild_id, MessageTemplate::DeadlineDm)
+        .await
+        .unwrap_or_else(|_| MessageTemplate::DeadlineDm.default_text().to_string());
+
     let embed = embeds::info_embed()
         .title("Voice Channel Deleted")
-        .description(
-            "Your voice channel was automatically deleted because it wasn't configured in time.\n\n\
-            When you create a new channel, you have 60 seconds to set a name. \
-            If you don't configure it, the channel is removed to keep things tidy.\n\n\
-            Simply join the channel again to create a new one."
-        );
+        .description(fill_template(
+            &template,
+            &[
+                ("user", format!("<@{}>", user_id)),
+                ("seconds", VC_NAMING_DEADLINE_SECONDS.to_string()),
+            ],
+        ));
 
     let message = CreateMessage::new().embed(embed);
 
@@ -103,3 +143,26 @@ async fn notify_user_channel_deleted(http: &Http, user_id: UserId) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_rooms_need_tags_only_when_required() {
+        let tags = vec!["gaming".to_string()];
+
+        assert!(is_unconfigured(None, &tags, false));
+        assert!(!is_unconfigured(Some("Chill"), &[], false));
+        // Named but untagged in a guild that requires tags
+        assert!(is_unconfigured(Some("Chill"), &[], true));
+        assert!(!is_unconfigured(Some("Chill"), &tags, true));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        for seed in [0, 1, DELETE_JITTER_MS, DELETE_JITTER_MS + 1, 999_999_999] {
+            assert!(jitter_ms(seed) <= DELETE_JITTER_MS);
+        }
+    }
+}
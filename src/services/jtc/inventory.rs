@@ -0,0 +1,93 @@
+//! Snapshot of every managed room for operators and external dashboards
+//! IDs are serialized as strings since they don't fit in a JavaScript number.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serenity::all::{ChannelId, Context, GuildId};
+
+use crate::bot::data::Data;
+use crate::db::models::VoiceChannel;
+use crate::services::jtc::channel_deleter::get_channel_member_count;
+
+/// One managed room and its live state
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomInventoryEntry {
+    pub channel_id: String,
+    pub guild_id: String,
+    pub owner_id: String,
+    pub channel_type: &'static str,
+    pub name: String,
+    pub tags: Vec<String>,
+    /// People in the room right now, from the cache
+    pub members: usize,
+    /// Join limit, or None for no limit (or an uncached channel)
+    pub user_limit: Option<u32>,
+    /// Locked by its owner
+    pub locked: bool,
+    /// Locked automatically after a burst of joins
+    pub raid_locked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Combine stored rooms with their live state from the cache
+pub async fn collect(
+    ctx: &Context,
+    data: &Data,
+    channels: &[VoiceChannel],
+) -> Vec<RoomInventoryEntry> {
+    let mut entries = Vec::with_capacity(channels.len());
+    for vc in channels {
+        let guild_id = GuildId::new(vc.guild_id as u64);
+        let channel_id = ChannelId::new(vc.channel_id as u64);
+        let user_limit = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|guild| guild.channels.get(&channel_id).and_then(|c| c.user_limit))
+            .filter(|&limit| limit > 0);
+
+        entries.push(RoomInventoryEntry {
+            channel_id: vc.channel_id.to_string(),
+            guild_id: vc.guild_id.to_string(),
+            owner_id: vc.owner_id.to_string(),
+            channel_type: vc.channel_type.as_str(),
+            name: vc.display_name(),
+            tags: vc.tags.clone(),
+            members: get_channel_member_count(ctx, data, guild_id, channel_id).await,
+            user_limit,
+            locked: vc.locked,
+            raid_locked: data.raid_locked.contains_key(&channel_id.get()),
+            created_at: vc.created_at,
+        });
+    }
+    entries
+}
+
+/// The inventory as pretty-printed JSON
+pub fn to_json(entries: &[RoomInventoryEntry]) -> String {
+    serde_json::to_string_pretty(entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_serialize_as_strings() {
+        let entry = RoomInventoryEntry {
+            channel_id: u64::MAX.to_string(),
+            guild_id: "1".to_string(),
+            owner_id: "2".to_string(),
+            channel_type: "casual",
+            name: "Casual VC".to_string(),
+            tags: vec![],
+            members: 3,
+            user_limit: None,
+            locked: false,
+            raid_locked: false,
+            created_at: Utc::now(),
+        };
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[entry])).unwrap();
+        assert_eq!(json[0]["channel_id"], "18446744073709551615");
+        assert_eq!(json[0]["user_limit"], serde_json::Value::Null);
+    }
+}
@@ -1,5 +1,12 @@
 pub mod channel_creator;
 pub mod channel_deleter;
+pub mod channel_lock;
 pub mod deadline_tracker;
+pub mod inventory;
+pub mod owner_role;
 pub mod queue;
+pub mod room_announcement;
+pub mod transfer_guard;
+pub mod voice_presence;
+pub mod welcome_dm;
 pub mod welcome_embed;
@@ -0,0 +1,73 @@
+//! Optional per-guild role marking members who currently own a managed room
+//! Other bots and integrations can key off the role instead of channel overwrites.
+
+use serenity::all::{GuildId, Http, RoleId, UserId};
+use sqlx::PgPool;
+use tracing::{debug, warn};
+
+use crate::db::queries::{guild_config, voice_channel};
+
+/// The guild's owner role, if one is configured
+async fn configured_role(pool: &PgPool, guild_id: GuildId) -> Option<RoleId> {
+    match guild_config::get(pool, guild_id.get() as i64).await {
+        Ok(config) => config
+            .and_then(|c| c.owner_role_id)
+            .map(|id| RoleId::new(id as u64)),
+        Err(e) => {
+            warn!("Could not load owner role for guild {}: {:?}", guild_id, e);
+            None
+        }
+    }
+}
+
+/// Give `user_id` the owner role after they gain a room
+pub async fn grant(http: &Http, pool: &PgPool, guild_id: GuildId, user_id: UserId) {
+    let Some(role_id) = configured_role(pool, guild_id).await else {
+        return;
+    };
+
+    match http
+        .add_member_role(guild_id, user_id, role_id, Some("Owns a voice channel"))
+        .await
+    {
+        Ok(()) => debug!("Gave owner role {} to {} in {}", role_id, user_id, guild_id),
+        Err(e) => warn!("Failed to give owner role {} to {}: {:?}", role_id, user_id, e),
+    }
+}
+
+/// Take the owner role from `user_id` after they lose a room
+/// Call once the ownership change is stored; the role stays while they still own another room
+pub async fn revoke(http: &Http, pool: &PgPool, guild_id: GuildId, user_id: UserId) {
+    let Some(role_id) = configured_role(pool, guild_id).await else {
+        return;
+    };
+
+    match voice_channel::list_by_owner(pool, guild_id.get() as i64, user_id.get() as i64).await {
+        Ok(rooms) if !rooms.is_empty() => return,
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Could not check remaining rooms for {}: {:?}", user_id, e);
+            return;
+        }
+    }
+
+    match http
+        .remove_member_role(guild_id, user_id, role_id, Some("No longer owns a voice channel"))
+        .await
+    {
+        Ok(()) => debug!("Took owner role {} from {} in {}", role_id, user_id, guild_id),
+        Err(e) => debug!("Could not take owner role {} from {}: {:?}", role_id, user_id, e),
+    }
+}
+
+/// Move the owner role along with a room
+pub async fn hand_over(
+    http: &Http,
+    pool: &PgPool,
+    guild_id: GuildId,
+    old_owner: UserId,
+    new_owner: UserId,
+) {
+    revoke(http, pool, guild_id, old_owner).await;
+    grant(http, pool, guild_id, new_owner).await;
+}
@@ -1,12 +1,26 @@
-use std::sync::Arc;
-use std::time::Duration;
+//! The single path for creating JTC rooms
+//! Live joins (`handle_channel_join`) and the startup scan both go through `queue_join`, and one
+//! processor creates rooms in order with `CREATION_SPACING` between them. A lone join on an idle
+//! queue is picked up immediately, so the spacing only adds latency during a burst. A user already
+//! waiting in the queue is not queued twice, so a join seen by both the scan and the live handler
+//! creates one room.
 
-use serenity::all::{ChannelId, Context, GuildId, UserId};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::{ChannelId, Context, CreateMessage, GuildId, UserId};
 use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::timeouts::{
+    JTC_QUEUE_DEPTH_WARN, JTC_QUEUE_HEALTH_LOG_SECONDS, JTC_QUEUE_NOTICE_SECONDS,
+};
 use crate::services::jtc::channel_creator;
 
 /// Queue entry for pending JTC channel creation
@@ -16,14 +30,202 @@ pub struct JtcQueueEntry {
     user_id: UserId,
     jtc_channel_id: ChannelId,
     is_casual: bool,
+    queued_at: Instant,
 }
 
-/// Check all JTC channels for users and add them to the queue
-pub async fn check_jtc_channels_on_startup(
+/// Handle for sending entries to the processor, with the users currently waiting
+#[derive(Debug, Default)]
+pub struct JtcQueue {
+    /// Set once the processor has started
+    sender: OnceLock<mpsc::UnboundedSender<JtcQueueEntry>>,
+    /// Users with an entry waiting: (guild_id, user_id) -> queued at
+    queued: DashMap<(u64, u64), Instant>,
+}
+
+impl JtcQueue {
+    /// Connect the queue to its processor; only the first call has an effect
+    pub fn attach(&self, sender: mpsc::UnboundedSender<JtcQueueEntry>) -> bool {
+        self.sender.set(sender).is_ok()
+    }
+
+    fn finish(&self, guild_id: GuildId, user_id: UserId) {
+        self.queued.remove(&(guild_id.get(), user_id.get()));
+    }
+}
+
+/// Queue depth and processing latency, updated lock-free from the producer and the processor
+#[derive(Debug, Default)]
+pub struct QueueStats {
+    depth: AtomicUsize,
+    processed: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+}
+
+/// Point-in-time view of `QueueStats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueHealth {
+    pub depth: usize,
+    pub processed: u64,
+    pub avg_latency_ms: u64,
+    pub max_latency_ms: u64,
+}
+
+impl QueueStats {
+    /// Count an entry going into the queue, returning the new depth
+    pub fn record_enqueued(&self) -> usize {
+        self.depth.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Count an entry taken off the queue
+    pub fn record_dequeued(&self) {
+        // Never wrap below zero if the counts ever get out of step
+        let _ = self
+            .depth
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| Some(d.saturating_sub(1)));
+    }
+
+    /// Record how long an entry took from enqueue to finished processing
+    pub fn record_processed(&self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.total_latency_ms.fetch_add(ms, Ordering::Relaxed);
+        self.max_latency_ms.fetch_max(ms, Ordering::Relaxed);
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> QueueHealth {
+        let processed = self.processed.load(Ordering::Relaxed);
+        let total = self.total_latency_ms.load(Ordering::Relaxed);
+        QueueHealth {
+            depth: self.depth(),
+            processed,
+            avg_latency_ms: total.checked_div(processed).unwrap_or(0),
+            max_latency_ms: self.max_latency_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Wait between channel creations to avoid Discord rate limits
+const CREATION_SPACING: Duration = Duration::from_secs(1);
+
+/// Spacing multiplier while the bot is being rate limited
+const THROTTLED_SPACING_FACTOR: u32 = 3;
+
+/// Wait between channel creations, stretched while Discord is rate limiting us
+fn creation_spacing(throttled: bool) -> Duration {
+    if throttled {
+        CREATION_SPACING * THROTTLED_SPACING_FACTOR
+    } else {
+        CREATION_SPACING
+    }
+}
+
+/// Roughly how long an entry at `depth` waits before its channel is created
+fn estimated_wait(depth: usize, throttled: bool) -> Duration {
+    creation_spacing(throttled) * depth.saturating_sub(1) as u32
+}
+
+/// Queue a room for a user who joined a JTC channel
+/// Returns false if they were already waiting or the processor isn't running yet
+/// (users who joined before startup finished are picked up by the startup scan)
+pub fn queue_join(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    jtc_channel_id: ChannelId,
+    is_casual: bool,
+) -> bool {
+    let Some(sender) = data.jtc_queue.sender.get() else {
+        debug!("JTC queue not started yet, leaving user {} for the startup scan", user_id);
+        return false;
+    };
+
+    let key = (guild_id.get(), user_id.get());
+    if data.jtc_queue.queued.insert(key, Instant::now()).is_some() {
+        debug!("User {} is already queued for a channel in guild {}", user_id, guild_id);
+        return false;
+    }
+
+    let entry = JtcQueueEntry {
+        guild_id,
+        user_id,
+        jtc_channel_id,
+        is_casual,
+        queued_at: Instant::now(),
+    };
+
+    if !enqueue(ctx, data, sender, entry) {
+        data.jtc_queue.queued.remove(&key);
+        return false;
+    }
+
+    true
+}
+
+/// Send an entry to the queue, tracking depth and warning when it backs up
+/// Users facing a noticeable wait get a DM so they aren't left sitting in silence
+fn enqueue(
     ctx: &Context,
-    data: &Arc<Data>,
+    data: &Data,
     queue_tx: &mpsc::UnboundedSender<JtcQueueEntry>,
-) -> Result<usize, Error> {
+    entry: JtcQueueEntry,
+) -> bool {
+    let depth = data.jtc_queue_stats.record_enqueued();
+    let user_id = entry.user_id;
+
+    if queue_tx.send(entry).is_err() {
+        data.jtc_queue_stats.record_dequeued();
+        return false;
+    }
+
+    if depth > JTC_QUEUE_DEPTH_WARN {
+        warn!(
+            "JTC queue is backing up: {} entries waiting (warn threshold {})",
+            depth, JTC_QUEUE_DEPTH_WARN
+        );
+    }
+
+    let wait = estimated_wait(depth, data.rate_limits.is_throttled());
+    if wait.as_secs() >= JTC_QUEUE_NOTICE_SECONDS {
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            send_wait_notice(&ctx, user_id, depth, wait).await;
+        });
+    }
+
+    true
+}
+
+/// DM a queued user that their channel is on the way
+async fn send_wait_notice(ctx: &Context, user_id: UserId, position: usize, wait: Duration) {
+    let embed = embeds::info_embed()
+        .title("Creating Your Channel")
+        .description(format!(
+            "Hang tight - you're #{} in line and your channel should be ready in about {} seconds. \
+            You'll be moved into it automatically; leaving the join channel cancels the request.",
+            position,
+            wait.as_secs()
+        ));
+
+    match user_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(ctx, CreateMessage::new().embed(embed)).await {
+                debug!("Could not DM queued user {}: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for queued user {}: {:?}", user_id, e);
+        }
+    }
+}
+
+/// Check all JTC channels for users and add them to the queue
+pub async fn check_jtc_channels_on_startup(ctx: &Context, data: &Arc<Data>) -> Result<usize, Error> {
     // Get all guild configs
     let configs = sqlx::query_as::<_, crate::db::models::GuildConfig>(
         "SELECT * FROM guild_configs WHERE jtc_casual_channel_id IS NOT NULL OR jtc_debate_channel_id IS NOT NULL"
@@ -36,40 +238,16 @@ pub async fn check_jtc_channels_on_startup(
     for config in configs {
         let guild_id = GuildId::new(config.guild_id as u64);
 
-        // Check casual JTC channel
-        if let Some(jtc_channel_id) = config.jtc_casual_channel_id {
+        for (jtc_channel_id, is_casual) in [
+            (config.jtc_casual_channel_id, true),
+            (config.jtc_debate_channel_id, false),
+        ] {
+            let Some(jtc_channel_id) = jtc_channel_id else {
+                continue;
+            };
             let channel_id = ChannelId::new(jtc_channel_id as u64);
             let users = get_users_in_channel(ctx, guild_id, channel_id).await?;
-            
-            for user_id in users {
-                // Check if user is a bot
-                if let Some(guild) = ctx.cache.guild(guild_id) {
-                    if let Some(member) = guild.members.get(&user_id) {
-                        if member.user.bot {
-                            continue;
-                        }
-                    }
-                }
 
-                let entry = JtcQueueEntry {
-                    guild_id,
-                    user_id,
-                    jtc_channel_id: channel_id,
-                    is_casual: true,
-                };
-                
-                if queue_tx.send(entry).is_ok() {
-                    queued += 1;
-                    info!("Queued JTC channel creation for user {} in casual channel {}", user_id, channel_id);
-                }
-            }
-        }
-
-        // Check debate JTC channel
-        if let Some(jtc_channel_id) = config.jtc_debate_channel_id {
-            let channel_id = ChannelId::new(jtc_channel_id as u64);
-            let users = get_users_in_channel(ctx, guild_id, channel_id).await?;
-            
             for user_id in users {
                 // Check if user is a bot
                 if let Some(guild) = ctx.cache.guild(guild_id) {
@@ -80,16 +258,14 @@ pub async fn check_jtc_channels_on_startup(
                     }
                 }
 
-                let entry = JtcQueueEntry {
-                    guild_id,
-                    user_id,
-                    jtc_channel_id: channel_id,
-                    is_casual: false,
-                };
-                
-                if queue_tx.send(entry).is_ok() {
+                if queue_join(ctx, data, guild_id, user_id, channel_id, is_casual) {
                     queued += 1;
-                    info!("Queued JTC channel creation for user {} in debate channel {}", user_id, channel_id);
+                    info!(
+                        "Queued JTC channel creation for user {} in {} channel {}",
+                        user_id,
+                        if is_casual { "casual" } else { "debate" },
+                        channel_id
+                    );
                 }
             }
         }
@@ -126,10 +302,10 @@ pub async fn process_jtc_queue_with_context(
 ) {
     info!("Started JTC queue processor");
 
-    // Rate limit: wait 1 second between channel creations to avoid Discord rate limits
-    const RATE_LIMIT_DELAY: Duration = Duration::from_secs(1);
-
     while let Some(entry) = queue_rx.recv().await {
+        data.jtc_queue_stats.record_dequeued();
+        data.jtc_queue.finish(entry.guild_id, entry.user_id);
+
         info!(
             "Processing JTC queue entry: user={}, guild={}, channel={}, casual={}",
             entry.user_id, entry.guild_id, entry.jtc_channel_id, entry.is_casual
@@ -153,6 +329,7 @@ pub async fn process_jtc_queue_with_context(
                 "User {} is no longer in JTC channel {}, skipping",
                 entry.user_id, entry.jtc_channel_id
             );
+            data.jtc_queue_stats.record_processed(entry.queued_at.elapsed());
             continue;
         }
 
@@ -174,6 +351,9 @@ pub async fn process_jtc_queue_with_context(
                 );
             }
             Err(e) => {
+                // The flow only clears its pending marker once a room exists
+                data.jtc_pending.remove(&entry.user_id.get());
+                data.rate_limits.record_error(&e);
                 error!(
                     "Failed to create channel for user {} from queue: {:?}",
                     entry.user_id, e
@@ -181,8 +361,17 @@ pub async fn process_jtc_queue_with_context(
             }
         }
 
+        let latency = entry.queued_at.elapsed();
+        data.jtc_queue_stats.record_processed(latency);
+        debug!(
+            "JTC queue entry for user {} done after {}ms ({} still queued)",
+            entry.user_id,
+            latency.as_millis(),
+            data.jtc_queue_stats.depth()
+        );
+
         // Rate limit: wait before processing next entry
-        tokio::time::sleep(RATE_LIMIT_DELAY).await;
+        tokio::time::sleep(creation_spacing(data.rate_limits.is_throttled())).await;
     }
 
     warn!("JTC queue processor stopped");
@@ -193,8 +382,73 @@ pub fn spawn_queue_processor(
     ctx: Context,
     data: Arc<Data>,
     queue_rx: mpsc::UnboundedReceiver<JtcQueueEntry>,
-) {
+) -> AbortHandle {
     tokio::spawn(async move {
         process_jtc_queue_with_context(ctx, data, queue_rx).await;
-    });
+    })
+    .abort_handle()
+}
+
+/// Spawn a task that periodically logs JTC queue health
+pub fn spawn_health_logger(data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(JTC_QUEUE_HEALTH_LOG_SECONDS));
+        // The first tick completes immediately; nothing has been processed yet
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let health = data.jtc_queue_stats.snapshot();
+            info!(
+                "JTC queue health: depth={}, processed={}, avg_latency={}ms, max_latency={}ms",
+                health.depth, health.processed, health.avg_latency_ms, health.max_latency_ms
+            );
+        }
+    })
+    .abort_handle()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_depth_and_latency() {
+        let stats = QueueStats::default();
+        assert_eq!(stats.record_enqueued(), 1);
+        assert_eq!(stats.record_enqueued(), 2);
+
+        stats.record_dequeued();
+        stats.record_processed(Duration::from_millis(100));
+        stats.record_dequeued();
+        stats.record_processed(Duration::from_millis(300));
+
+        assert_eq!(
+            stats.snapshot(),
+            QueueHealth {
+                depth: 0,
+                processed: 2,
+                avg_latency_ms: 200,
+                max_latency_ms: 300,
+            }
+        );
+    }
+
+    #[test]
+    fn wait_grows_with_position() {
+        assert_eq!(estimated_wait(0, false), Duration::ZERO);
+        assert_eq!(estimated_wait(1, false), Duration::ZERO);
+        assert_eq!(estimated_wait(6, false), CREATION_SPACING * 5);
+        assert_eq!(estimated_wait(6, true), CREATION_SPACING * THROTTLED_SPACING_FACTOR * 5);
+    }
+
+    #[test]
+    fn depth_never_goes_negative() {
+        let stats = QueueStats::default();
+        stats.record_dequeued();
+        assert_eq!(stats.depth(), 0);
+        assert_eq!(stats.snapshot().avg_latency_ms, 0);
+    }
 }
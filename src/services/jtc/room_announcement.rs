@@ -0,0 +1,40 @@
+use serenity::all::{ChannelId, CreateMessage, GuildId, Http, UserId};
+use tracing::debug;
+
+use crate::constants::embeds;
+use crate::db::models::GuildConfig;
+
+/// Post a short "come join" embed for a new room to the guild's announcement channel, if set
+/// Best-effort: failures are logged and never affect the room
+pub async fn announce_created(
+    http: &Http,
+    config: &GuildConfig,
+    channel_id: ChannelId,
+    owner_id: UserId,
+    is_casual: bool,
+    name: &str,
+) {
+    let Some(notify_channel) = config.channel_created_notify_channel_id else {
+        return;
+    };
+    let guild_id = GuildId::new(config.guild_id as u64);
+
+    let embed = embeds::info_embed()
+        .title("New Room Open")
+        .description(format!(
+            "**{}**\n[Join <#{}>](https://discord.com/channels/{}/{})",
+            name, channel_id, guild_id, channel_id
+        ))
+        .field("Owner", format!("<@{}>", owner_id), true)
+        .field("Type", if is_casual { "Casual" } else { "Debate" }, true);
+
+    if let Err(e) = ChannelId::new(notify_channel as u64)
+        .send_message(http, CreateMessage::new().embed(embed))
+        .await
+    {
+        debug!(
+            "Could not announce room {} in channel {} for guild {}: {:?}",
+            channel_id, notify_channel, guild_id, e
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Rate limit on owner-initiated room transfers
+//! Passing a room around quickly (often to an alt and back) can dodge owner-based limits, so
+//! each room allows a few transfers per window and back-and-forth handovers are logged.
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tracing::warn;
+
+use crate::constants::timeouts::{TRANSFER_RATE_MAX_USES, TRANSFER_RATE_WINDOW_SECONDS};
+
+const TRANSFER_RATE_WINDOW: Duration = Duration::from_secs(TRANSFER_RATE_WINDOW_SECONDS);
+
+#[derive(Debug, Clone, Copy)]
+struct Transfer {
+    at: Instant,
+    from: u64,
+    to: u64,
+}
+
+/// Recent owner transfers per room (channel_id -> transfers inside the window)
+#[derive(Debug, Default)]
+pub struct TransferHistory {
+    channels: DashMap<u64, Vec<Transfer>>,
+}
+
+impl TransferHistory {
+    /// Record a transfer about to happen, or explain why it has to wait
+    pub fn record(&self, channel_id: u64, from: u64, to: u64) -> Result<(), String> {
+        self.record_at(Instant::now(), channel_id, from, to)
+    }
+
+    fn record_at(&self, now: Instant, channel_id: u64, from: u64, to: u64) -> Result<(), String> {
+        let mut transfers = self.channels.entry(channel_id).or_default();
+        transfers.retain(|t| now.duration_since(t.at) < TRANSFER_RATE_WINDOW);
+
+        if transfers.len() >= TRANSFER_RATE_MAX_USES {
+            let reset_in = TRANSFER_RATE_WINDOW - now.duration_since(transfers[0].at);
+            let mins = reset_in.as_secs() / 60;
+            return Err(format!(
+                "This room has changed hands {} times recently. Try again in {} minute{}.",
+                TRANSFER_RATE_MAX_USES,
+                mins + 1,
+                if mins == 0 { "" } else { "s" }
+            ));
+        }
+
+        if is_back_and_forth(&transfers, from, to) {
+            warn!(
+                "Channel {} is being passed back and forth between {} and {}",
+                channel_id, from, to
+            );
+        }
+
+        transfers.push(Transfer { at: now, from, to });
+        Ok(())
+    }
+
+    /// Forget a room that was deleted
+    pub fn forget(&self, channel_id: u64) {
+        self.channels.remove(&channel_id);
+    }
+
+    /// Drop rooms with no transfers left inside the window, returning how many went
+    pub fn prune(&self) -> usize {
+        let before = self.channels.len();
+        self.channels.retain(|_, transfers| {
+            transfers.retain(|t| t.at.elapsed() < TRANSFER_RATE_WINDOW);
+            !transfers.is_empty()
+        });
+        before - self.channels.len()
+    }
+}
+
+/// Whether handing the room from `from` to `to` undoes a recent transfer between the same two
+fn is_back_and_forth(recent: &[Transfer], from: u64, to: u64) -> bool {
+    recent.iter().any(|t| t.from == to && t.to == from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_transfers_per_window() {
+        let history = TransferHistory::default();
+        let now = Instant::now();
+
+        for i in 0..TRANSFER_RATE_MAX_USES as u64 {
+            assert!(history.record_at(now, 1, i, i + 1).is_ok());
+        }
+        assert!(history.record_at(now, 1, 10, 11).is_err());
+        // Other rooms aren't affected
+        assert!(history.record_at(now, 2, 10, 11).is_ok());
+        // The window moves on
+        assert!(history.record_at(now + TRANSFER_RATE_WINDOW, 1, 10, 11).is_ok());
+    }
+
+    #[test]
+    fn spots_handing_a_room_back() {
+        let recent = [Transfer {
+            at: Instant::now(),
+            from: 1,
+            to: 2,
+        }];
+        assert!(is_back_and_forth(&recent, 2, 1));
+        assert!(!is_back_and_forth(&recent, 2, 3));
+    }
+}
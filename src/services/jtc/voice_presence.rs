@@ -0,0 +1,207 @@
+//! Room occupancy that doesn't take the voice state cache on faith
+//! In very large guilds the cache can come up short, which makes a busy room look empty. Joins the
+//! bot saw without a matching leave are remembered for a while, and when the cache says a room is
+//! empty while one of those joiners is outstanding, they're looked up over HTTP before the room is
+//! treated as empty. Rooms with nobody outstanding never cost an API call.
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::{ChannelId, Context, GuildId, StatusCode, UserId};
+use tracing::{debug, warn};
+
+use crate::bot::data::Data;
+use crate::constants::timeouts::VOICE_JOIN_MEMORY_SECONDS;
+
+const VOICE_JOIN_MEMORY: Duration = Duration::from_secs(VOICE_JOIN_MEMORY_SECONDS);
+/// Most outstanding joiners looked up over HTTP for one room at a time
+const MAX_VOICE_STATE_LOOKUPS: usize = 5;
+
+/// Joins into managed rooms that no leave has been seen for (channel_id -> (user_id, joined at))
+#[derive(Debug, Default)]
+pub struct RecentJoins {
+    channels: DashMap<u64, Vec<(u64, Instant)>>,
+}
+
+impl RecentJoins {
+    /// Remember that a user joined a room
+    pub fn record_join(&self, channel_id: u64, user_id: u64) {
+        self.record_join_at(Instant::now(), channel_id, user_id);
+    }
+
+    fn record_join_at(&self, now: Instant, channel_id: u64, user_id: u64) {
+        let mut joins = self.channels.entry(channel_id).or_default();
+        joins.retain(|(user, _)| *user != user_id);
+        joins.push((user_id, now));
+    }
+
+    /// Forget a user's join once they're known to have left the room
+    pub fn record_leave(&self, channel_id: u64, user_id: u64) {
+        self.channels.remove_if_mut(&channel_id, |_, joins| {
+            joins.retain(|(user, _)| *user != user_id);
+            joins.is_empty()
+        });
+    }
+
+    /// Forget a room that was deleted
+    pub fn forget(&self, channel_id: u64) {
+        self.channels.remove(&channel_id);
+    }
+
+    /// Users who joined a room recently and haven't been seen leaving, newest first
+    fn outstanding(&self, channel_id: u64) -> Vec<u64> {
+        self.outstanding_at(Instant::now(), channel_id)
+    }
+
+    fn outstanding_at(&self, now: Instant, channel_id: u64) -> Vec<u64> {
+        self.channels
+            .get(&channel_id)
+            .map(|joins| {
+                joins
+                    .iter()
+                    .rev()
+                    .filter(|(_, at)| now.duration_since(*at) < VOICE_JOIN_MEMORY)
+                    .map(|(user, _)| *user)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop joins too old to go by, returning how many rooms were forgotten
+    pub fn prune(&self) -> usize {
+        let before = self.channels.len();
+        self.channels.retain(|_, joins| {
+            joins.retain(|(_, at)| at.elapsed() < VOICE_JOIN_MEMORY);
+            !joins.is_empty()
+        });
+        before - self.channels.len()
+    }
+}
+
+/// Users in a voice channel according to the cache, or None when the guild isn't cached
+fn cached_members(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Option<Vec<UserId>> {
+    ctx.cache.guild(guild_id).map(|guild| {
+        guild
+            .voice_states
+            .values()
+            .filter(|vs| vs.channel_id == Some(channel_id))
+            .map(|vs| vs.user_id)
+            .collect()
+    })
+}
+
+/// Whether a user is in a voice channel according to Discord itself
+/// Returns None when that can't be determined (e.g. transient API failure)
+async fn fetch_in_channel(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Option<bool> {
+    match ctx.http.get_user_voice_state(guild_id, user_id).await {
+        Ok(state) => Some(state.channel_id == Some(channel_id)),
+        // Discord answers 404 for users who aren't in voice at all
+        Err(serenity::Error::Http(e)) if e.status_code() == Some(StatusCode::NOT_FOUND) => {
+            Some(false)
+        }
+        Err(e) => {
+            warn!("Could not fetch voice state of {} in guild {}: {:?}", user_id, guild_id, e);
+            None
+        }
+    }
+}
+
+/// The users in a room, double-checked over HTTP when the cache shows it empty but the bot saw
+/// someone join who hasn't left
+/// Joiners whose voice state can't be fetched are counted as present, so a room is never deleted
+/// on a guess; the next check looks again.
+pub async fn channel_members(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Vec<UserId> {
+    let cached = cached_members(ctx, guild_id, channel_id).unwrap_or_default();
+    if !cached.is_empty() {
+        return cached;
+    }
+
+    let outstanding = data.recent_joins.outstanding(channel_id.get());
+    if outstanding.is_empty() {
+        return cached;
+    }
+
+    debug!(
+        "Cache shows channel {} empty but {} recent joiners weren't seen leaving, checking",
+        channel_id,
+        outstanding.len()
+    );
+
+    let mut present = Vec::new();
+    for user_id in outstanding.into_iter().take(MAX_VOICE_STATE_LOOKUPS).map(UserId::new) {
+        match fetch_in_channel(ctx, guild_id, channel_id, user_id).await {
+            Some(false) => data.recent_joins.record_leave(channel_id.get(), user_id.get()),
+            Some(true) | None => present.push(user_id),
+        }
+    }
+
+    if !present.is_empty() {
+        warn!(
+            "Voice state cache is missing {} member(s) of channel {} in guild {}",
+            present.len(),
+            channel_id,
+            guild_id
+        );
+    }
+
+    present
+}
+
+/// Whether a user is in a room, asking Discord when the cache says no but the bot saw them join
+pub async fn is_in_channel(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> bool {
+    let cached = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| guild.voice_states.get(&user_id).map(|vs| vs.channel_id));
+    match cached {
+        Some(current) => current == Some(channel_id),
+        None if data.recent_joins.outstanding(channel_id.get()).contains(&user_id.get()) => {
+            fetch_in_channel(ctx, guild_id, channel_id, user_id).await.unwrap_or(true)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_joins_without_a_leave() {
+        let joins = RecentJoins::default();
+        let now = Instant::now();
+
+        joins.record_join_at(now, 1, 10);
+        joins.record_join_at(now, 1, 11);
+        joins.record_join_at(now, 2, 12);
+        joins.record_leave(1, 10);
+        assert_eq!(joins.outstanding_at(now, 1), vec![11]);
+
+        // Rejoining doesn't list someone twice, and the newest join comes first
+        joins.record_join_at(now, 1, 13);
+        joins.record_join_at(now, 1, 11);
+        assert_eq!(joins.outstanding_at(now, 1), vec![11, 13]);
+
+        // Old joins no longer count
+        assert!(joins.outstanding_at(now + VOICE_JOIN_MEMORY, 1).is_empty());
+
+        joins.record_leave(2, 12);
+        assert!(!joins.channels.contains_key(&2));
+    }
+}
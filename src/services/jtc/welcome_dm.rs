@@ -0,0 +1,57 @@
+use serenity::all::{ChannelId, CreateMessage, GuildId, Http, UserId};
+use sqlx::PgPool;
+use tracing::{debug, warn};
+
+use crate::constants::embeds;
+use crate::db::models::{GuildConfig, MessageTemplate};
+use crate::db::queries::welcome;
+use crate::utils::formatting::fill_template;
+
+/// DM a first-time room owner how their room works, if the guild has opted in
+/// Best-effort: failures are logged and never affect the room
+pub async fn send_if_first(
+    http: &Http,
+    pool: &PgPool,
+    config: &GuildConfig,
+    channel_id: ChannelId,
+    user_id: UserId,
+) {
+    if !config.send_welcome_dm {
+        return;
+    }
+
+    let guild_id = GuildId::new(config.guild_id as u64);
+    // Marked before sending so closed DMs aren't retried on every room
+    match welcome::mark_seen(pool, config.guild_id, user_id.get() as i64).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            warn!(
+                "Failed to record welcome DM for user {} in guild {}: {:?}",
+                user_id, guild_id, e
+            );
+            return;
+        }
+    }
+
+    let embed = embeds::info_embed()
+        .title("Your First Room")
+        .description(fill_template(
+            config.message_template(MessageTemplate::WelcomeDm),
+            &[
+                ("user", format!("<@{}>", user_id)),
+                ("channel", format!("<#{}>", channel_id)),
+            ],
+        ));
+
+    match user_id.create_dm_channel(http).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(http, CreateMessage::new().embed(embed)).await {
+                debug!("Could not send welcome DM to user {}: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for user {}: {:?}", user_id, e);
+        }
+    }
+}
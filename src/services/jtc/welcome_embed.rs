@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use serenity::all::{
-    ButtonStyle, ChannelId, Context, CreateActionRow, CreateButton, CreateMessage,
+    ButtonStyle, ChannelId, Context, CreateActionRow, CreateButton, CreateEmbed,
+    CreateMessage,
     CreateSelectMenu, CreateSelectMenuKind, UserId,
 };
 use tracing::error;
@@ -18,49 +19,22 @@ pub async fn send(
     owner_id: UserId,
     is_casual: bool,
 ) -> Result<(), Error> {
-    let channel_type = if is_casual { "Casual" } else { "Debate" };
-
-    let tips = [
-        "Use the menus below to manage users",
-        "Muted users are unmuted when they leave",
-        "Banned users cannot rejoin",
-        "Channel deletes when empty",
-    ];
-
-    let description = format!(
-        "Welcome to your {} voice channel.\n\n\
-        {}\n\n\
-        **Tips**\n{}",
-        channel_type,
-        DIVIDER_SHORT,
-        tips.iter().map(|t| format!("{} {}", BULLET, t)).collect::<Vec<_>>().join("\n"),
-    );
-
-    let embed = embeds::standard_embed()
-        .title(format!("{} Voice Channel", channel_type))
-        .description(description)
-        .field("Owner", format!("<@{}>", owner_id), true);
+    // New rooms start without a join limit
+    let embed = panel_embed(is_casual, owner_id, 0);
 
     // Build components - user select menus for owner actions
     let mut components = Vec::new();
 
-    // Row 1: Mute user select
+    // Row 1: Mute user select (picking someone already muted unmutes them)
+    // Older panels also have a separate vc_unmute_ menu, which is still handled
     let mute_select = CreateSelectMenu::new(
         format!("vc_mute_{}", channel_id),
         CreateSelectMenuKind::User { default_users: None },
     )
-    .placeholder("Mute a user");
+    .placeholder("Mute or unmute a user");
     components.push(CreateActionRow::SelectMenu(mute_select));
 
-    // Row 2: Unmute user select
-    let unmute_select = CreateSelectMenu::new(
-        format!("vc_unmute_{}", channel_id),
-        CreateSelectMenuKind::User { default_users: None },
-    )
-    .placeholder("Unmute a user");
-    components.push(CreateActionRow::SelectMenu(unmute_select));
-
-    // Row 3: Transfer ownership select
+    // Row 2: Transfer ownership select
     let transfer_select = CreateSelectMenu::new(
         format!("vc_transfer_{}", channel_id),
         CreateSelectMenuKind::User { default_users: None },
@@ -68,6 +42,14 @@ pub async fn send(
     .placeholder("Transfer ownership");
     components.push(CreateActionRow::SelectMenu(transfer_select));
 
+    // Row 3: Kick user select
+    let kick_select = CreateSelectMenu::new(
+        format!("vc_kick_{}", channel_id),
+        CreateSelectMenuKind::User { default_users: None },
+    )
+    .placeholder("Kick a user");
+    components.push(CreateActionRow::SelectMenu(kick_select));
+
     // Row 4: Ban user select
     let ban_select = CreateSelectMenu::new(
         format!("vc_ban_{}", channel_id),
@@ -76,7 +58,7 @@ pub async fn send(
     .placeholder("Ban a user");
     components.push(CreateActionRow::SelectMenu(ban_select));
 
-    // Row 5: Buttons (Reconfigure + Support)
+    // Row 5: Buttons (Reconfigure, Limit, Lock toggle, Unmute All + Support)
     let mut buttons = Vec::new();
 
     let reconfigure_button = CreateButton::new(format!("reconfigure_{}", channel_id))
@@ -89,6 +71,18 @@ pub async fn send(
         .style(ButtonStyle::Secondary);
     buttons.push(limit_button);
 
+    // One toggle so the row has room for Unmute All; older panels keep separate
+    // roomlock_lock_/roomlock_unlock_ buttons, which are still handled
+    let lock_button = CreateButton::new(format!("roomlock_toggle_{}", channel_id))
+        .label("Lock / Unlock")
+        .style(ButtonStyle::Secondary);
+    buttons.push(lock_button);
+
+    let unmute_all_button = CreateButton::new(format!("unmuteall_{}", channel_id))
+        .label("Unmute All")
+        .style(ButtonStyle::Secondary);
+    buttons.push(unmute_all_button);
+
     if let Some(ref donate_link) = data.settings.donate_link {
         let donate_button = CreateButton::new_link(donate_link)
             .label("❤️ Support Us");
@@ -107,3 +101,39 @@ pub async fn send(
 
     Ok(())
 }
+
+/// Build the panel embed; `user_limit` of 0 means no limit
+pub fn panel_embed(is_casual: bool, owner_id: UserId, user_limit: u32) -> CreateEmbed {
+    let channel_type = if is_casual { "Casual" } else { "Debate" };
+
+    let tips = [
+        "Use the menus below to manage users",
+        "Muted users are unmuted when they leave",
+        "Banned users cannot rejoin",
+        "Lock the room to keep new people out",
+        "Channel deletes when empty",
+    ];
+
+    let description = format!(
+        "Welcome to your {} voice channel.\n\n\
+        {}\n\n\
+        **Tips**\n{}",
+        channel_type,
+        DIVIDER_SHORT,
+        tips.iter().map(|t| format!("{} {}", BULLET, t)).collect::<Vec<_>>().join("\n"),
+    );
+
+    embeds::standard_embed()
+        .title(format!("{} Voice Channel", channel_type))
+        .description(description)
+        .field("Owner", format!("<@{}>", owner_id), true)
+        .field("Join Limit", limit_label(user_limit), true)
+}
+
+fn limit_label(user_limit: u32) -> String {
+    if user_limit == 0 {
+        "Unlimited".to_string()
+    } else {
+        format!("{} users", user_limit)
+    }
+}
@@ -1,4 +1,7 @@
+pub mod backup;
+pub mod component_expiry;
 pub mod jtc;
 pub mod moderation;
+pub mod rate_limit;
 pub mod spam;
 pub mod stats;
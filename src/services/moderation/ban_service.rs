@@ -1,16 +1,21 @@
 use std::sync::Arc;
 
 use serenity::all::{
-    ChannelId, Context, GuildId, PermissionOverwrite, PermissionOverwriteType,
-    Permissions, UserId,
+    ButtonStyle, ChannelId, Context, CreateActionRow, CreateButton, CreateMessage, GuildChannel,
+    GuildId, Http, PermissionOverwrite, PermissionOverwriteType, Permissions, UserId,
 };
-use tracing::{debug, info};
+use sqlx::PgPool;
+use tracing::{debug, error, info, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::db::queries::ban;
+use crate::constants::embeds;
+use crate::db::models::BanRecord;
+use crate::db::queries::{ban, guild_config};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
 
 /// Ban a user from a voice channel
+/// Returns false if the user was already banned (the ban is re-enforced but not re-recorded)
 pub async fn ban_user(
     ctx: &Context,
     data: &Arc<Data>,
@@ -19,12 +24,12 @@ pub async fn ban_user(
     banned_user_id: UserId,
     banned_by_user_id: UserId,
     reason: Option<&str>,
-) -> Result<(), Error> {
+) -> Result<bool, Error> {
     // Add permission deny for the banned user
     apply_channel_ban(ctx, channel_id, banned_user_id).await?;
 
-    // Store in database
-    ban::create(
+    // Store in database (no-op if already banned)
+    let record = ban::create(
         &data.pool,
         guild_id.get() as i64,
         channel_id.get() as i64,
@@ -33,21 +38,96 @@ pub async fn ban_user(
         reason,
     )
     .await?;
+    let is_new_ban = record.is_some();
 
     // Disconnect the user from the channel if they're in it
     disconnect_user(ctx, guild_id, banned_user_id).await?;
 
-    info!(
-        "User {} banned user {} from channel {} (reason: {:?})",
-        banned_by_user_id, banned_user_id, channel_id, reason
+    if let Some(record) = record {
+        info!(
+            "User {} banned user {} from channel {} (reason: {:?})",
+            banned_by_user_id, banned_user_id, channel_id, reason
+        );
+
+        let entry = LogEntry {
+            action: LogAction::Ban,
+            actor: Some(banned_by_user_id),
+            target: banned_user_id,
+            channel_id: Some(channel_id),
+            reason: reason.map(str::to_string),
+        };
+        mod_log::record(ctx, data, guild_id, entry).await;
+
+        // DM the user in the background so interaction handlers aren't held up
+        let ctx = ctx.clone();
+        let data = Arc::clone(data);
+        tokio::spawn(async move {
+            send_ban_notice(&ctx, &data, &record).await;
+        });
+    } else {
+        debug!(
+            "User {} was already banned from channel {}, re-applied ban",
+            banned_user_id, channel_id
+        );
+    }
+
+    Ok(is_new_ban)
+}
+
+/// DM a newly banned user, with an "Appeal" button when the guild has an appeal channel
+/// Fails silently if the user has DMs closed
+async fn send_ban_notice(ctx: &Context, data: &Arc<Data>, record: &BanRecord) {
+    let appeals_enabled = match guild_config::get(&data.pool, record.guild_id).await {
+        Ok(Some(config)) => config.appeal_channel_id.is_some(),
+        Ok(None) => false,
+        Err(e) => {
+            error!("Failed to load guild config for ban notice: {:?}", e);
+            return;
+        }
+    };
+
+    if !appeals_enabled {
+        return;
+    }
+
+    let mut description = format!(
+        "You have been banned from <#{}>.",
+        record.channel_id
     );
+    if let Some(ref reason) = record.reason {
+        description.push_str(&format!("\n**Reason:** {}", reason));
+    }
+    description.push_str("\n\nIf you think this was a mistake, you can appeal below.");
 
-    Ok(())
+    let embed = embeds::warning_embed()
+        .title("Voice Channel Ban")
+        .description(description);
+
+    let button = CreateActionRow::Buttons(vec![CreateButton::new(format!(
+        "appeal_open_{}",
+        record.id
+    ))
+    .label("Appeal")
+    .style(ButtonStyle::Primary)]);
+
+    let message = CreateMessage::new().embed(embed).components(vec![button]);
+    let user_id = UserId::new(record.banned_user_id as u64);
+
+    match user_id.create_dm_channel(ctx).await {
+        Ok(dm_channel) => {
+            if let Err(e) = dm_channel.send_message(ctx, message).await {
+                debug!("Could not DM user {} about their ban: {:?}", user_id, e);
+            }
+        }
+        Err(e) => {
+            debug!("Could not create DM channel for user {}: {:?}", user_id, e);
+        }
+    }
 }
 
 /// Apply a channel permission deny for a banned user
 async fn apply_channel_ban(
-    ctx: &Context,
+    http: impl AsRef<Http>,
     channel_id: ChannelId,
     user_id: UserId,
 ) -> Result<(), Error> {
@@ -58,7 +138,7 @@ async fn apply_channel_ban(
     };
 
     channel_id
-        .create_permission(ctx, permission_overwrite)
+        .create_permission(http, permission_overwrite)
         .await?;
 
     debug!(
@@ -69,6 +149,83 @@ async fn apply_channel_ban(
     Ok(())
 }
 
+/// Re-apply recorded bans whose permission deny is missing from a channel
+/// Overwrites can be lost while the bot is down (edited by hand, or the channel recreated);
+/// without them a banned user can rejoin. Returns how many were restored.
+pub async fn reapply_channel_bans(
+    http: &Http,
+    pool: &PgPool,
+    channel: &GuildChannel,
+) -> Result<usize, Error> {
+    let bans = ban::get_bans_for_channel(pool, channel.id.get() as i64).await?;
+    let banned = bans.iter().map(|b| UserId::new(b.banned_user_id as u64));
+
+    let mut restored = 0;
+    for user_id in missing_ban_overwrites(&channel.permission_overwrites, banned) {
+        match apply_channel_ban(http, channel.id, user_id).await {
+            Ok(()) => restored += 1,
+            Err(e) => warn!(
+                "Failed to re-apply ban for user {} on channel {}: {:?}",
+                user_id, channel.id, e
+            ),
+        }
+    }
+
+    if restored > 0 {
+        info!("Re-applied {} lost ban(s) on channel {}", restored, channel.id);
+    }
+
+    Ok(restored)
+}
+
+/// Turn away a banned user who got into a channel, restoring the deny if it was lost
+/// A backstop for overwrites removed while the bot wasn't watching.
+/// Returns true if the user was banned and has been disconnected.
+pub async fn enforce_ban_on_join(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Result<bool, Error> {
+    if !is_banned(data, channel_id, user_id).await? {
+        return Ok(false);
+    }
+
+    let overwrites = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.channels.get(&channel_id).map(|c| c.permission_overwrites.clone()))
+        .unwrap_or_default();
+    if !missing_ban_overwrites(&overwrites, std::iter::once(user_id)).is_empty() {
+        apply_channel_ban(ctx, channel_id, user_id).await?;
+    }
+
+    disconnect_user(ctx, guild_id, user_id).await?;
+
+    warn!(
+        "Banned user {} got into channel {}, ban re-enforced",
+        user_id, channel_id
+    );
+
+    Ok(true)
+}
+
+/// Banned users without a member overwrite denying CONNECT
+fn missing_ban_overwrites(
+    overwrites: &[PermissionOverwrite],
+    banned: impl Iterator<Item = UserId>,
+) -> Vec<UserId> {
+    banned
+        .filter(|&user_id| {
+            !overwrites.iter().any(|o| {
+                o.kind == PermissionOverwriteType::Member(user_id)
+                    && o.deny.contains(Permissions::CONNECT)
+            })
+        })
+        .collect()
+}
+
 /// Disconnect a user from their current voice channel
 async fn disconnect_user(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Result<(), Error> {
     // Move user to no channel (disconnect)
@@ -144,3 +301,43 @@ async fn remove_channel_ban(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_bans_without_a_connect_deny_are_missing() {
+        let overwrite = |user: u64, deny: Permissions| PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny,
+            kind: PermissionOverwriteType::Member(UserId::new(user)),
+        };
+        let overwrites = [
+            overwrite(1, Permissions::CONNECT | Permissions::VIEW_CHANNEL),
+            // Someone edited the deny away by hand
+            overwrite(2, Permissions::VIEW_CHANNEL),
+        ];
+
+        let banned = [1, 2, 3].into_iter().map(UserId::new);
+        assert_eq!(
+            missing_ban_overwrites(&overwrites, banned),
+            vec![UserId::new(2), UserId::new(3)]
+        );
+    }
+
+    #[test]
+    fn ban_is_restored_for_a_rejoin_after_restart() {
+        let user = UserId::new(7);
+        // The channel came back from a restart with no overwrites at all
+        assert_eq!(missing_ban_overwrites(&[], std::iter::once(user)), vec![user]);
+
+        // Once re-applied, a second rejoin only needs the disconnect
+        let restored = PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::CONNECT | Permissions::VIEW_CHANNEL,
+            kind: PermissionOverwriteType::Member(user),
+        };
+        assert!(missing_ban_overwrites(&[restored], std::iter::once(user)).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, Context, GuildId, UserId};
+use tracing::{info, warn};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::db::queries::{ban, global_mute, mute, spam};
+use crate::services::moderation::{ban_service, mute_service};
+
+/// What `forgive_user` cleared
+#[derive(Debug, Default)]
+pub struct ForgiveSummary {
+    /// Active channel mutes closed
+    pub mutes_cleared: u64,
+    /// Channel bans lifted
+    pub bans_lifted: usize,
+    /// Bans whose overwrite couldn't be removed (left in place)
+    pub bans_failed: usize,
+    /// Spam timeout level before the reset, if it was above zero
+    pub timeout_level_reset: Option<i32>,
+}
+
+/// Wipe a user's moderation record in a guild: active mutes, channel bans and spam timeout level
+/// Global (server-wide) mutes are left alone
+pub async fn forgive_user(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<ForgiveSummary, Error> {
+    let mut summary = ForgiveSummary::default();
+    let guild = guild_id.get() as i64;
+    let user = user_id.get() as i64;
+
+    // Note the mute in the user's current channel before clearing, so we know whether to undeafen
+    let current_channel = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|g| g.voice_states.get(&user_id).and_then(|vs| vs.channel_id));
+    let current_mute = match current_channel {
+        Some(channel_id) => mute::get_active_mute(&data.pool, channel_id.get() as i64, user).await?,
+        None => None,
+    };
+
+    summary.mutes_cleared = mute::unmute_all_for_user_in_guild(&data.pool, guild, user).await?;
+
+    // Lift the server mute if one of ours is on them right now
+    if let Some(record) = current_mute {
+        if !global_mute::is_globally_muted(&data.pool, guild, user).await? {
+            data.mark_pending_unmute(guild_id.get(), user_id.get());
+            let undeafen = record.is_deafened.then_some(false);
+            if let Err(e) =
+                mute_service::apply_voice_state(ctx, guild_id, user_id, false, undeafen).await
+            {
+                warn!("Failed to lift voice mute for forgiven user {}: {:?}", user_id, e);
+            }
+        }
+    }
+
+    for record in ban::list_for_user_in_guild(&data.pool, guild, user).await? {
+        let channel_id = ChannelId::new(record.channel_id as u64);
+        match ban_service::unban_user(ctx, data, channel_id, user_id).await {
+            Ok(_) => summary.bans_lifted += 1,
+            Err(e) => {
+                warn!(
+                    "Failed to lift ban for user {} on channel {}: {:?}",
+                    user_id, channel_id, e
+                );
+                summary.bans_failed += 1;
+            }
+        }
+    }
+
+    if let Some(record) = spam::get_user_stats(&data.pool, guild, user).await? {
+        if record.current_timeout_level > 0 {
+            spam::reset_timeout_level(&data.pool, guild, user).await?;
+            summary.timeout_level_reset = Some(record.current_timeout_level);
+        }
+    }
+
+    info!(
+        "Forgave user {} in guild {}: {} mutes, {} bans ({} failed), timeout level {:?}",
+        user_id,
+        guild_id,
+        summary.mutes_cleared,
+        summary.bans_lifted,
+        summary.bans_failed,
+        summary.timeout_level_reset
+    );
+
+    Ok(summary)
+}
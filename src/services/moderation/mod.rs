@@ -1,2 +1,5 @@
 pub mod ban_service;
+pub mod forgive_service;
+pub mod mod_log;
 pub mod mute_service;
+pub mod room_notice;
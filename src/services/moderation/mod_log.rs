@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use serenity::all::{ChannelId, Context, CreateEmbed, CreateMessage, GuildId, UserId};
+use tracing::{debug, warn};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::db::queries::guild_config;
+
+/// Moderation actions recorded in the mod log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogAction {
+    Mute,
+    Unmute,
+    Ban,
+    SpamTimeout,
+    Transfer,
+}
+
+impl LogAction {
+    fn title(&self) -> &'static str {
+        match self {
+            LogAction::Mute => "User Muted",
+            LogAction::Unmute => "User Unmuted",
+            LogAction::Ban => "User Banned",
+            LogAction::SpamTimeout => "Spam Timeout",
+            LogAction::Transfer => "Ownership Transferred",
+        }
+    }
+}
+
+/// One moderation action for the mod log
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub action: LogAction,
+    /// Who acted; None when the bot acted on its own
+    pub actor: Option<UserId>,
+    pub target: UserId,
+    pub channel_id: Option<ChannelId>,
+    pub reason: Option<String>,
+}
+
+impl LogEntry {
+    fn embed(&self) -> CreateEmbed {
+        let actor = match self.actor {
+            Some(id) => format!("<@{}>", id),
+            None => "Automatic".to_string(),
+        };
+        let channel = match self.channel_id {
+            Some(id) => format!("<#{}>", id),
+            None => "None".to_string(),
+        };
+
+        embeds::info_embed()
+            .title(self.action.title())
+            .field("Actor", actor, true)
+            .field("Target", format!("<@{}> (`{}`)", self.target, self.target), true)
+            .field("Channel", channel, true)
+            .field("Reason", self.reason.as_deref().unwrap_or("No reason given"), false)
+    }
+}
+
+/// Record a moderation action in the guild's mod log, if it has one
+/// Best-effort: failures are logged and never affect the action itself
+pub async fn record(ctx: &Context, data: &Arc<Data>, guild_id: GuildId, entry: LogEntry) {
+    if let Err(e) = post(ctx, data, guild_id, entry.embed()).await {
+        warn!("Failed to post {:?} to the mod log of guild {}: {:?}", entry.action, guild_id, e);
+    }
+}
+
+/// Post an embed to the guild's mod log channel
+/// Returns false if no mod log channel is configured
+pub async fn post(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    embed: CreateEmbed,
+) -> Result<bool, Error> {
+    let channel_id = match guild_config::get(&data.pool, guild_id.get() as i64)
+        .await?
+        .and_then(|config| config.mod_log_channel_id)
+    {
+        Some(id) => ChannelId::new(id as u64),
+        None => {
+            debug!("No mod log channel configured for guild {}", guild_id);
+            return Ok(false);
+        }
+    };
+
+    channel_id
+        .send_message(ctx, CreateMessage::new().embed(embed))
+        .await?;
+
+    Ok(true)
+}
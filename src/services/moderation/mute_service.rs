@@ -1,13 +1,91 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use dashmap::DashMap;
 
 use serenity::all::{CacheHttp, ChannelId, Context, EditMember, GuildId, UserId};
-use tracing::{debug, error, info};
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info, warn};
 
 use crate::bot::data::Data;
 use crate::bot::error::Error;
-use crate::db::queries::mute;
+use crate::constants::timeouts::MUTE_EXPIRY_CHECK_INTERVAL_SECONDS;
+use crate::db::models::MuteRecord;
+use crate::db::queries::{global_mute, mute};
+use crate::db::repository::{MuteRepo, VoiceChannelRepo};
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
+use crate::utils::duration::format_short;
+
+/// Pause between voice edits when lifting every mute in a room
+const UNMUTE_ALL_SPACING_MS: u64 = 250;
+
+/// Delayed unmutes scheduled when a muted user leaves a channel, keyed by (guild_id, user_id)
+/// Rejoining a channel where they're muted cancels the unmute instead of unmuting then re-muting
+#[derive(Debug, Default)]
+pub struct PendingUnmutes {
+    tasks: DashMap<(u64, u64), PendingUnmute>,
+}
+
+#[derive(Debug)]
+struct PendingUnmute {
+    handle: AbortHandle,
+    /// Set once the delay has elapsed and the unmute is underway
+    fired: Arc<AtomicBool>,
+    /// Whether the mute being lifted also deafened the user
+    deafened: bool,
+}
+
+impl PendingUnmutes {
+    /// Run `unmute` after `delay` unless cancelled first
+    /// Replaces (and cancels) any unmute already pending for the user
+    pub fn schedule<F>(&self, guild_id: u64, user_id: u64, deafened: bool, delay: Duration, unmute: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let fired = Arc::new(AtomicBool::new(false));
+        let task_fired = Arc::clone(&fired);
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            task_fired.store(true, Ordering::SeqCst);
+            unmute.await;
+        })
+        .abort_handle();
+
+        let pending = PendingUnmute {
+            handle,
+            fired,
+            deafened,
+        };
+        if let Some(previous) = self.tasks.insert((guild_id, user_id), pending) {
+            previous.abort_if_waiting();
+        }
+    }
+
+    /// Cancel a pending unmute that hasn't started yet
+    /// Returns whether the cancelled mute deafened the user, or None if there was nothing to cancel
+    pub fn cancel(&self, guild_id: u64, user_id: u64) -> Option<bool> {
+        let (_, pending) = self.tasks.remove(&(guild_id, user_id))?;
+        pending.abort_if_waiting().then_some(pending.deafened)
+    }
+}
+
+impl PendingUnmute {
+    /// Abort the task if it's still in its delay; an unmute already underway is left to finish
+    fn abort_if_waiting(&self) -> bool {
+        if self.fired.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.handle.abort();
+        true
+    }
+}
 
-/// Mute a user in a voice channel
+/// Mute (and optionally deafen) a user in a voice channel
+/// With a `duration` the mute is lifted automatically once it passes
+#[allow(clippy::too_many_arguments)]
 pub async fn mute_user(
     ctx: &Context,
     data: &Arc<Data>,
@@ -16,9 +94,17 @@ pub async fn mute_user(
     muted_user_id: UserId,
     muted_by_user_id: UserId,
     is_admin_mute: bool,
+    deafen: bool,
+    duration: Option<Duration>,
 ) -> Result<(), Error> {
-    // Apply server mute
-    apply_server_mute(ctx, guild_id, muted_user_id, true).await?;
+    let expires_at = duration
+        .map(chrono::Duration::from_std)
+        .transpose()
+        .map_err(|_| Error::InvalidOperation("That mute duration is too long.".to_string()))?
+        .map(|d| Utc::now() + d);
+
+    // Apply server mute (and deafen)
+    apply_voice_state(ctx, guild_id, muted_user_id, true, deafen.then_some(true)).await?;
 
     // Store in database
     mute::create(
@@ -28,17 +114,66 @@ pub async fn mute_user(
         muted_user_id.get() as i64,
         muted_by_user_id.get() as i64,
         is_admin_mute,
+        deafen,
+        expires_at,
     )
     .await?;
 
     info!(
-        "User {} muted user {} in channel {} (admin: {})",
-        muted_by_user_id, muted_user_id, channel_id, is_admin_mute
+        "User {} muted user {} in channel {} (admin: {}, deafened: {}, expires: {:?})",
+        muted_by_user_id, muted_user_id, channel_id, is_admin_mute, deafen, expires_at
     );
 
+    let entry = LogEntry {
+        action: LogAction::Mute,
+        actor: Some(muted_by_user_id),
+        target: muted_user_id,
+        channel_id: Some(channel_id),
+        reason: mute_details(is_admin_mute, deafen, duration),
+    };
+    mod_log::record(ctx, data, guild_id, entry).await;
+
     Ok(())
 }
 
+/// How a mute was applied, for the mod log
+fn mute_details(is_admin_mute: bool, deafen: bool, duration: Option<Duration>) -> Option<String> {
+    let mut details = Vec::new();
+    if is_admin_mute {
+        details.push("admin mute".to_string());
+    }
+    if deafen {
+        details.push("deafened".to_string());
+    }
+    if let Some(duration) = duration {
+        details.push(format!("for {}", format_short(duration)));
+    }
+
+    (!details.is_empty()).then(|| details.join(", "))
+}
+
+/// Escalate an existing channel mute to also deafen the user
+/// Returns false if the user has no active mute in the channel
+pub async fn deafen_user(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Result<bool, Error> {
+    let record =
+        mute::set_deafened(&data.pool, channel_id.get() as i64, user_id.get() as i64).await?;
+
+    if record.is_none() {
+        return Ok(false);
+    }
+
+    apply_voice_state(ctx, guild_id, user_id, true, Some(true)).await?;
+    info!("User {} deafened in channel {}", user_id, channel_id);
+
+    Ok(true)
+}
+
 /// Unmute a user in a voice channel
 pub async fn unmute_user(
     ctx: &Context,
@@ -46,21 +181,351 @@ pub async fn unmute_user(
     guild_id: GuildId,
     channel_id: ChannelId,
     user_id: UserId,
+    unmuted_by_user_id: UserId,
 ) -> Result<bool, Error> {
-    // Check if user has an active mute
+    // Check if user has an active mute (and whether it deafened them)
+    let active =
+        mute::get_active_mute(&data.pool, channel_id.get() as i64, user_id.get() as i64).await?;
     let had_mute =
         mute::unmute_by_channel_user(&data.pool, channel_id.get() as i64, user_id.get() as i64)
             .await?;
 
     if had_mute {
-        // Remove server mute
-        apply_server_mute(ctx, guild_id, user_id, false).await?;
+        // Remove server mute (and the deafen we applied)
+        let undeafen = active.is_some_and(|m| m.is_deafened).then_some(false);
+        apply_voice_state(ctx, guild_id, user_id, false, undeafen).await?;
         info!("User {} unmuted in channel {}", user_id, channel_id);
+
+        let entry = LogEntry {
+            action: LogAction::Unmute,
+            actor: Some(unmuted_by_user_id),
+            target: user_id,
+            channel_id: Some(channel_id),
+            reason: None,
+        };
+        mod_log::record(ctx, data, guild_id, entry).await;
     }
 
     Ok(had_mute)
 }
 
+/// Lift every owner mute in a room, returning how many were lifted
+/// Admin mutes and globally muted users are left alone. Voice edits are spaced out so a
+/// room full of muted people doesn't burst the API.
+pub async fn unmute_all_in_channel(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<usize, Error> {
+    let records = mute::list_active_mutes_for_channel(&data.pool, channel_id.get() as i64).await?;
+
+    let mut lifted = 0;
+    for record in records.iter().filter(|r| !r.is_admin_mute) {
+        if global_mute::is_globally_muted(&data.pool, record.guild_id, record.muted_user_id).await? {
+            continue;
+        }
+
+        mute::unmute(&data.pool, record.id).await?;
+        lifted += 1;
+
+        // People who already left were unmuted on their way out
+        let user_id = UserId::new(record.muted_user_id as u64);
+        let in_channel = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+            == Some(channel_id);
+        if !in_channel {
+            continue;
+        }
+
+        if lifted > 1 {
+            tokio::time::sleep(Duration::from_millis(UNMUTE_ALL_SPACING_MS)).await;
+        }
+        data.mark_pending_unmute(guild_id.get(), user_id.get());
+        let undeafen = record.is_deafened.then_some(false);
+        if let Err(e) = apply_voice_state(ctx, guild_id, user_id, false, undeafen).await {
+            data.rate_limits.record_error(&e);
+            warn!("Failed to lift mute for {} in channel {}: {:?}", user_id, channel_id, e);
+        }
+    }
+
+    if lifted > 0 {
+        info!("Lifted {} mutes in channel {}", lifted, channel_id);
+    }
+
+    Ok(lifted)
+}
+
+/// Lift every timed mute whose expiry has passed, returning how many were lifted
+/// Users who are still in the muted channel get their server mute removed, unless a global
+/// mute is holding it; everyone else was already unmuted when they left.
+pub async fn lift_expired_mutes(ctx: &Context, data: &Arc<Data>) -> Result<usize, Error> {
+    let expired = close_expired_mutes(&data.pool).await?;
+
+    let mut edits = 0;
+    for record in &expired {
+        let guild_id = GuildId::new(record.guild_id as u64);
+        let channel_id = ChannelId::new(record.channel_id as u64);
+        let user_id = UserId::new(record.muted_user_id as u64);
+
+        let in_channel = ctx
+            .cache
+            .guild(guild_id)
+            .and_then(|g| g.voice_states.get(&user_id).and_then(|vs| vs.channel_id))
+            == Some(channel_id);
+        if !in_channel {
+            continue;
+        }
+
+        // A global mute keeps the server mute; so does an error finding out
+        match global_mute::is_globally_muted(&data.pool, record.guild_id, record.muted_user_id).await {
+            Ok(false) => {}
+            Ok(true) => continue,
+            Err(e) => {
+                warn!("Failed to check global mute for {}: {:?}", user_id, e);
+                continue;
+            }
+        }
+
+        if edits > 0 {
+            tokio::time::sleep(Duration::from_millis(UNMUTE_ALL_SPACING_MS)).await;
+        }
+        edits += 1;
+        data.mark_pending_unmute(guild_id.get(), user_id.get());
+        let undeafen = record.is_deafened.then_some(false);
+        if let Err(e) = apply_voice_state(ctx, guild_id, user_id, false, undeafen).await {
+            data.rate_limits.record_error(&e);
+            warn!("Failed to lift expired mute for {} in channel {}: {:?}", user_id, channel_id, e);
+        } else {
+            info!("Mute of user {} in channel {} expired", user_id, channel_id);
+        }
+    }
+
+    Ok(expired.len())
+}
+
+/// Close every timed mute whose expiry has passed, returning the ones this call closed
+/// Mutes closed by a manual unmute since the query ran are skipped, and a mute that fails
+/// to close is logged so the rest still do.
+pub async fn close_expired_mutes<R: MuteRepo>(repo: &R) -> Result<Vec<MuteRecord>, Error> {
+    let mut closed = Vec::new();
+    for record in repo.get_expired_mutes().await? {
+        match repo.close_mute(record.id).await {
+            Ok(Some(record)) => closed.push(record),
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Failed to close expired mute for {} in channel {}: {:?}",
+                record.muted_user_id, record.channel_id, e
+            ),
+        }
+    }
+
+    Ok(closed)
+}
+
+/// Periodically lift timed mutes that have run out
+pub fn spawn_mute_expiry(ctx: Context, data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(MUTE_EXPIRY_CHECK_INTERVAL_SECONDS));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            // A mute running a few seconds over is better than piling onto a rate limit
+            if data.rate_limits.is_throttled() {
+                debug!("Rate limited, deferring mute expiry check");
+                continue;
+            }
+
+            match lift_expired_mutes(&ctx, &data).await {
+                Ok(0) => {}
+                Ok(lifted) => debug!("Lifted {} expired mute(s)", lifted),
+                Err(e) => error!("Failed to lift expired mutes: {:?}", e),
+            }
+        }
+    })
+    .abort_handle()
+}
+
+/// Close the owner mutes of a room that is being deleted and lift the server mutes
+/// Without this, people disconnected by the deletion stay server-muted wherever they go next.
+/// Only voice-connected users can be edited now; the others keep their mute open and have it
+/// lifted on their next join (see `lift_orphaned_mutes`).
+/// Guilds with `keep_mutes_on_channel_delete` keep both. Admin and global mutes are never touched.
+/// Call before the Discord channel is deleted, while the muted users can still be edited.
+/// Returns the closed mutes, so they can be restored if the deletion fails
+pub async fn release_channel_mutes(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<Vec<MuteRecord>, Error> {
+    let keep = data
+        .guild_config(guild_id.get())
+        .await?
+        .is_some_and(|config| config.keep_mutes_on_channel_delete);
+    if keep {
+        return Ok(Vec::new());
+    }
+
+    let connected = |user_id: UserId| {
+        ctx.cache
+            .guild(guild_id)
+            .is_some_and(|g| g.voice_states.get(&user_id).is_some_and(|vs| vs.channel_id.is_some()))
+    };
+    let records = close_channel_mutes(&data.pool, channel_id, connected).await?;
+
+    for record in &records {
+        if global_mute::is_globally_muted(&data.pool, record.guild_id, record.muted_user_id).await? {
+            continue;
+        }
+
+        let user_id = UserId::new(record.muted_user_id as u64);
+        data.mark_pending_unmute(guild_id.get(), user_id.get());
+        let undeafen = record.is_deafened.then_some(false);
+        if let Err(e) = apply_voice_state(ctx, guild_id, user_id, false, undeafen).await {
+            warn!("Failed to lift mute for {} from deleted channel {}: {:?}", user_id, channel_id, e);
+        }
+    }
+
+    if !records.is_empty() {
+        debug!("Closed {} mutes for deleted channel {}", records.len(), channel_id);
+    }
+
+    Ok(records)
+}
+
+/// Close the owner mutes of a room being torn down for users who are connected, returning them
+/// Admin mutes stay open; they're only lifted by a moderator. So do the mutes of users who
+/// aren't connected, since their server mute can't be lifted until they join again.
+pub async fn close_channel_mutes<R: MuteRepo>(
+    repo: &R,
+    channel_id: ChannelId,
+    is_connected: impl Fn(UserId) -> bool,
+) -> Result<Vec<MuteRecord>, Error> {
+    let active = repo.list_active_mutes_for_channel(channel_id.get() as i64).await?;
+
+    let mut closed = Vec::new();
+    for record in active.iter().filter(|record| !record.is_admin_mute) {
+        if !is_connected(UserId::new(record.muted_user_id as u64)) {
+            continue;
+        }
+        if let Some(record) = repo.close_mute(record.id).await? {
+            closed.push(record);
+        }
+    }
+
+    Ok(closed)
+}
+
+/// A user's open owner mutes from rooms that no longer exist
+/// These are left by a room deleted while the user was offline.
+pub async fn orphaned_mutes<R: MuteRepo + VoiceChannelRepo>(
+    repo: &R,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Vec<MuteRecord>, Error> {
+    let active = repo
+        .list_active_local_mutes_for_user(guild_id.get() as i64, user_id.get() as i64)
+        .await?;
+
+    let mut orphaned = Vec::new();
+    for record in active {
+        if repo.get_channel(record.channel_id).await?.is_none() {
+            orphaned.push(record);
+        }
+    }
+
+    Ok(orphaned)
+}
+
+/// Close the mutes a server-muted user still has from deleted rooms, and lift the server mute
+/// The server mute stays if they're globally muted or muted in the channel they just joined.
+/// Guilds with `keep_mutes_on_channel_delete` keep both.
+/// Returns whether the server mute was lifted
+pub async fn lift_orphaned_mutes(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    user_id: UserId,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let keep = data
+        .guild_config(guild_id.get())
+        .await?
+        .is_some_and(|config| config.keep_mutes_on_channel_delete);
+    if keep {
+        return Ok(false);
+    }
+
+    let orphaned = orphaned_mutes(&data.pool, guild_id, user_id).await?;
+    if orphaned.is_empty() {
+        return Ok(false);
+    }
+
+    for record in &orphaned {
+        data.pool.close_mute(record.id).await?;
+    }
+    debug!("Closed {} mutes of user {} from deleted channels", orphaned.len(), user_id);
+
+    if global_mute::is_globally_muted(&data.pool, guild_id.get() as i64, user_id.get() as i64).await?
+        || should_remute(&data.pool, channel_id, user_id).await?.is_some()
+    {
+        return Ok(false);
+    }
+
+    data.mark_pending_unmute(guild_id.get(), user_id.get());
+    let undeafen = orphaned.iter().any(|record| record.is_deafened).then_some(false);
+    apply_voice_state(ctx, guild_id, user_id, false, undeafen).await?;
+    info!("Lifted leftover mute from deleted channel for user {}", user_id);
+
+    Ok(true)
+}
+
+/// Undo `release_channel_mutes` for a room Discord refused to delete
+/// Reopens the mutes and re-applies them to anyone still connected; the rest are
+/// re-muted by the rejoin check on their next join.
+pub async fn restore_channel_mutes(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    records: &[MuteRecord],
+) -> Result<(), Error> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    // Reopened first, so the mute events below are seen as already tracked
+    let ids: Vec<_> = records.iter().map(|record| record.id).collect();
+    mute::reopen(&data.pool, &ids).await?;
+
+    for record in records {
+        let user_id = UserId::new(record.muted_user_id as u64);
+
+        let connected = ctx
+            .cache
+            .guild(guild_id)
+            .is_some_and(|g| g.voice_states.get(&user_id).is_some_and(|vs| vs.channel_id.is_some()));
+        if !connected {
+            continue;
+        }
+
+        let deafen = record.is_deafened.then_some(true);
+        if let Err(e) = apply_voice_state(ctx, guild_id, user_id, true, deafen).await {
+            warn!("Failed to re-apply mute for {} in channel {}: {:?}", user_id, channel_id, e);
+        }
+    }
+
+    debug!("Restored {} mutes for channel {}", records.len(), channel_id);
+
+    Ok(())
+}
+
 /// Apply or remove server mute from a user
 pub async fn apply_server_mute(
     http: impl CacheHttp,
@@ -68,15 +533,31 @@ pub async fn apply_server_mute(
     user_id: UserId,
     mute: bool,
 ) -> Result<(), Error> {
-    let edit = EditMember::new().mute(mute);
+    apply_voice_state(http, guild_id, user_id, mute, None).await
+}
+
+/// Apply or remove server mute, and optionally server deafen, in a single edit
+/// `deafen: None` leaves the user's deafen state untouched
+pub async fn apply_voice_state(
+    http: impl CacheHttp,
+    guild_id: GuildId,
+    user_id: UserId,
+    mute: bool,
+    deafen: Option<bool>,
+) -> Result<(), Error> {
+    let mut edit = EditMember::new().mute(mute);
+    if let Some(deafen) = deafen {
+        edit = edit.deafen(deafen);
+    }
 
     match guild_id.edit_member(&http, user_id, edit).await {
         Ok(_) => {
             debug!(
-                "Server {} user {} in guild {}",
+                "Server {} user {} in guild {} (deafen: {:?})",
                 if mute { "muted" } else { "unmuted" },
                 user_id,
-                guild_id
+                guild_id,
+                deafen
             );
             Ok(())
         }
@@ -93,13 +574,299 @@ pub async fn apply_server_mute(
 }
 
 /// Check if a user should be muted when joining a channel
-pub async fn should_remute(
-    data: &Arc<Data>,
+/// Returns the active mute to re-apply (including whether it deafens). A timed mute that ran
+/// out while they were away isn't re-applied; the expiry check closes it.
+pub async fn should_remute<R: MuteRepo>(
+    repo: &R,
     channel_id: ChannelId,
     user_id: UserId,
-) -> Result<bool, Error> {
-    let active_mute =
-        mute::get_active_mute(&data.pool, channel_id.get() as i64, user_id.get() as i64).await?;
+) -> Result<Option<MuteRecord>, Error> {
+    let active_mute = repo
+        .get_active_mute(channel_id.get() as i64, user_id.get() as i64)
+        .await?;
+
+    let now = Utc::now();
+    Ok(active_mute.filter(|record| record.expires_at.is_none_or(|at| at > now)))
+}
+
+/// Reconcile the mute ledger with actual voice states after downtime
+/// Users still in the channel they were muted in get their server mute re-applied.
+/// Local mutes for channels the user has since moved out of are cleared, and the server mute
+/// is lifted unless they are globally muted or sitting in another channel they're muted in.
+/// Users who disconnected keep their mutes until they join again.
+/// Admin mutes and global mutes are never touched.
+/// Returns (reapplied_count, cleared_count)
+pub async fn reconcile_active_mutes(
+    ctx: &Context,
+    data: &Arc<Data>,
+) -> Result<(usize, usize), Error> {
+    let records = mute::list_all_active(&data.pool).await?;
+    let mut reapplied = 0;
+    let mut cleared = 0;
+
+    for record in records {
+        if record.is_admin_mute {
+            continue;
+        }
 
-    Ok(active_mute.is_some())
+        let guild_id = GuildId::new(record.guild_id as u64);
+        let user_id = UserId::new(record.muted_user_id as u64);
+
+        // Current voice state from cache: (channel, server muted)
+        // A guild that isn't cached yet (e.g. still unavailable) can't tell us whether they left
+        let voice_state = match ctx.cache.guild(guild_id) {
+            Some(g) => g
+                .voice_states
+                .get(&user_id)
+                .and_then(|vs| vs.channel_id.map(|c| (c, vs.mute))),
+            None => continue,
+        };
+
+        match voice_state {
+            Some((current_channel, is_muted)) if current_channel.get() as i64 == record.channel_id => {
+                // Still in the channel they were muted in - make sure the mute stuck
+                if !is_muted {
+                    let deafen = record.is_deafened.then_some(true);
+                    if let Err(e) = apply_voice_state(ctx, guild_id, user_id, true, deafen).await {
+                        warn!("Failed to re-apply mute to user {}: {:?}", user_id, e);
+                        continue;
+                    }
+                    reapplied += 1;
+                    debug!(
+                        "Re-applied mute to user {} in channel {} after downtime",
+                        user_id, current_channel
+                    );
+                }
+            }
+            // Disconnected users keep the mute, like anyone who leaves a muted room; their next
+            // join re-applies it, or lifts it if the room is gone
+            None => {}
+            other => {
+                // User moved while we were offline - the local mute is stale
+                if let Err(e) = mute::unmute(&data.pool, record.id).await {
+                    warn!("Failed to clear stale mute for user {}: {:?}", user_id, e);
+                    continue;
+                }
+                cleared += 1;
+                debug!(
+                    "Cleared stale mute for user {} in channel {} (user no longer there)",
+                    user_id, record.channel_id
+                );
+
+                // Nothing to lift unless they are still server-muted
+                let Some((current_channel, true)) = other else {
+                    continue;
+                };
+
+                // Keep the server mute whenever we can't confirm nothing else holds it
+                let globally_muted =
+                    global_mute::is_globally_muted(&data.pool, record.guild_id, record.muted_user_id)
+                        .await;
+                match globally_muted {
+                    Ok(false) => {}
+                    Ok(true) => continue,
+                    Err(e) => {
+                        warn!("Failed to check global mute for user {}: {:?}", user_id, e);
+                        continue;
+                    }
+                }
+
+                let muted_here =
+                    mute::get_active_mute(&data.pool, current_channel.get() as i64, record.muted_user_id)
+                        .await;
+                match muted_here {
+                    Ok(None) => {}
+                    Ok(Some(_)) => continue,
+                    Err(e) => {
+                        warn!("Failed to check mutes for user {} in {}: {:?}", user_id, current_channel, e);
+                        continue;
+                    }
+                }
+
+                data.mark_pending_unmute(guild_id.get(), user_id.get());
+                let undeafen = record.is_deafened.then_some(false);
+                if let Err(e) = apply_voice_state(ctx, guild_id, user_id, false, undeafen).await {
+                    warn!("Failed to lift stale mute for user {}: {:?}", user_id, e);
+                }
+            }
+        }
+    }
+
+    Ok((reapplied, cleared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::ChannelType;
+    use crate::db::repository::memory::InMemoryRepo;
+
+    #[test]
+    fn mute_details_describe_the_mute() {
+        assert_eq!(mute_details(false, false, None), None);
+        assert_eq!(
+            mute_details(true, true, Some(Duration::from_secs(600))).as_deref(),
+            Some("admin mute, deafened, for 10m")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_remute_follows_active_mute() {
+        let repo = InMemoryRepo::new();
+        let channel_id = ChannelId::new(10);
+        let user_id = UserId::new(20);
+
+        assert!(should_remute(&repo, channel_id, user_id).await.unwrap().is_none());
+
+        repo.create_mute(1, 10, 20, 30, false, false, None).await.unwrap();
+        assert!(should_remute(&repo, channel_id, user_id).await.unwrap().is_some());
+        // A mute in one channel does not carry over to another
+        assert!(should_remute(&repo, ChannelId::new(11), user_id).await.unwrap().is_none());
+
+        repo.unmute_by_channel_user(10, 20).await.unwrap();
+        assert!(should_remute(&repo, channel_id, user_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_remute_carries_deafen() {
+        let repo = InMemoryRepo::new();
+        repo.create_mute(1, 10, 20, 30, false, true, None).await.unwrap();
+
+        let record = should_remute(&repo, ChannelId::new(10), UserId::new(20)).await.unwrap();
+        assert!(record.is_some_and(|r| r.is_deafened));
+    }
+
+    #[tokio::test]
+    async fn test_rejoin_within_grace_cancels_unmute() {
+        let pending = PendingUnmutes::default();
+        let unmuted = Arc::new(AtomicBool::new(false));
+
+        // Leave a channel muted (and deafened)...
+        let flag = Arc::clone(&unmuted);
+        pending.schedule(1, 20, true, Duration::from_millis(50), async move {
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        // ...and rejoin a muted channel before the grace runs out
+        assert_eq!(pending.cancel(1, 20), Some(true));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!unmuted.load(Ordering::SeqCst));
+
+        // Nothing left to cancel
+        assert_eq!(pending.cancel(1, 20), None);
+    }
+
+    #[tokio::test]
+    async fn test_unmute_runs_after_grace() {
+        let pending = PendingUnmutes::default();
+        let unmuted = Arc::new(AtomicBool::new(false));
+
+        let flag = Arc::clone(&unmuted);
+        pending.schedule(1, 20, false, Duration::from_millis(10), async move {
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(unmuted.load(Ordering::SeqCst));
+        // A rejoin after the unmute fired must re-apply the mute itself
+        assert_eq!(pending.cancel(1, 20), None);
+    }
+
+    #[tokio::test]
+    async fn test_should_remute_only_the_muted_user() {
+        let repo = InMemoryRepo::new();
+        repo.create_mute(1, 10, 20, 30, false, false, None).await.unwrap();
+
+        // Someone else joining the same channel isn't muted
+        assert!(should_remute(&repo, ChannelId::new(10), UserId::new(21)).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_remute_skips_run_out_mute() {
+        let repo = InMemoryRepo::new();
+        let ended = Utc::now() - chrono::Duration::minutes(1);
+        repo.create_mute(1, 10, 20, 30, false, false, Some(ended)).await.unwrap();
+
+        assert!(should_remute(&repo, ChannelId::new(10), UserId::new(20)).await.unwrap().is_none());
+
+        let later = Utc::now() + chrono::Duration::minutes(10);
+        repo.create_mute(1, 11, 20, 30, false, false, Some(later)).await.unwrap();
+        assert!(should_remute(&repo, ChannelId::new(11), UserId::new(20)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_close_expired_mutes_closes_each_once() {
+        let repo = InMemoryRepo::new();
+        let ended = Utc::now() - chrono::Duration::minutes(1);
+        let later = Utc::now() + chrono::Duration::minutes(10);
+        repo.create_mute(1, 10, 20, 30, false, false, Some(ended)).await.unwrap();
+        repo.create_mute(1, 10, 21, 30, false, false, Some(later)).await.unwrap();
+        repo.create_mute(1, 10, 22, 30, false, false, None).await.unwrap();
+        // Unmuted by hand before the expiry check got to it
+        repo.create_mute(1, 11, 23, 30, false, false, Some(ended)).await.unwrap();
+        repo.unmute_by_channel_user(11, 23).await.unwrap();
+
+        let closed = close_expired_mutes(&repo).await.unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].muted_user_id, 20);
+        assert!(repo.get_active_mute(10, 20).await.unwrap().is_none());
+        assert!(repo.get_active_mute(10, 21).await.unwrap().is_some());
+
+        // The next check finds nothing left to close
+        assert!(close_expired_mutes(&repo).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_close_channel_mutes_keeps_admin_mutes() {
+        let repo = InMemoryRepo::new();
+        repo.create_mute(1, 10, 20, 30, false, true, None).await.unwrap();
+        repo.create_mute(1, 10, 21, 30, true, false, None).await.unwrap();
+        repo.create_mute(1, 11, 20, 30, false, false, None).await.unwrap();
+
+        let closed = close_channel_mutes(&repo, ChannelId::new(10), |_| true).await.unwrap();
+        assert_eq!(closed.len(), 1);
+        assert!(closed[0].is_deafened);
+
+        // The admin mute and the mute in another room stay
+        assert!(repo.get_active_mute(10, 21).await.unwrap().is_some());
+        assert!(repo.get_active_mute(11, 20).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_close_channel_mutes_keeps_disconnected_users() {
+        let repo = InMemoryRepo::new();
+        repo.create_mute(1, 10, 20, 30, false, false, None).await.unwrap();
+        repo.create_mute(1, 10, 21, 30, false, false, None).await.unwrap();
+
+        let connected = |user_id: UserId| user_id == UserId::new(20);
+        let closed = close_channel_mutes(&repo, ChannelId::new(10), connected).await.unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].muted_user_id, 20);
+
+        // Kept open so their server mute is lifted when they next join
+        assert!(repo.get_active_mute(10, 21).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_orphaned_mutes_only_from_deleted_rooms() {
+        let repo = InMemoryRepo::new();
+        let (guild_id, user_id) = (GuildId::new(1), UserId::new(20));
+        repo.create_channel(10, 1, 30, ChannelType::Casual, None, &[]).await.unwrap();
+        repo.create_channel(11, 1, 30, ChannelType::Casual, None, &[]).await.unwrap();
+        repo.create_mute(1, 10, 20, 30, false, false, None).await.unwrap();
+        repo.create_mute(1, 11, 20, 30, false, true, None).await.unwrap();
+        // Admin mutes are a moderator's to lift
+        repo.create_mute(1, 12, 20, 30, true, false, None).await.unwrap();
+
+        assert!(orphaned_mutes(&repo, guild_id, user_id).await.unwrap().is_empty());
+
+        repo.delete_channel(11).await.unwrap();
+        let orphaned = orphaned_mutes(&repo, guild_id, user_id).await.unwrap();
+        assert_eq!(orphaned.len(), 1);
+        assert_eq!(orphaned[0].channel_id, 11);
+        assert!(orphaned[0].is_deafened);
+
+        // Other guilds aren't looked at
+        assert!(orphaned_mutes(&repo, GuildId::new(2), user_id).await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{ChannelId, Context, CreateMessage, GuildId, Http, MessageId, UserId};
+use tracing::{debug, warn};
+
+use crate::bot::data::Data;
+use crate::constants::embeds;
+use crate::constants::timeouts::ROOM_NOTICE_SECONDS;
+use crate::db::queries::guild_config;
+
+/// Owner moderation actions that can be announced in the room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomAction {
+    Mute,
+    Unmute,
+    Ban,
+    Unban,
+    Kick,
+}
+
+impl RoomAction {
+    fn describe(&self, user_id: UserId) -> String {
+        match self {
+            RoomAction::Mute => format!("🔇 <@{}> was muted by the room owner", user_id),
+            RoomAction::Unmute => format!("🔊 <@{}> was unmuted by the room owner", user_id),
+            RoomAction::Ban => format!("🚫 <@{}> was banned from this room by its owner", user_id),
+            RoomAction::Unban => format!("✅ <@{}> was unbanned from this room by its owner", user_id),
+            RoomAction::Kick => format!("👢 <@{}> was kicked from this room by its owner", user_id),
+        }
+    }
+}
+
+/// Post a short-lived notice of an owner action in the room's text chat, if the guild enabled it
+/// Best-effort: failures are logged and never affect the action itself
+pub async fn announce(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    action: RoomAction,
+    user_id: UserId,
+) {
+    let enabled = match guild_config::get(&data.pool, guild_id.get() as i64).await {
+        Ok(config) => config.is_some_and(|c| c.announce_room_mod_actions),
+        Err(e) => {
+            warn!("Failed to load guild config for room notice: {:?}", e);
+            false
+        }
+    };
+    if !enabled {
+        return;
+    }
+
+    let embed = embeds::secondary_embed().description(action.describe(user_id));
+    match channel_id
+        .send_message(ctx, CreateMessage::new().embed(embed))
+        .await
+    {
+        Ok(message) => delete_after(
+            ctx.http.clone(),
+            channel_id,
+            message.id,
+            Duration::from_secs(ROOM_NOTICE_SECONDS),
+        ),
+        Err(e) => debug!("Could not post room notice in {}: {:?}", channel_id, e),
+    }
+}
+
+/// Delete a message after `delay` in the background
+pub fn delete_after(http: Arc<Http>, channel_id: ChannelId, message_id: MessageId, delay: Duration) {
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Err(e) = channel_id.delete_message(&http, message_id).await {
+            debug!("Could not delete message {} in {}: {:?}", message_id, channel_id, e);
+        }
+    });
+}
@@ -0,0 +1,165 @@
+//! Detects sustained Discord rate limiting so non-critical work can back off
+//! Fed by serenity's ratelimit events and by 429 responses surfacing from key service calls.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude as serenity;
+use tracing::warn;
+
+use crate::bot::error::Error;
+use crate::constants::timeouts::{
+    RATE_LIMIT_HITS_TO_THROTTLE, RATE_LIMIT_THROTTLE_SECONDS, RATE_LIMIT_WINDOW_SECONDS,
+};
+
+/// Recent rate limit hits and whether the bot is currently throttling itself
+#[derive(Debug, Default)]
+pub struct RateLimitMonitor {
+    recent_hits: Mutex<VecDeque<Instant>>,
+    throttled_until: Mutex<Option<Instant>>,
+    total_hits: AtomicU64,
+}
+
+/// Point-in-time view of `RateLimitMonitor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Time left on the current throttle, if any
+    pub throttled_for: Option<Duration>,
+    pub recent_hits: usize,
+    pub total_hits: u64,
+}
+
+impl RateLimitMonitor {
+    /// Record a rate limit hit
+    /// A global limit throttles right away; route limits only once they pile up inside the window
+    pub fn record_hit(&self, global: bool, retry_after: Duration) {
+        self.record_hit_at(Instant::now(), global, retry_after);
+    }
+
+    fn record_hit_at(&self, now: Instant, global: bool, retry_after: Duration) {
+        self.total_hits.fetch_add(1, Ordering::Relaxed);
+
+        let recent = {
+            let mut hits = self.recent_hits.lock().unwrap_or_else(|e| e.into_inner());
+            hits.push_back(now);
+            prune_hits(&mut hits, now);
+            hits.len()
+        };
+
+        if global || recent >= RATE_LIMIT_HITS_TO_THROTTLE {
+            let throttle = retry_after.max(Duration::from_secs(RATE_LIMIT_THROTTLE_SECONDS));
+            let until = now + throttle;
+
+            let mut throttled_until = self.throttled_until.lock().unwrap_or_else(|e| e.into_inner());
+            let was_throttled = throttled_until.is_some_and(|t| t > now);
+            if throttled_until.is_none_or(|t| t < until) {
+                *throttled_until = Some(until);
+            }
+            if !was_throttled {
+                warn!(
+                    "Rate limited by Discord ({} hits in {}s, global: {}), throttling background work for {}s",
+                    recent,
+                    RATE_LIMIT_WINDOW_SECONDS,
+                    global,
+                    throttle.as_secs()
+                );
+            }
+        }
+    }
+
+    /// Record a failed call, returning true if it failed because of a rate limit
+    pub fn record_error(&self, error: &Error) -> bool {
+        let limited = is_rate_limit_error(error);
+        if limited {
+            self.record_hit(false, Duration::ZERO);
+        }
+        limited
+    }
+
+    /// Whether non-critical work should currently back off
+    pub fn is_throttled(&self) -> bool {
+        self.throttled_for_at(Instant::now()).is_some()
+    }
+
+    fn throttled_for_at(&self, now: Instant) -> Option<Duration> {
+        let throttled_until = self.throttled_until.lock().unwrap_or_else(|e| e.into_inner());
+        throttled_until.and_then(|t| t.checked_duration_since(now)).filter(|d| !d.is_zero())
+    }
+
+    pub fn snapshot(&self) -> RateLimitStatus {
+        let now = Instant::now();
+        let recent_hits = {
+            let mut hits = self.recent_hits.lock().unwrap_or_else(|e| e.into_inner());
+            prune_hits(&mut hits, now);
+            hits.len()
+        };
+        RateLimitStatus {
+            throttled_for: self.throttled_for_at(now),
+            recent_hits,
+            total_hits: self.total_hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Drop hits that fell out of the detection window
+fn prune_hits(hits: &mut VecDeque<Instant>, now: Instant) {
+    let window = Duration::from_secs(RATE_LIMIT_WINDOW_SECONDS);
+    while hits.front().is_some_and(|&t| now.duration_since(t) > window) {
+        hits.pop_front();
+    }
+}
+
+/// Whether an error is Discord answering 429 Too Many Requests
+pub fn is_rate_limit_error(error: &Error) -> bool {
+    match error {
+        Error::Serenity(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response))) => {
+            response.status_code.as_u16() == 429
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_after_repeated_route_hits() {
+        let monitor = RateLimitMonitor::default();
+        let now = Instant::now();
+
+        for _ in 1..RATE_LIMIT_HITS_TO_THROTTLE {
+            monitor.record_hit_at(now, false, Duration::from_secs(1));
+        }
+        assert!(monitor.throttled_for_at(now).is_none());
+
+        monitor.record_hit_at(now, false, Duration::from_secs(1));
+        assert_eq!(
+            monitor.throttled_for_at(now),
+            Some(Duration::from_secs(RATE_LIMIT_THROTTLE_SECONDS))
+        );
+        assert!(monitor
+            .throttled_for_at(now + Duration::from_secs(RATE_LIMIT_THROTTLE_SECONDS))
+            .is_none());
+    }
+
+    #[test]
+    fn global_hit_throttles_for_retry_after() {
+        let monitor = RateLimitMonitor::default();
+        let now = Instant::now();
+        let long = Duration::from_secs(RATE_LIMIT_THROTTLE_SECONDS * 2);
+
+        monitor.record_hit_at(now, true, long);
+        assert_eq!(monitor.throttled_for_at(now), Some(long));
+        assert_eq!(monitor.snapshot().total_hits, 1);
+    }
+
+    #[test]
+    fn other_errors_are_not_rate_limits() {
+        let monitor = RateLimitMonitor::default();
+        assert!(!monitor.record_error(&Error::custom("boom")));
+        assert_eq!(monitor.snapshot().total_hits, 0);
+    }
+}
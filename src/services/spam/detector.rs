@@ -9,8 +9,12 @@ use tracing::{info, warn};
 use crate::bot::data::Data;
 use crate::bot::error::Error;
 use crate::components::spam_prompt;
-use crate::db::queries::spam;
+use crate::db::models::SpamPromptTarget;
+use crate::db::queries::{guild_config, spam};
+use crate::constants::timeouts::SPAM_PROMPT_COOLDOWN_SECONDS;
+use crate::services::moderation::mod_log::{self, LogAction, LogEntry};
 use crate::services::spam::timeout_calculator;
+use crate::utils::duration::format_short;
 
 /// Tracks join/leave activity for spam detection
 pub struct ActivityTracker {
@@ -18,6 +22,8 @@ pub struct ActivityTracker {
     activity: DashMap<u64, DashMap<u64, VecDeque<Instant>>>,
     /// channel_id -> set of users who have been prompted (to avoid duplicate prompts)
     prompted: DashMap<u64, DashMap<u64, Instant>>,
+    /// channel_id -> timestamps of joins by anyone, for flood detection
+    channel_joins: DashMap<u64, VecDeque<Instant>>,
 }
 
 impl ActivityTracker {
@@ -25,6 +31,7 @@ impl ActivityTracker {
         Self {
             activity: DashMap::new(),
             prompted: DashMap::new(),
+            channel_joins: DashMap::new(),
         }
     }
 
@@ -85,8 +92,7 @@ impl ActivityTracker {
             if let Some(time_ref) = channel_prompts.get(&user_id) {
                 let elapsed = time_ref.elapsed();
                 drop(time_ref);
-                // Consider a prompt recent if within the last 5 minutes
-                return elapsed < Duration::from_secs(300);
+                return elapsed < Duration::from_secs(SPAM_PROMPT_COOLDOWN_SECONDS);
             }
         }
         false
@@ -98,10 +104,64 @@ impl ActivityTracker {
         channel_prompts.insert(user_id, Instant::now());
     }
 
+    /// Record a join to a channel by anyone, returning the joins within the window
+    pub fn record_channel_join(&self, channel_id: u64, window_seconds: u64) -> u32 {
+        let mut joins = self.channel_joins.entry(channel_id).or_default();
+
+        let now = Instant::now();
+        let window = Duration::from_secs(window_seconds);
+        while joins.front().is_some_and(|t| now.duration_since(*t) > window) {
+            joins.pop_front();
+        }
+
+        joins.push_back(now);
+        joins.len() as u32
+    }
+
+    /// Forget a channel's join history (e.g. once a flood has been dealt with)
+    pub fn reset_channel_joins(&self, channel_id: u64) {
+        self.channel_joins.remove(&channel_id);
+    }
+
+    /// Drop activity older than `max_age` and expired prompt cooldowns, returning how many entries went
+    pub fn prune(&self, max_age: Duration) -> usize {
+        let now = Instant::now();
+        let mut removed = 0;
+
+        for channel in self.activity.iter() {
+            let before = channel.len();
+            channel.retain(|_, events| {
+                // Trimmed here too, since a user who never comes back never trims their own
+                while events.front().is_some_and(|t| now.duration_since(*t) > max_age) {
+                    events.pop_front();
+                }
+                !events.is_empty()
+            });
+            removed += before - channel.len();
+        }
+        self.activity.retain(|_, channel| !channel.is_empty());
+
+        let cooldown = Duration::from_secs(SPAM_PROMPT_COOLDOWN_SECONDS);
+        for channel in self.prompted.iter() {
+            let before = channel.len();
+            channel.retain(|_, at| now.duration_since(*at) < cooldown);
+            removed += before - channel.len();
+        }
+        self.prompted.retain(|_, channel| !channel.is_empty());
+
+        let before = self.channel_joins.len();
+        self.channel_joins
+            .retain(|_, joins| joins.back().is_some_and(|t| now.duration_since(*t) <= max_age));
+        removed += before - self.channel_joins.len();
+
+        removed
+    }
+
     /// Clean up data for a deleted channel
     pub fn cleanup_channel(&self, channel_id: u64) {
         self.activity.remove(&channel_id);
         self.prompted.remove(&channel_id);
+        self.channel_joins.remove(&channel_id);
     }
 }
 
@@ -119,9 +179,18 @@ pub async fn check_spam(
     channel_id: ChannelId,
     owner_id: UserId,
 ) -> Result<(), Error> {
-    let prompt_threshold = data.settings.spam_prompt_threshold;
-    let timeout_threshold = data.settings.spam_timeout_threshold;
-    let window_seconds = data.settings.spam_window_seconds;
+    if !data.activity_tracker.activity.contains_key(&channel_id.get()) {
+        return Ok(());
+    }
+
+    // The guild's own sensitivity, falling back to the bot-wide settings
+    let config = guild_config::get(&data.pool, guild_id.get() as i64).await?;
+    let thresholds = config.as_ref().map_or(data.settings.spam_thresholds(), |c| {
+        c.spam_thresholds(data.settings.spam_thresholds())
+    });
+    let prompt_target = config
+        .as_ref()
+        .map_or(SpamPromptTarget::Channel, |c| c.spam_prompt_target());
 
     // Get all users with high activity in this channel
     let channel_map = match data.activity_tracker.activity.get(&channel_id.get()) {
@@ -131,16 +200,28 @@ pub async fn check_spam(
 
     for entry in channel_map.iter() {
         let user_id = *entry.key();
-        let count = data.activity_tracker.get_activity_count(channel_id.get(), user_id, window_seconds);
+        let count = data.activity_tracker.get_activity_count(
+            channel_id.get(),
+            user_id,
+            thresholds.window_seconds,
+        );
 
-        if count >= timeout_threshold {
+        if count >= thresholds.timeout {
             // Apply progressive timeout
-            handle_spam_timeout(ctx, data, guild_id, UserId::new(user_id)).await?;
-        } else if count >= prompt_threshold {
+            handle_spam_timeout(ctx, data, guild_id, channel_id, UserId::new(user_id)).await?;
+        } else if count >= thresholds.prompt {
             // Prompt owner if not already prompted
             if !data.activity_tracker.was_recently_prompted(channel_id.get(), user_id) {
-                spam_prompt::send_prompt(ctx, data, channel_id, owner_id, UserId::new(user_id))
-                    .await?;
+                spam_prompt::send_prompt(
+                    ctx,
+                    data,
+                    guild_id,
+                    channel_id,
+                    owner_id,
+                    UserId::new(user_id),
+                    prompt_target,
+                )
+                .await?;
                 data.activity_tracker.mark_prompted(channel_id.get(), user_id);
             }
         }
@@ -154,6 +235,7 @@ async fn handle_spam_timeout(
     ctx: &Context,
     data: &Arc<Data>,
     guild_id: GuildId,
+    channel_id: ChannelId,
     user_id: UserId,
 ) -> Result<(), Error> {
     // Get or create spam record and increment level
@@ -182,7 +264,61 @@ async fn handle_spam_timeout(
         .await
     {
         warn!("Failed to apply timeout to user {}: {:?}", user_id, e);
+        return Ok(());
     }
 
+    let entry = LogEntry {
+        action: LogAction::SpamTimeout,
+        actor: None,
+        target: user_id,
+        channel_id: Some(channel_id),
+        reason: Some(format!(
+            "Join spam, timeout level {} ({})",
+            record.current_timeout_level,
+            format_short(duration)
+        )),
+    };
+    mod_log::record(ctx, data, guild_id, entry).await;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_drops_idle_users_and_empty_channels() {
+        let tracker = ActivityTracker::new();
+        let max_age = Duration::from_secs(60);
+        let old = Instant::now() - max_age * 2;
+
+        let stale: DashMap<u64, VecDeque<Instant>> = DashMap::new();
+        stale.insert(1, VecDeque::from([old, old]));
+        tracker.activity.insert(10, stale);
+
+        let mixed: DashMap<u64, VecDeque<Instant>> = DashMap::new();
+        mixed.insert(2, VecDeque::from([old, Instant::now()]));
+        mixed.insert(3, VecDeque::from([old]));
+        tracker.activity.insert(11, mixed);
+
+        assert_eq!(tracker.prune(max_age), 2);
+        assert!(!tracker.activity.contains_key(&10));
+        let channel = tracker.activity.get(&11).unwrap();
+        assert_eq!(channel.len(), 1);
+        assert_eq!(channel.get(&2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cleanup_forgets_a_channel() {
+        let tracker = ActivityTracker::new();
+        tracker.record_join(10, 1, 60);
+        tracker.mark_prompted(10, 1);
+        tracker.record_channel_join(10, 60);
+
+        tracker.cleanup_channel(10);
+        assert_eq!(tracker.get_activity_count(10, 1, 60), 0);
+        assert!(!tracker.was_recently_prompted(10, 1));
+        assert!(!tracker.channel_joins.contains_key(&10));
+    }
+}
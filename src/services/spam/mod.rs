@@ -1,2 +1,4 @@
 pub mod detector;
+pub mod raid_guard;
 pub mod timeout_calculator;
+pub mod timeout_decay;
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use serenity::all::{
+    ButtonStyle, ChannelId, Context, CreateActionRow, CreateButton, CreateMessage, GuildId, UserId,
+};
+use tracing::{debug, warn};
+
+use crate::bot::data::Data;
+use crate::bot::error::Error;
+use crate::constants::embeds;
+use crate::constants::timeouts::RAID_LOCK_WINDOW_SECONDS;
+use crate::db::queries::voice_channel;
+use crate::services::jtc::channel_lock;
+
+/// Count a join and lock the room if the guild's flood threshold was reached
+/// The owner is pinged in the room with a button to unlock it
+pub async fn check_join_flood(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) -> Result<(), Error> {
+    let joins = data
+        .activity_tracker
+        .record_channel_join(channel_id.get(), RAID_LOCK_WINDOW_SECONDS);
+
+    if data.raid_locked.contains_key(&channel_id.get()) {
+        return Ok(());
+    }
+
+    let threshold = match data
+        .guild_config(guild_id.get())
+        .await?
+        .and_then(|config| config.raid_lock_joins)
+    {
+        Some(threshold) if threshold > 0 => threshold as u32,
+        _ => return Ok(()),
+    };
+    if joins < threshold {
+        return Ok(());
+    }
+
+    // Claim the lock so concurrent joins don't all try to apply it
+    if data.raid_locked.insert(channel_id.get(), Instant::now()).is_some() {
+        return Ok(());
+    }
+
+    if let Err(e) = channel_lock::set_connect_denied(ctx, guild_id, channel_id, true).await {
+        data.raid_locked.remove(&channel_id.get());
+        return Err(e);
+    }
+    // Stored so the lock can still be lifted after a restart
+    if let Err(e) = voice_channel::set_raid_locked(&data.pool, channel_id.get() as i64, true).await {
+        warn!("Failed to record raid lock on channel {}: {:?}", channel_id, e);
+    }
+
+    warn!(
+        "Auto-locked channel {} after {} joins in {}s",
+        channel_id, joins, RAID_LOCK_WINDOW_SECONDS
+    );
+
+    let embed = embeds::warning_embed()
+        .title("Room Locked")
+        .description(format!(
+            "This room had {} joins in {} seconds, so it's been locked to new joins. \
+            People already here can stay. Unlock it when things calm down.",
+            joins, RAID_LOCK_WINDOW_SECONDS
+        ));
+    let button = CreateActionRow::Buttons(vec![CreateButton::new(format!(
+        "raidlock_unlock_{}",
+        channel_id
+    ))
+    .label("Unlock Room")
+    .style(ButtonStyle::Secondary)]);
+
+    let message = CreateMessage::new()
+        .content(format!("<@{}>", owner_id))
+        .embed(embed)
+        .components(vec![button]);
+    if let Err(e) = channel_id.send_message(ctx, message).await {
+        debug!("Could not post raid lock notice in {}: {:?}", channel_id, e);
+    }
+
+    Ok(())
+}
+
+/// Lift an auto-lock, returning false if the room wasn't locked
+pub async fn unlock(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<bool, Error> {
+    let id = channel_id.get() as i64;
+    let vc = voice_channel::get(&data.pool, id).await?;
+    let remembered = data.raid_locked.remove(&channel_id.get()).is_some();
+    if !remembered && !vc.as_ref().is_some_and(|vc| vc.raid_locked) {
+        return Ok(false);
+    }
+
+    // An owner's own lock stays in place
+    if !vc.is_some_and(|vc| vc.locked) {
+        channel_lock::set_connect_denied(ctx, guild_id, channel_id, false).await?;
+    }
+    voice_channel::set_raid_locked(&data.pool, id, false).await?;
+    // Start counting afresh so the joins that caused the lock don't re-trigger it
+    data.activity_tracker.reset_channel_joins(channel_id.get());
+
+    Ok(true)
+}
@@ -33,6 +33,24 @@ pub fn max_level() -> u32 {
     (TIMEOUT_DURATIONS.len() - 1) as u32
 }
 
+/// A manual change to a user's timeout level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelChange {
+    Set(u32),
+    Add(i32),
+    Reset,
+}
+
+/// Apply a manual change to a timeout level, clamped to the valid levels
+pub fn adjust_level(current: u32, change: LevelChange) -> u32 {
+    let level = match change {
+        LevelChange::Set(level) => level as i64,
+        LevelChange::Add(delta) => current as i64 + delta as i64,
+        LevelChange::Reset => 0,
+    };
+    level.clamp(0, max_level() as i64) as u32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,4 +72,13 @@ mod tests {
         assert_eq!(format_timeout_level(5), "1 week");
         assert_eq!(format_timeout_level(6), "2 weeks");
     }
+
+    #[test]
+    fn manual_changes_stay_in_range() {
+        assert_eq!(adjust_level(2, LevelChange::Add(3)), 5);
+        assert_eq!(adjust_level(2, LevelChange::Add(-5)), 0);
+        assert_eq!(adjust_level(6, LevelChange::Add(4)), max_level());
+        assert_eq!(adjust_level(1, LevelChange::Set(100)), max_level());
+        assert_eq!(adjust_level(4, LevelChange::Reset), 0);
+    }
 }
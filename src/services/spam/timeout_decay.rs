@@ -0,0 +1,33 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::AbortHandle;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, error, info};
+
+use crate::bot::data::Data;
+use crate::constants::timeouts::{TIMEOUT_DECAY_INTERVAL_SECONDS, TIMEOUT_RESET_DAYS};
+use crate::db::queries::spam;
+
+/// Periodically reset the timeout level of users with no infraction in `TIMEOUT_RESET_DAYS`
+/// Repeat offenders still escalate, but someone who reformed starts over at the shortest timeout
+pub fn spawn_timeout_decay(data: Arc<Data>) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(TIMEOUT_DECAY_INTERVAL_SECONDS));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            match spam::reset_stale_timeout_levels(&data.pool, TIMEOUT_RESET_DAYS).await {
+                Ok(0) => debug!("No stale timeout levels to reset"),
+                Ok(reset) => info!(
+                    "Reset the timeout level of {} user(s) after {} days without an infraction",
+                    reset, TIMEOUT_RESET_DAYS
+                ),
+                Err(e) => error!("Failed to reset stale timeout levels: {:?}", e),
+            }
+        }
+    })
+    .abort_handle()
+}
@@ -29,6 +29,7 @@ struct StatBar {
 }
 
 /// Generate a modern PNG chart showing user statistics
+#[allow(clippy::result_large_err)]
 pub fn generate_user_stats_chart(stats: &UserStats, username: &str) -> Result<Vec<u8>, Error> {
     let mut buffer = vec![0u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
 
@@ -144,6 +145,7 @@ pub fn generate_user_stats_chart(stats: &UserStats, username: &str) -> Result<Ve
 }
 
 /// Generate a modern bar chart for guild statistics
+#[allow(clippy::result_large_err)]
 pub fn generate_guild_stats_chart(
     total_mutes: i64,
     total_bans: i64,
@@ -0,0 +1,184 @@
+//! Human-friendly duration parsing for timed moderation actions
+//! Accepts values like "10m", "2h", "1d" and combinations such as "1h30m" or "1d 12h"
+
+use std::time::Duration;
+
+/// Longest duration Discord allows for a member timeout (28 days)
+pub const MAX_MODERATION_DURATION: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+/// Parse a duration made of `<number><unit>` parts, where the unit is s, m, h, d or w
+/// Returns None for empty, zero, malformed, repeated-unit or overflowing input
+pub fn parse_human(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    let mut seen_units = String::new();
+    let mut chars = s.chars().peekable();
+
+    while chars.peek().is_some() {
+        // Allow whitespace between parts ("1h 30m")
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let mut digits = String::new();
+        while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+            digits.push(c);
+            chars.next();
+        }
+        if digits.is_empty() {
+            return None;
+        }
+        let value: u64 = digits.parse().ok()?;
+
+        let unit = chars.next()?.to_ascii_lowercase();
+        let multiplier = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+
+        // "1h1h" is almost certainly a typo
+        if seen_units.contains(unit) {
+            return None;
+        }
+        seen_units.push(unit);
+
+        total = total.checked_add(value.checked_mul(multiplier)?)?;
+    }
+
+    if total == 0 {
+        return None;
+    }
+
+    Some(Duration::from_secs(total))
+}
+
+/// Parse a duration for a moderation command, with a user-facing error message
+pub fn parse_moderation_duration(s: &str) -> Result<Duration, String> {
+    let duration = parse_human(s).ok_or_else(|| {
+        format!(
+            "\"{}\" isn't a valid duration. Use something like `10m`, `2h`, `1d` or `1h30m`.",
+            s.trim()
+        )
+    })?;
+
+    if duration > MAX_MODERATION_DURATION {
+        return Err(format!(
+            "Durations can be at most {} days (Discord's limit).",
+            MAX_MODERATION_DURATION.as_secs() / (24 * 60 * 60)
+        ));
+    }
+
+    Ok(duration)
+}
+
+/// Format a duration compactly using its two largest units, e.g. "3d 4h", "12m 5s"
+pub fn format_short(duration: Duration) -> String {
+    const UNITS: [(u64, &str); 4] = [(86_400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+
+    let mut remaining = duration.as_secs();
+    let parts: Vec<String> = UNITS
+        .iter()
+        .filter_map(|&(size, unit)| {
+            let count = remaining / size;
+            remaining %= size;
+            (count > 0).then(|| format!("{}{}", count, unit))
+        })
+        .take(2)
+        .collect();
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Option<Duration> {
+        Some(Duration::from_secs(n))
+    }
+
+    #[test]
+    fn parses_single_units() {
+        assert_eq!(parse_human("45s"), secs(45));
+        assert_eq!(parse_human("10m"), secs(600));
+        assert_eq!(parse_human("2h"), secs(7200));
+        assert_eq!(parse_human("1d"), secs(86_400));
+        assert_eq!(parse_human("1w"), secs(604_800));
+    }
+
+    #[test]
+    fn parses_combinations() {
+        assert_eq!(parse_human("1h30m"), secs(5400));
+        assert_eq!(parse_human("1d12h"), secs(129_600));
+        assert_eq!(parse_human("1w2d3h4m5s"), secs(604_800 + 172_800 + 10_800 + 240 + 5));
+        // Order doesn't matter
+        assert_eq!(parse_human("30m1h"), secs(5400));
+    }
+
+    #[test]
+    fn tolerates_whitespace_and_case() {
+        assert_eq!(parse_human("  2H  "), secs(7200));
+        assert_eq!(parse_human("1h 30m"), secs(5400));
+        assert_eq!(parse_human("1D 2h"), secs(93_600));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_human(""), None);
+        assert_eq!(parse_human("   "), None);
+        assert_eq!(parse_human("10"), None); // missing unit
+        assert_eq!(parse_human("h"), None); // missing number
+        assert_eq!(parse_human("10x"), None); // unknown unit
+        assert_eq!(parse_human("1.5h"), None); // fractions
+        assert_eq!(parse_human("-5m"), None); // negatives
+        assert_eq!(parse_human("1h30"), None); // trailing number
+        assert_eq!(parse_human("1 h"), None); // space inside a part
+        assert_eq!(parse_human("1hh"), None);
+        assert_eq!(parse_human("1h1h"), None); // repeated unit
+        assert_eq!(parse_human("ten minutes"), None);
+    }
+
+    #[test]
+    fn rejects_zero() {
+        assert_eq!(parse_human("0s"), None);
+        assert_eq!(parse_human("0h0m"), None);
+        // A zero part is fine as long as the total isn't
+        assert_eq!(parse_human("0h5m"), secs(300));
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(parse_human("99999999999999999999s"), None); // doesn't fit in u64
+        assert_eq!(parse_human("18446744073709551615w"), None); // multiplication overflow
+        assert_eq!(parse_human("18446744073709551615s1m"), None); // addition overflow
+    }
+
+    #[test]
+    fn moderation_duration_enforces_discord_limit() {
+        assert_eq!(parse_moderation_duration("28d"), Ok(MAX_MODERATION_DURATION));
+        assert_eq!(parse_moderation_duration("4w"), Ok(MAX_MODERATION_DURATION));
+        assert!(parse_moderation_duration("28d1s").is_err());
+        assert!(parse_moderation_duration("5w").is_err());
+        assert!(parse_moderation_duration("soon").is_err());
+    }
+
+    #[test]
+    fn formats_short() {
+        assert_eq!(format_short(Duration::ZERO), "0s");
+        assert_eq!(format_short(Duration::from_secs(45)), "45s");
+        assert_eq!(format_short(Duration::from_secs(5400)), "1h 30m");
+        assert_eq!(format_short(Duration::from_secs(3 * 86_400 + 4 * 3600 + 59)), "3d 4h");
+    }
+}
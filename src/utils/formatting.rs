@@ -19,14 +19,12 @@ pub fn mention_role(role_id: u64) -> String {
 pub fn format_number(n: i64) -> String {
     let s = n.abs().to_string();
     let mut result = String::new();
-    let mut count = 0;
 
-    for c in s.chars().rev() {
+    for (count, c) in s.chars().rev().enumerate() {
         if count > 0 && count % 3 == 0 {
             result.push(',');
         }
         result.push(c);
-        count += 1;
     }
 
     if n < 0 {
@@ -59,3 +57,11 @@ pub fn format_tags(tags: &[String]) -> String {
             .join(" ")
     }
 }
+
+/// Fill `{name}` placeholders in a message template
+/// Unknown placeholders are left as-is
+pub fn fill_template(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
@@ -1,3 +1,4 @@
+pub mod duration;
 pub mod formatting;
 pub mod permissions;
 pub mod profanity;
@@ -1,11 +1,19 @@
 use serenity::all::{Context, GuildId, Member, Permissions, UserId};
 
+/// Resolve a member's guild-level permissions from the cache
+async fn member_permissions(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Option<Permissions> {
+    let member = guild_id.member(ctx, user_id).await.ok()?;
+    ctx.cache
+        .guild(guild_id)
+        .map(|guild| guild.member_permissions(&member))
+}
+
 /// Check if a member has administrator permissions
 pub async fn is_admin(ctx: &Context, guild_id: GuildId, user_id: UserId) -> bool {
-    if let Ok(member) = guild_id.member(ctx, user_id).await {
-        return member.permissions(ctx).map(|p| p.administrator()).unwrap_or(false);
-    }
-    false
+    member_permissions(ctx, guild_id, user_id)
+        .await
+        .map(|p| p.administrator())
+        .unwrap_or(false)
 }
 
 /// Check if a member has a specific permission
@@ -15,20 +23,18 @@ pub async fn has_permission(
     user_id: UserId,
     permission: Permissions,
 ) -> bool {
-    if let Ok(member) = guild_id.member(ctx, user_id).await {
-        return member.permissions(ctx).map(|p| p.contains(permission)).unwrap_or(false);
-    }
-    false
+    member_permissions(ctx, guild_id, user_id)
+        .await
+        .map(|p| p.contains(permission))
+        .unwrap_or(false)
 }
 
 /// Check if a member can moderate (has kick/ban permissions)
 pub async fn can_moderate(ctx: &Context, guild_id: GuildId, user_id: UserId) -> bool {
-    if let Ok(member) = guild_id.member(ctx, user_id).await {
-        return member.permissions(ctx)
-            .map(|p| p.administrator() || p.kick_members() || p.ban_members())
-            .unwrap_or(false);
-    }
-    false
+    member_permissions(ctx, guild_id, user_id)
+        .await
+        .map(|p| p.administrator() || p.kick_members() || p.ban_members())
+        .unwrap_or(false)
 }
 
 /// Check if a member is in a voice channel
@@ -37,3 +43,69 @@ pub fn is_in_voice_channel(_member: &Member, _channel_id: u64) -> bool {
     // For now, this is a placeholder that would be implemented with proper caching
     false
 }
+
+/// Permissions a room permission template may set, all of which apply to voice channels
+pub const ROOM_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::CONNECT)
+    .union(Permissions::SPEAK)
+    .union(Permissions::STREAM)
+    .union(Permissions::USE_VAD)
+    .union(Permissions::PRIORITY_SPEAKER)
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::EMBED_LINKS)
+    .union(Permissions::ATTACH_FILES)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::USE_EXTERNAL_EMOJIS)
+    .union(Permissions::USE_SOUNDBOARD)
+    .union(Permissions::USE_EMBEDDED_ACTIVITIES)
+    .union(Permissions::MUTE_MEMBERS)
+    .union(Permissions::DEAFEN_MEMBERS)
+    .union(Permissions::MOVE_MEMBERS);
+
+/// Parse a list of permission names like "connect, view_channel" into room permissions
+pub fn parse_room_permissions(input: &str) -> Result<Permissions, String> {
+    let mut permissions = Permissions::empty();
+    for name in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+    {
+        let permission = Permissions::from_name(&name.to_uppercase())
+            .filter(|p| ROOM_PERMISSIONS.contains(*p))
+            .ok_or_else(|| format!("Unknown or unsupported room permission: {}", name))?;
+        permissions |= permission;
+    }
+    Ok(permissions)
+}
+
+/// Permission names in the form `parse_room_permissions` accepts
+pub fn format_permissions(permissions: Permissions) -> String {
+    if permissions.is_empty() {
+        return "none".to_string();
+    }
+    permissions
+        .iter_names()
+        .map(|(name, _)| name.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_room_permission_names() {
+        assert_eq!(
+            parse_room_permissions("connect, View_Channel  speak").unwrap(),
+            Permissions::CONNECT | Permissions::VIEW_CHANNEL | Permissions::SPEAK
+        );
+        assert_eq!(parse_room_permissions("").unwrap(), Permissions::empty());
+        assert!(parse_room_permissions("administrator").is_err());
+        assert!(parse_room_permissions("fly").is_err());
+        assert_eq!(
+            format_permissions(Permissions::CONNECT | Permissions::SPEAK),
+            "connect, speak"
+        );
+    }
+}
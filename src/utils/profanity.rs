@@ -1,8 +1,25 @@
-/// Profanity filter for channel names
-/// Based on common profanity lists to prevent Discord TOS violations
+//! Profanity filter for channel names
+//! Based on common profanity lists to prevent Discord TOS violations
 
 use std::collections::HashSet;
+use std::sync::Arc;
+
+use aho_corasick::AhoCorasick;
+use dashmap::DashMap;
 use once_cell::sync::Lazy;
+use tracing::{debug, info};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Discord's hard limit on channel name length
+pub const MAX_CHANNEL_NAME_LENGTH: usize = 100;
+/// Shortest channel name accepted
+pub const MIN_CHANNEL_NAME_LENGTH: usize = 2;
+/// Bounds on the words a guild can add to the filter
+pub const MIN_BANNED_WORD_LENGTH: usize = 2;
+pub const MAX_BANNED_WORD_LENGTH: usize = 32;
+/// Most words a guild can add to the filter
+pub const MAX_GUILD_BANNED_WORDS: usize = 100;
 
 /// Common profanity/slurs that violate Discord TOS
 /// This list includes English profanity - extend as needed
@@ -130,65 +147,326 @@ static BAD_WORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     .collect()
 });
 
+/// Separators and leetspeak characters stripped before matching
+const EVASION_CHARS: [char; 13] = ['_', '-', '.', ' ', '0', '1', '3', '4', '5', '@', '!', '$', '#'];
+/// Leetspeak characters stripped from list entries to match evasion-stripped text
+const LEET_CHARS: [char; 9] = ['0', '1', '3', '4', '5', '@', '!', '$', '#'];
+/// Entries shorter than this only match as whole words, to avoid false positives
+const MIN_SUBSTRING_WORD_LENGTH: usize = 4;
+
+/// A word list compiled for matching
+/// Whole words are looked up directly; longer entries are also found inside other text
+/// with one automaton pass instead of scanning the text once per entry.
+struct Matcher {
+    substrings: AhoCorasick,
+    /// The list entry each automaton pattern came from, by pattern index
+    words: Vec<String>,
+}
+
+impl Matcher {
+    /// Compile the entries long enough to be matched inside other text
+    fn new<'a>(entries: impl IntoIterator<Item = &'a str>) -> Self {
+        let (words, patterns): (Vec<String>, Vec<String>) = entries
+            .into_iter()
+            .filter(|word| word.len() >= MIN_SUBSTRING_WORD_LENGTH)
+            .map(|word| (word.to_string(), word.replace(LEET_CHARS, "")))
+            // An entry made only of leetspeak characters would match everything
+            .filter(|(_, pattern)| !pattern.is_empty())
+            .unzip();
+
+        let substrings = AhoCorasick::new(&patterns).expect("profanity patterns are valid");
+        Self { substrings, words }
+    }
+
+    /// The entry found inside separator-stripped text, if any
+    fn find(&self, normalized: &str) -> Option<&str> {
+        self.substrings
+            .find(normalized)
+            .map(|found| self.words[found.pattern().as_usize()].as_str())
+    }
+}
+
+/// The built-in list on its own, shared by every guild without words of its own
+static MATCHER: Lazy<Matcher> = Lazy::new(|| {
+    let words: Vec<&'static str> = BAD_WORDS
+        .iter()
+        .copied()
+        .filter(|word| word.len() >= MIN_SUBSTRING_WORD_LENGTH)
+        .collect();
+    let patterns: Vec<String> = words.iter().map(|word| word.replace(LEET_CHARS, "")).collect();
+
+    for (word, dominated_by) in redundant_entries(&words, &patterns) {
+        debug!("Profanity list entry \"{}\" is redundant with \"{}\"", word, dominated_by);
+    }
+
+    Matcher::new(words)
+});
+
+/// The filter a guild's room names are checked against
+/// The built-in list plus the guild's own words, compiled into one matcher
+#[derive(Default)]
+pub struct WordFilter {
+    /// The guild's words as loaded, compared to tell whether the matcher is stale
+    extra_words: Vec<String>,
+    /// Built-in and guild words together; `None` uses the shared built-in matcher
+    matcher: Option<Matcher>,
+}
+
+impl WordFilter {
+    /// Build a filter with a guild's own banned words on top of the built-in list
+    pub fn new(extra_words: Vec<String>) -> Self {
+        let matcher = (!extra_words.is_empty()).then(|| {
+            Matcher::new(
+                BAD_WORDS
+                    .iter()
+                    .copied()
+                    .chain(extra_words.iter().map(String::as_str)),
+            )
+        });
+        Self { extra_words, matcher }
+    }
+
+    fn matcher(&self) -> &Matcher {
+        self.matcher.as_ref().unwrap_or(&MATCHER)
+    }
+
+    fn is_whole_word(&self, word: &str) -> bool {
+        BAD_WORDS.contains(word) || self.extra_words.iter().any(|w| w == word)
+    }
+}
+
+/// Compiled filters by guild_id, kept until the guild's word list changes
+#[derive(Default)]
+pub struct GuildFilters {
+    filters: DashMap<u64, Arc<WordFilter>>,
+}
+
+impl GuildFilters {
+    /// The filter for a guild's current banned words
+    /// Only rebuilt when `extra_words` differs from what the cached filter was built from
+    pub fn get(&self, guild_id: u64, extra_words: Vec<String>) -> Arc<WordFilter> {
+        if let Some(filter) = self.filters.get(&guild_id) {
+            if filter.extra_words == extra_words {
+                return Arc::clone(&filter);
+            }
+        }
+
+        debug!(
+            "Building profanity matcher for guild {} ({} extra words)",
+            guild_id,
+            extra_words.len()
+        );
+        let filter = Arc::new(WordFilter::new(extra_words));
+        self.filters.insert(guild_id, Arc::clone(&filter));
+        filter
+    }
+
+    /// Drop a guild's filter, e.g. once the bot has left it
+    pub fn invalidate(&self, guild_id: u64) {
+        self.filters.remove(&guild_id);
+    }
+}
+
+/// Build the matcher up front so the first name check doesn't pay for it
+pub fn init() {
+    let matcher = Lazy::force(&MATCHER);
+    info!(
+        "Profanity filter ready ({} words, {} matched inside text)",
+        BAD_WORDS.len(),
+        matcher.words.len()
+    );
+}
+
+/// Entries that can never be the reason text is caught, paired with a shorter entry that
+/// always matches first: once leetspeak is stripped, the shorter one is inside the longer
+fn redundant_entries<'a>(words: &[&'a str], patterns: &[String]) -> Vec<(&'a str, &'a str)> {
+    let mut redundant = Vec::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        let dominated_by = patterns
+            .iter()
+            .enumerate()
+            .find(|&(j, other)| {
+                j != i && pattern.contains(other.as_str()) && (other != pattern || j < i)
+            })
+            .map(|(j, _)| words[j]);
+        if let Some(dominated_by) = dominated_by {
+            redundant.push((words[i], dominated_by));
+        }
+    }
+    redundant
+}
+
 /// Check if text contains profanity
 /// Returns the first bad word found, if any
 pub fn contains_profanity(text: &str) -> Option<String> {
-    let text_lower = text.to_lowercase();
+    find_profanity(text, &WordFilter::default())
+}
+
+/// Like `contains_profanity`, also checking a guild's own banned words
+/// The guild's words are matched the same way as the built-in list, which is always checked
+pub fn find_profanity(text: &str, filter: &WordFilter) -> Option<String> {
+    let text_lower = fold_for_matching(text);
+
+    if let Some(word) = match_bad_words(&text_lower, filter) {
+        return Some(word);
+    }
+
+    // Stretched words ("fuuuck", "shiiit"): collapse runs of 3+ repeated characters
+    // to one and to two, since the word may need either ("fuck" vs "ass")
+    // Runs of two are left alone, so "committee" or "balloon" are unaffected
+    let stripped = text_lower.replace(EVASION_CHARS, "");
+    for source in [&text_lower, &stripped] {
+        for keep in [1, 2] {
+            let collapsed = collapse_runs(source, keep);
+            if collapsed != *source {
+                if let Some(word) = match_bad_words(&collapsed, filter) {
+                    return Some(word);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Lowercase text and fold Unicode lookalikes to the ASCII letters they imitate
+/// NFKD turns fullwidth and mathematical letters ("ｆｕｃｋ", "𝔣𝔲𝔠𝔨") into plain ones and splits
+/// accents off so they can be dropped, along with zero-width characters. Cyrillic and Greek
+/// letters that look Latin are mapped by hand.
+fn fold_for_matching(text: &str) -> String {
+    let decomposed: String = text
+        .nfkd()
+        .filter(|&c| !is_combining_mark(c) && !is_invisible(c))
+        .collect();
+
+    decomposed
+        .to_lowercase()
+        .chars()
+        .map(|c| latin_lookalike(c).unwrap_or(c))
+        .collect()
+}
 
+/// Characters that render as nothing and can be slipped between letters
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{034F}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    )
+}
+
+/// The Latin letter a lowercase Cyrillic or Greek letter is indistinguishable from
+fn latin_lookalike(c: char) -> Option<char> {
+    let latin = match c {
+        // Cyrillic
+        'а' => 'a',
+        'ԁ' => 'd',
+        'е' => 'e',
+        'һ' => 'h',
+        'і' => 'i',
+        'ј' => 'j',
+        'к' => 'k',
+        'о' => 'o',
+        'р' => 'p',
+        'ԛ' => 'q',
+        'с' => 'c',
+        'ѕ' => 's',
+        'у' => 'y',
+        'ԝ' => 'w',
+        'х' => 'x',
+        // Greek
+        'α' => 'a',
+        'ι' => 'i',
+        'κ' => 'k',
+        'ν' => 'v',
+        'ο' => 'o',
+        'ρ' => 'p',
+        'υ' => 'u',
+        'ϲ' => 'c',
+        'χ' => 'x',
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// Match lowercased text against the word list, including separator-stripped evasions
+fn match_bad_words(text_lower: &str, filter: &WordFilter) -> Option<String> {
     // Remove common separators that might be used to evade filter
-    let normalized = text_lower
-        .replace(['_', '-', '.', ' ', '0', '1', '3', '4', '5', '@', '!', '$', '#'], "");
+    let normalized = text_lower.replace(EVASION_CHARS, "");
 
     // Check each word in the original text
     for word in text_lower.split_whitespace() {
         let clean_word = word.trim_matches(|c: char| !c.is_alphanumeric());
-        if BAD_WORDS.contains(clean_word) {
+        if filter.is_whole_word(clean_word) {
             return Some(clean_word.to_string());
         }
     }
 
-    // Check the normalized (no separators) version for evasion attempts
-    for bad_word in BAD_WORDS.iter() {
-        let word: &str = *bad_word;
-        // Skip short words to avoid false positives
-        if word.len() < 4 {
-            continue;
-        }
-        let normalized_bad = word
-            .replace(['0', '1', '3', '4', '5', '@', '!', '$', '#'], "");
-        if normalized.contains(&normalized_bad) {
-            return Some(word.to_string());
-        }
-    }
+    // Check the normalized (no separators) version for evasion attempts and compound words
+    // Anything inside the plain text is also inside the normalized text, so one pass covers both
+    filter.matcher().find(&normalized).map(str::to_string)
+}
 
-    // Check if any bad word is contained within the text (for compound words)
-    for bad_word in BAD_WORDS.iter() {
-        let word: &str = *bad_word;
-        if word.len() >= 4 && text_lower.contains(word) {
-            return Some(word.to_string());
-        }
+/// Shorten every run of 3 or more identical characters to `keep` characters
+fn collapse_runs(text: &str, keep: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let run = chars[i..].iter().take_while(|&&c| c == chars[i]).count();
+        let len = if run >= 3 { keep } else { run };
+        out.extend(std::iter::repeat_n(chars[i], len));
+        i += run;
     }
 
-    None
+    out
+}
+
+/// Check a word a guild wants to ban, returning it in the form it's stored and matched in
+pub fn normalize_banned_word(word: &str) -> Result<String, String> {
+    let word = fold_for_matching(word.trim());
+    if word.chars().any(char::is_whitespace) {
+        return Err("Banned words must be a single word.".to_string());
+    }
+    let length = word.chars().count();
+    if !(MIN_BANNED_WORD_LENGTH..=MAX_BANNED_WORD_LENGTH).contains(&length) {
+        return Err(format!(
+            "Banned words must be between {} and {} characters.",
+            MIN_BANNED_WORD_LENGTH, MAX_BANNED_WORD_LENGTH
+        ));
+    }
+    Ok(word)
 }
 
 /// Check if channel name is appropriate
+/// `filter` holds the guild's own banned words, on top of the built-in list
 /// Returns Ok(()) if clean, Err with reason if not
-pub fn validate_channel_name(name: &str) -> Result<(), String> {
-    if contains_profanity(name).is_some() {
+pub fn validate_channel_name(
+    name: &str,
+    max_length: usize,
+    filter: &WordFilter,
+) -> Result<(), String> {
+    if find_profanity(name, filter).is_some() {
         return Err(
             "Channel name contains inappropriate language. Please choose a different name.".to_string()
         );
     }
 
     // Check minimum length
-    if name.trim().len() < 2 {
-        return Err("Channel name must be at least 2 characters.".to_string());
+    if name.trim().chars().count() < MIN_CHANNEL_NAME_LENGTH {
+        return Err(format!(
+            "Channel name must be at least {} characters.",
+            MIN_CHANNEL_NAME_LENGTH
+        ));
     }
 
-    // Check maximum length (Discord limit)
-    if name.len() > 100 {
-        return Err("Channel name must be 100 characters or less.".to_string());
+    // Check maximum length (the guild's limit, never above Discord's)
+    let max_length = max_length.min(MAX_CHANNEL_NAME_LENGTH);
+    if name.chars().count() > max_length {
+        return Err(format!(
+            "Channel name must be {} characters or less in this server.",
+            max_length
+        ));
     }
 
     Ok(())
@@ -200,9 +478,9 @@ mod tests {
 
     #[test]
     fn test_clean_names() {
-        assert!(validate_channel_name("Gaming Lounge").is_ok());
-        assert!(validate_channel_name("Chill Zone").is_ok());
-        assert!(validate_channel_name("Music & Chat").is_ok());
+        assert!(validate_channel_name("Gaming Lounge", MAX_CHANNEL_NAME_LENGTH, &WordFilter::default()).is_ok());
+        assert!(validate_channel_name("Chill Zone", MAX_CHANNEL_NAME_LENGTH, &WordFilter::default()).is_ok());
+        assert!(validate_channel_name("Music & Chat", MAX_CHANNEL_NAME_LENGTH, &WordFilter::default()).is_ok());
     }
 
     #[test]
@@ -211,4 +489,170 @@ mod tests {
         assert!(contains_profanity("Gaming fuck Zone").is_some());
         assert!(contains_profanity("f_u_c_k").is_some());
     }
+
+    #[test]
+    fn test_guild_name_limit() {
+        assert!(validate_channel_name("Gaming Lounge", 10, &WordFilter::default()).is_err());
+        assert!(validate_channel_name("Gaming", 10, &WordFilter::default()).is_ok());
+        // Limits above Discord's are capped
+        assert!(validate_channel_name(&"a".repeat(101), 500, &WordFilter::default()).is_err());
+        // Counted in characters, not bytes
+        assert!(validate_channel_name("Café Café", 9, &WordFilter::default()).is_ok());
+    }
+
+    #[test]
+    fn test_stretched_profanity() {
+        assert!(contains_profanity("fuuuuck").is_some());
+        assert!(contains_profanity("shiiiit").is_some());
+        assert!(contains_profanity("Gaming fuuuck Zone").is_some());
+        assert!(contains_profanity("f_uuuu_ck").is_some());
+        assert_eq!(collapse_runs("fuuuuck", 1), "fuck");
+        assert_eq!(collapse_runs("asssss", 2), "ass");
+    }
+
+    #[test]
+    fn test_leetspeak_and_compound_words() {
+        assert!(contains_profanity("b1tch").is_some());
+        assert!(contains_profanity("bi.tch").is_some());
+        assert!(contains_profanity("SuperShittyRoom").is_some());
+        // Short entries only match whole words
+        assert!(contains_profanity("ass").is_some());
+        assert!(contains_profanity("Class Room").is_none());
+    }
+
+    #[test]
+    fn test_unicode_evasion() {
+        // Fullwidth
+        assert!(contains_profanity("ｆｕｃｋ").is_some());
+        assert!(contains_profanity("ＦＵＣＫ room").is_some());
+        // Mathematical alphanumeric symbols
+        assert!(contains_profanity("𝔣𝔲𝔠𝔨").is_some());
+        assert!(contains_profanity("𝐬𝐡𝐢𝐭").is_some());
+        assert!(contains_profanity("𝕓𝕚𝕥𝕔𝕙").is_some());
+        // Zero-width spaces and joiners between letters
+        assert!(contains_profanity("f\u{200b}uck").is_some());
+        assert!(contains_profanity("f\u{200d}u\u{200d}c\u{200d}k").is_some());
+        assert!(contains_profanity("sh\u{feff}it").is_some());
+        // Combining marks and lookalike letters
+        assert!(contains_profanity("fu\u{0301}ck").is_some());
+        assert!(contains_profanity("fuсk").is_some()); // Cyrillic es
+        assert!(contains_profanity("ѕhіt").is_some()); // Cyrillic dze and i
+        // Ordinary non-ASCII names are still fine
+        assert!(contains_profanity("Café Lounge").is_none());
+        assert!(contains_profanity("Привет").is_none());
+        assert!(contains_profanity("日本語ルーム").is_none());
+    }
+
+    #[test]
+    fn test_guild_words() {
+        let extra = WordFilter::new(vec!["drama".to_string(), "xy".to_string()]);
+        assert!(validate_channel_name("No Drama Zone", MAX_CHANNEL_NAME_LENGTH, &extra).is_err());
+        assert!(validate_channel_name("d.r.a.m.a", MAX_CHANNEL_NAME_LENGTH, &extra).is_err());
+        assert!(validate_channel_name("xy room", MAX_CHANNEL_NAME_LENGTH, &extra).is_err());
+        // Short words only match whole words, like the built-in list
+        assert!(validate_channel_name("xyz room", MAX_CHANNEL_NAME_LENGTH, &extra).is_ok());
+        assert!(validate_channel_name("No Drama Zone", MAX_CHANNEL_NAME_LENGTH, &WordFilter::default()).is_ok());
+
+        assert_eq!(normalize_banned_word("  Drama "), Ok("drama".to_string()));
+        assert!(normalize_banned_word("two words").is_err());
+        assert!(normalize_banned_word("x").is_err());
+    }
+
+    #[test]
+    fn test_guild_filter_rebuilt_only_on_change() {
+        let filters = GuildFilters::default();
+        let words = vec!["drama".to_string()];
+        let first = filters.get(1, words.clone());
+        assert!(Arc::ptr_eq(&first, &filters.get(1, words.clone())));
+
+        filters.invalidate(1);
+        assert!(!Arc::ptr_eq(&first, &filters.get(1, words)));
+
+        let changed = filters.get(1, vec!["drama".to_string(), "gossip".to_string()]);
+        assert!(!Arc::ptr_eq(&first, &changed));
+        assert!(find_profanity("Gossip Corner", &changed).is_some());
+        // Guild words go through the same automaton as the built-in list
+        assert!(find_profanity("g.o.s.s.i.p", &changed).is_some());
+        assert!(find_profanity("Gossip Corner", &first).is_none());
+    }
+
+    #[test]
+    fn test_leet_only_guild_word_matches_nothing_extra() {
+        let filter = WordFilter::new(vec!["1234".to_string()]);
+        assert!(find_profanity("Gaming Lounge", &filter).is_none());
+        assert!(find_profanity("1234", &filter).is_some());
+    }
+
+    /// Timing of the name check against a plain per-entry scan of the same list
+    /// Run with: cargo test --release bench_find_profanity -- --ignored --nocapture
+    #[test]
+    #[ignore = "benchmark; run in release mode"]
+    fn bench_find_profanity() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        const ROUNDS: u32 = 2_000;
+        let names = [
+            "Gaming Lounge",
+            "Chill Zone",
+            "Music & Chat",
+            "Late Night Debate",
+            "SuperShittyRoom",
+            "f_u_c_k",
+            "Committee Meeting",
+            "Café Lounge",
+        ];
+        let extra: Vec<String> = (0..MAX_GUILD_BANNED_WORDS).map(|i| format!("banned{:03}", i)).collect();
+        let guild = WordFilter::new(extra.clone());
+        let all_words: Vec<String> = BAD_WORDS
+            .iter()
+            .map(|w| w.to_string())
+            .chain(extra)
+            .collect();
+
+        // One `contains` per entry, per text variant: the cost the automaton replaces
+        let linear_scan = |name: &str| {
+            let lower = fold_for_matching(name);
+            let stripped = lower.replace(EVASION_CHARS, "");
+            all_words.iter().find(|word| {
+                word.len() >= MIN_SUBSTRING_WORD_LENGTH
+                    && (lower.contains(word.as_str())
+                        || stripped.contains(&word.replace(LEET_CHARS, "")))
+            })
+            .is_some()
+        };
+
+        let time = |label: &str, check: &dyn Fn(&str) -> bool| {
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                for name in names {
+                    black_box(check(black_box(name)));
+                }
+            }
+            let per_name = start.elapsed() / (ROUNDS * names.len() as u32);
+            println!("{:<24} {:?} per name", label, per_name);
+        };
+
+        time("linear scan", &|name| linear_scan(name));
+        time("built-in automaton", &|name| contains_profanity(name).is_some());
+        time("guild automaton", &|name| find_profanity(name, &guild).is_some());
+    }
+
+    #[test]
+    fn test_redundant_entries() {
+        let words = ["fuck", "fucking", "b1tch", "b!tch", "shit"];
+        let patterns: Vec<String> = words.iter().map(|w| w.replace(LEET_CHARS, "")).collect();
+        assert_eq!(
+            redundant_entries(&words, &patterns),
+            vec![("fucking", "fuck"), ("b!tch", "b1tch")]
+        );
+    }
+
+    #[test]
+    fn test_doubled_letters_are_not_collapsed() {
+        assert_eq!(collapse_runs("committee", 1), "committee");
+        assert!(contains_profanity("Committee Meeting").is_none());
+        assert!(contains_profanity("Balloon Room").is_none());
+        assert!(contains_profanity("Cooool Zone").is_none());
+    }
 }
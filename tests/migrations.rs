@@ -0,0 +1,276 @@
+//! Upgrading a database migrated before migrations were tracked
+//! Needs a Postgres server: TEST_DATABASE_URL=postgres://... cargo test -- --ignored
+
+use std::str::FromStr;
+
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Executor, PgPool};
+
+use jarvis::db::config_cache::GuildConfigCache;
+use jarvis::db::pool::run_migrations;
+use jarvis::db::queries::{guild_config, user_vc_preference};
+
+/// Migrations that existed before the runner recorded what it applied
+const LEGACY_MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/001_initial_schema.sql"),
+    include_str!("../migrations/002_guild_configs.sql"),
+    include_str!("../migrations/003_channels.sql"),
+    include_str!("../migrations/004_mute_history.sql"),
+    include_str!("../migrations/005_ban_history.sql"),
+    include_str!("../migrations/006_spam_tracking.sql"),
+    include_str!("../migrations/007_user_vc_preferences.sql"),
+    include_str!("../migrations/008_rate_limits.sql"),
+    include_str!("../migrations/009_global_mutes.sql"),
+    include_str!("../migrations/010_ban_dedup.sql"),
+    include_str!("../migrations/011_message_templates.sql"),
+    include_str!("../migrations/012_ban_appeals.sql"),
+    include_str!("../migrations/013_mod_log.sql"),
+    include_str!("../migrations/014_mute_deafen.sql"),
+    include_str!("../migrations/015_discussion_channels.sql"),
+    include_str!("../migrations/016_owner_hop_policy.sql"),
+    include_str!("../migrations/017_empty_channel_grace.sql"),
+    include_str!("../migrations/018_room_mod_notices.sql"),
+    include_str!("../migrations/019_raid_lock.sql"),
+    include_str!("../migrations/020_overflow_categories.sql"),
+    include_str!("../migrations/021_command_usage.sql"),
+    include_str!("../migrations/022_max_name_length.sql"),
+    include_str!("../migrations/023_owner_role.sql"),
+    include_str!("../migrations/024_keep_mutes_on_delete.sql"),
+    include_str!("../migrations/025_default_tags.sql"),
+    include_str!("../migrations/026_welcome_dm.sql"),
+];
+
+/// A throwaway database, dropped when the test finishes
+struct TestDatabase {
+    admin: PgPool,
+    name: String,
+    pool: PgPool,
+}
+
+impl TestDatabase {
+    async fn create(url: &str) -> TestDatabase {
+        let admin = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(url)
+            .await
+            .expect("connect to TEST_DATABASE_URL");
+        let name = format!("jarvis_migration_test_{}", uuid::Uuid::new_v4().simple());
+        admin
+            .execute(format!("CREATE DATABASE {}", name).as_str())
+            .await
+            .expect("create test database");
+
+        let options = PgConnectOptions::from_str(url).unwrap().database(&name);
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await
+            .expect("connect to test database");
+
+        TestDatabase { admin, name, pool }
+    }
+
+    async fn drop(self) {
+        self.pool.close().await;
+        let _ = self
+            .admin
+            .execute(format!("DROP DATABASE IF EXISTS {}", self.name).as_str())
+            .await;
+    }
+}
+
+/// Apply the legacy migrations the way the untracked runner did
+async fn seed_legacy_schema(pool: &PgPool) {
+    for migration in LEGACY_MIGRATIONS {
+        sqlx::raw_sql(migration)
+            .execute(pool)
+            .await
+            .unwrap_or_else(|e| panic!("legacy migration failed: {}\n{}", e, migration));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO guild_configs (
+            guild_id, jtc_casual_channel_id, jtc_debate_channel_id,
+            category_casual_id, category_debate_id, mod_log_channel_id, owner_hop_policy
+        )
+        VALUES (1, 10, 20, 11, 21, 30, 'hold'), (2, 40, NULL, 41, NULL, NULL, 'release')
+        "#,
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn jtc_rows(pool: &PgPool) -> Vec<(i64, i64, String)> {
+    sqlx::query_as(
+        "SELECT channel_id, guild_id, channel_type::text FROM jtc_channels ORDER BY channel_id",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL pointing at a Postgres server"]
+async fn legacy_config_survives_upgrade() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+    let db = TestDatabase::create(&url).await;
+    let pool = &db.pool;
+
+    seed_legacy_schema(pool).await;
+    run_migrations(pool).await.expect("upgrade a legacy database");
+
+    let config = guild_config::get(pool, 1).await.unwrap().expect("guild 1 kept");
+    assert_eq!(config.jtc_casual_channel_id, Some(10));
+    assert_eq!(config.jtc_debate_channel_id, Some(20));
+    assert_eq!(config.category_id(true), Some(11));
+    assert_eq!(config.category_id(false), Some(21));
+    assert_eq!(config.mod_log_channel_id, Some(30));
+    assert_eq!(config.owner_hop_policy, "hold");
+    assert!(!config.send_welcome_dm);
+
+    assert_eq!(
+        jtc_rows(pool).await,
+        vec![
+            (10, 1, "casual".to_string()),
+            (20, 1, "debate".to_string()),
+            (40, 2, "casual".to_string()),
+        ]
+    );
+
+    // A second start must not run anything again, including the backfill
+    sqlx::query("DELETE FROM jtc_channels WHERE guild_id = 2")
+        .execute(pool)
+        .await
+        .unwrap();
+    run_migrations(pool).await.expect("rerun migrations");
+    assert_eq!(jtc_rows(pool).await.len(), 2);
+
+    let (recorded, distinct): (i64, i64) =
+        sqlx::query_as("SELECT COUNT(*), COUNT(DISTINCT version) FROM _sqlx_migrations")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    let migration_files = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+        .unwrap()
+        .filter(|entry| entry.as_ref().unwrap().path().extension() == Some("sql".as_ref()))
+        .count();
+    assert_eq!(recorded, migration_files as i64);
+    assert_eq!(recorded, distinct);
+
+    // Setters keep the new table in step with the legacy columns
+    guild_config::set_jtc_channel(pool, 1, true, 12).await.unwrap();
+    assert_eq!(
+        jtc_rows(pool).await,
+        vec![(12, 1, "casual".to_string()), (20, 1, "debate".to_string())]
+    );
+
+    db.drop().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL pointing at a Postgres server"]
+async fn tracked_migrations_are_not_rerun() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+    let db = TestDatabase::create(&url).await;
+    let pool = &db.pool;
+
+    // A database the schema_migrations runner had taken up to 027
+    seed_legacy_schema(pool).await;
+    sqlx::raw_sql(include_str!("../migrations/027_jtc_channels.sql"))
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::raw_sql(
+        r#"
+        CREATE TABLE schema_migrations (
+            name TEXT PRIMARY KEY,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );
+        INSERT INTO schema_migrations (name)
+        SELECT lpad(n::text, 3, '0') || '_legacy' FROM generate_series(1, 27) AS n;
+        DELETE FROM jtc_channels WHERE guild_id = 2;
+        "#,
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    run_migrations(pool).await.expect("adopt tracked migrations");
+
+    // 027's backfill would bring guild 2's row back if it ran again
+    assert_eq!(jtc_rows(pool).await.len(), 2);
+    // Later migrations still apply
+    let config = guild_config::get(pool, 1).await.unwrap().expect("guild 1 kept");
+    assert_eq!(config.recreate_cooldown_seconds, 0);
+
+    db.drop().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL pointing at a Postgres server"]
+async fn fresh_database_migrates_once() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+    let db = TestDatabase::create(&url).await;
+
+    run_migrations(&db.pool).await.expect("migrate a fresh database");
+    run_migrations(&db.pool).await.expect("rerun migrations");
+
+    let config = guild_config::get_or_create(&db.pool, 5).await.unwrap();
+    assert!(config.default_casual_tags.is_empty());
+
+    db.drop().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL pointing at a Postgres server"]
+async fn renaming_keeps_saved_tags() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+    let db = TestDatabase::create(&url).await;
+    run_migrations(&db.pool).await.expect("migrate a fresh database");
+
+    let tags = vec!["gaming".to_string(), "chill".to_string()];
+    user_vc_preference::upsert(&db.pool, 1, 2, "casual", Some("Old Name"), &tags)
+        .await
+        .unwrap();
+
+    let pref = user_vc_preference::save_name(&db.pool, 1, 2, "casual", "New Name")
+        .await
+        .unwrap();
+    assert_eq!(pref.preferred_name.as_deref(), Some("New Name"));
+    assert_eq!(pref.preferred_tags, tags);
+
+    // A first-time name starts with no tags
+    let fresh = user_vc_preference::save_name(&db.pool, 1, 3, "casual", "Mine").await.unwrap();
+    assert!(fresh.preferred_tags.is_empty());
+
+    db.drop().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL pointing at a Postgres server"]
+async fn config_cache_serves_changes_after_invalidation() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+    let db = TestDatabase::create(&url).await;
+    let pool = &db.pool;
+    run_migrations(pool).await.expect("migrate a fresh database");
+
+    let cache = GuildConfigCache::default();
+    assert!(cache.get(pool, 7).await.unwrap().is_none());
+
+    guild_config::set_jtc_channel(pool, 7, true, 70).await.unwrap();
+    let config = cache.get(pool, 7).await.unwrap().expect("new config is read");
+    assert_eq!(config.jtc_type(70), Some(true));
+
+    guild_config::set_jtc_channel(pool, 7, true, 71).await.unwrap();
+    let stale = cache.get(pool, 7).await.unwrap().unwrap();
+    assert_eq!(stale.jtc_casual_channel_id, Some(70));
+
+    cache.invalidate(7);
+    let fresh = cache.get(pool, 7).await.unwrap().unwrap();
+    assert_eq!(fresh.jtc_type(71), Some(true));
+    assert_eq!(fresh.jtc_type(70), None);
+
+    db.drop().await;
+}